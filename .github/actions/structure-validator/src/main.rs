@@ -25,6 +25,10 @@ struct Args {
     /// Fix violations automatically
     #[arg(long)]
     fix: bool,
+
+    /// Output as SARIF or JUnit XML instead (sarif, junit)
+    #[arg(long, value_parser = gc_report::FORMATS)]
+    format: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -74,11 +78,13 @@ const REQUIRED_FILES: &[(&str, &str)] = &[
     (".github/copilot-instructions.md", "Copilot rules"),
 ];
 
-fn main() {
+fn main() -> Result<(), gc_report::RenderError> {
     let args = Args::parse();
     let result = validate_structure(&args.path);
 
-    if args.json {
+    if let Some(format) = &args.format {
+        println!("{}", to_report(&result).render(format)?);
+    } else if args.json {
         println!("{}", serde_json::to_string_pretty(&result).unwrap());
     } else {
         print_human_readable(&result);
@@ -87,6 +93,53 @@ fn main() {
     if !result.valid {
         std::process::exit(1);
     }
+    Ok(())
+}
+
+/// Workflow file that ran the validator, where findings without an existing file are reported
+///
+/// Read from `GITHUB_WORKFLOW_REF` (`owner/repo/.github/workflows/x.yml@ref`) in Actions.
+fn workflow_location() -> String {
+    std::env::var("GITHUB_WORKFLOW_REF")
+        .ok()
+        .and_then(|r| {
+            let path = r.split('@').next()?;
+            let start = path.find(".github/")?;
+            Some(path[start..].to_string())
+        })
+        .unwrap_or_else(|| ".github/workflows/structure-validator.yml".to_string())
+}
+
+/// Map violations to the shared Git-Core finding model
+///
+/// Code scanning needs a location: missing files and directories are reported
+/// on the workflow file that ran the validator.
+fn to_report(result: &ValidationResult) -> gc_report::Report {
+    let workflow = workflow_location();
+    let findings = result.violations.iter().map(|v| {
+        let mut finding = gc_report::Finding::new(
+            &v.violation_type,
+            gc_report::Severity::parse(&v.severity),
+            v.message.clone(),
+        );
+        finding = match &v.file {
+            Some(file) if !v.violation_type.starts_with("MISSING_") => finding.at(file, None),
+            _ => finding.at(&workflow, None),
+        };
+        if let Some(suggestion) = &v.suggestion {
+            finding = finding.with_fix(suggestion.clone());
+        }
+        finding
+    });
+
+    gc_report::Report::new("structure-validator", env!("CARGO_PKG_VERSION"))
+        .with_rule("FORBIDDEN_FILE", "Planning files must not live in the repository root")
+        .with_rule("MISSING_DIRECTORY", "Required Git-Core directory is missing")
+        .with_rule("MISSING_FILE", "Required Git-Core file is missing")
+        .with_rule("MISPLACED_FILE", "File belongs in another directory")
+        .with_rule("MISPLACED_AGENT_DOC", "Agent documentation outside docs/agent-docs")
+        .with_rule("UNKNOWN_ROOT_MD", "Unexpected Markdown file in the repository root")
+        .with_findings(findings)
 }
 
 fn validate_structure(base_path: &str) -> ValidationResult {
@@ -63,6 +63,13 @@ check_hooks() {
 
 install_hooks() {
     echo -e "${CYAN}🔧 Installing Git-Core Protocol hooks...${NC}"
+
+    # Prefer the native hooks (same rules and config as the CI check)
+    if command -v atomicity-checker >/dev/null 2>&1; then
+        (cd "$REPO_ROOT" && atomicity-checker hook install)
+        return
+    fi
+    echo -e "${YELLOW}⚠️  atomicity-checker not found, installing the legacy Bash hook${NC}"
     
     # Check if .git directory exists
     if [ ! -d "$REPO_ROOT/.git" ]; then
@@ -126,6 +133,11 @@ EOF
 
 uninstall_hooks() {
     echo -e "${CYAN}🔧 Uninstalling Git-Core Protocol hooks...${NC}"
+
+    if command -v atomicity-checker >/dev/null 2>&1; then
+        (cd "$REPO_ROOT" && atomicity-checker hook uninstall)
+        return
+    fi
     
     if [ -f "$GIT_HOOKS_DIR/pre-commit" ]; then
         if grep -q "git-core-protocol" "$GIT_HOOKS_DIR/pre-commit" 2>/dev/null; then
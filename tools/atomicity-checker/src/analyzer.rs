@@ -1,14 +1,23 @@
 //! Commit atomicity analyzer.
 //!
 //! Categorizes file changes and determines if a commit is atomic.
+//! Concerns in an `allowed_combinations` entry count once, each counted
+//! concern adds its weight, and the total is compared against `max_concerns`
+//! (or the limit of the matching path override). With workspace scoping,
+//! this happens per package and the package totals add up.
+//! When `commit_message` is enabled, the message is validated against the
+//! concerns found (see [`crate::conventional`]).
 
 use anyhow::Result;
 use rayon::prelude::*;
+use serde::Serialize;
 use std::collections::HashSet;
 use tracing::{debug, info};
 
-use crate::config::{Config, Concern};
+use crate::config::{Config, Concern, Mode};
+use crate::conventional::{self, MessageIssue};
 use crate::git::{self, CommitInfo};
+use crate::workspace::{Workspace, WorkspaceLoader};
 
 /// Result of analyzing a single commit
 #[derive(Debug, Clone)]
@@ -17,12 +26,83 @@ pub struct CommitAnalysis {
     pub commit: CommitInfo,
     /// Detected concerns in this commit
     pub concerns: HashSet<Concern>,
+    /// Counted concern groups (per package with workspace scoping)
+    pub groups: Vec<ConcernGroup>,
     /// Whether the commit is atomic
     pub is_atomic: bool,
     /// Whether the commit was skipped (e.g., bot author)
     pub skipped: bool,
     /// Reason for skipping (if skipped)
     pub skip_reason: Option<String>,
+    /// Commit message problems (empty when validation is disabled)
+    pub message_issues: Vec<MessageIssue>,
+    /// Weighted concern count
+    pub score: f64,
+    /// Mode that applies to this commit
+    pub mode: Mode,
+}
+
+impl CommitAnalysis {
+    /// Whether this commit fails the check
+    pub fn fails(&self) -> bool {
+        !self.skipped && self.mode == Mode::Error && (!self.is_atomic || !self.message_issues.is_empty())
+    }
+}
+
+/// Which rule classified a file
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRule {
+    pub path: String,
+    /// `None` when the file is ignored
+    pub concern: Option<Concern>,
+    pub rule: String,
+    /// Workspace package containing the file
+    pub package: Option<String>,
+}
+
+/// Concerns counted together: one, or an allowed combination, within a package
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConcernGroup {
+    pub package: Option<String>,
+    pub concerns: Vec<Concern>,
+}
+
+impl std::fmt::Display for ConcernGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let concerns: Vec<String> = self.concerns.iter().map(|c| c.to_string()).collect();
+        match &self.package {
+            Some(package) => write!(f, "{}:{}", package, concerns.join("+")),
+            None => write!(f, "{}", concerns.join("+")),
+        }
+    }
+}
+
+/// How the config applies to one commit
+#[derive(Debug, Clone, Serialize)]
+pub struct Verdict {
+    pub files: Vec<FileRule>,
+    /// Author rule that applied (`author_modes[i]` or `bot_patterns`)
+    pub author_rule: Option<String>,
+    /// Path override that applied (`path_overrides[i]`)
+    pub path_override: Option<String>,
+    pub skipped: bool,
+    /// Counted concerns, allowed combinations grouped together
+    pub groups: Vec<ConcernGroup>,
+    pub score: f64,
+    pub max_concerns: usize,
+    pub mode: Mode,
+}
+
+impl Verdict {
+    /// Concerns of the counted files
+    pub fn concerns(&self) -> HashSet<Concern> {
+        self.files.iter().filter_map(|f| f.concern.clone()).collect()
+    }
+
+    /// Whether the weighted concern count stays within the limit
+    pub fn is_atomic(&self) -> bool {
+        self.skipped || self.score <= self.max_concerns as f64
+    }
 }
 
 /// Result of checking atomicity across multiple commits
@@ -36,8 +116,12 @@ pub struct AtomicityResult {
     pub non_atomic_commits: usize,
     /// Number of skipped commits (bots, etc.)
     pub skipped_commits: usize,
+    /// Number of commits with message problems
+    pub invalid_messages: usize,
     /// Whether any issues were found
     pub has_issues: bool,
+    /// Whether an issue was found in a commit whose mode is error
+    pub should_fail: bool,
     /// Individual commit analyses
     pub analyses: Vec<CommitAnalysis>,
 }
@@ -60,17 +144,27 @@ pub async fn check_atomicity(
             atomic_commits: 0,
             non_atomic_commits: 0,
             skipped_commits: 0,
+            invalid_messages: 0,
             has_issues: false,
+            should_fail: false,
             analyses: Vec::new(),
         });
     }
 
     info!("📊 Found {} commits to analyze", commits.len());
 
+    // Packages as of each commit, so added or moved packages scope the commits that follow
+    let mut loader = WorkspaceLoader::new(repo_path, &config.workspace).await?;
+    let mut scoped = Vec::with_capacity(commits.len());
+    for commit in commits {
+        let workspace = loader.at(Some(&commit.sha)).await?;
+        scoped.push((commit, workspace));
+    }
+
     // Analyze commits in parallel using rayon
-    let analyses: Vec<CommitAnalysis> = commits
+    let analyses: Vec<CommitAnalysis> = scoped
         .into_par_iter()
-        .map(|commit| analyze_commit(commit, config))
+        .map(|(commit, workspace)| analyze_commit(commit, config, &workspace))
         .collect();
 
     // Calculate statistics
@@ -78,7 +172,9 @@ pub async fn check_atomicity(
     let skipped_commits = analyses.iter().filter(|a| a.skipped).count();
     let atomic_commits = analyses.iter().filter(|a| !a.skipped && a.is_atomic).count();
     let non_atomic_commits = analyses.iter().filter(|a| !a.skipped && !a.is_atomic).count();
-    let has_issues = non_atomic_commits > 0;
+    let invalid_messages = analyses.iter().filter(|a| !a.message_issues.is_empty()).count();
+    let has_issues = non_atomic_commits > 0 || invalid_messages > 0;
+    let should_fail = analyses.iter().any(CommitAnalysis::fails);
 
     info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     info!("📊 Summary");
@@ -87,46 +183,48 @@ pub async fn check_atomicity(
     info!("   Atomic: {}", atomic_commits);
     info!("   Non-atomic: {}", non_atomic_commits);
     info!("   Skipped (bots): {}", skipped_commits);
+    if config.commit_message.enabled {
+        info!("   Invalid messages: {}", invalid_messages);
+    }
 
     Ok(AtomicityResult {
         total_commits,
         atomic_commits,
         non_atomic_commits,
         skipped_commits,
+        invalid_messages,
         has_issues,
+        should_fail,
         analyses,
     })
 }
 
 /// Analyze a single commit for atomicity
-fn analyze_commit(commit: CommitInfo, config: &Config) -> CommitAnalysis {
-    // Check if author is a bot
-    if config.is_bot_author(&commit.author) {
-        debug!("○ {}: Skipped (bot: {})", commit.short_sha, commit.author);
+fn analyze_commit(commit: CommitInfo, config: &Config, workspace: &Workspace) -> CommitAnalysis {
+    let verdict = evaluate(&commit.author, &commit.files, config, workspace);
+
+    if verdict.skipped {
+        debug!("○ {}: Skipped ({})", commit.short_sha, verdict.author_rule.as_deref().unwrap_or_default());
         return CommitAnalysis {
             commit,
             concerns: HashSet::new(),
+            groups: Vec::new(),
             is_atomic: true,
             skipped: true,
-            skip_reason: Some("Bot author".to_string()),
+            skip_reason: Some(match verdict.author_rule.as_deref() {
+                Some("bot_patterns") => "Bot author".to_string(),
+                Some(rule) => format!("Author matches {}", rule),
+                None => "Skipped".to_string(),
+            }),
+            message_issues: Vec::new(),
+            score: 0.0,
+            mode: verdict.mode,
         };
     }
 
-    // Categorize files and collect concerns
-    let mut concerns = HashSet::new();
-
-    for file in &commit.files {
-        // Skip ignored files
-        if config.should_ignore_file(file) {
-            continue;
-        }
-
-        let concern = categorize_file(file, config);
-        concerns.insert(concern);
-    }
-
-    let is_atomic = concerns.len() <= config.max_concerns;
-    let concern_list: Vec<String> = concerns.iter().map(|c| c.to_string()).collect();
+    let concerns = verdict.concerns();
+    let is_atomic = verdict.is_atomic();
+    let concern_list: Vec<String> = verdict.groups.iter().map(|g| g.to_string()).collect();
 
     if is_atomic {
         debug!(
@@ -142,32 +240,147 @@ fn analyze_commit(commit: CommitInfo, config: &Config) -> CommitAnalysis {
         );
         info!(
             "   └─ Mixes {} concerns: {}",
-            concerns.len(),
+            concern_list.len(),
             concern_list.join(", ")
         );
     }
 
+    let message_issues = conventional::validate(&commit.message, &commit.body, &concerns, &config.commit_message);
+    for issue in &message_issues {
+        info!("✏️ {}: {} [{}]", commit.short_sha, issue.message, issue.rule);
+    }
+
     CommitAnalysis {
         commit,
         concerns,
+        groups: verdict.groups,
         is_atomic,
         skipped: false,
         skip_reason: None,
+        message_issues,
+        score: verdict.score,
+        mode: verdict.mode,
+    }
+}
+
+/// Apply the config to a commit's author and files
+pub fn evaluate(author: &str, files: &[String], config: &Config, workspace: &Workspace) -> Verdict {
+    let files: Vec<FileRule> = files.iter()
+        .map(|f| FileRule {
+            package: workspace.package_of(f).map(|p| p.name.clone()),
+            ..classify_file(f, config)
+        })
+        .collect();
+    let counted: Vec<&str> = files.iter()
+        .filter(|f| f.concern.is_some())
+        .map(|f| f.path.as_str())
+        .collect();
+
+    let mut verdict = Verdict {
+        author_rule: None,
+        path_override: None,
+        skipped: false,
+        groups: Vec::new(),
+        score: 0.0,
+        max_concerns: config.max_concerns,
+        mode: config.mode.clone(),
+        files: Vec::new(),
+    };
+
+    if let Some((index, rule)) = config.path_override(&counted) {
+        verdict.path_override = Some(format!("path_overrides[{}] `{}`", index, rule.pattern));
+        verdict.max_concerns = rule.max_concerns.unwrap_or(config.max_concerns);
+        verdict.mode = rule.mode.clone().unwrap_or(verdict.mode);
+    }
+
+    // Author rules take precedence over path overrides and bot detection
+    if let Some((index, rule)) = config.author_mode(author) {
+        verdict.author_rule = Some(format!("author_modes[{}] `{}`", index, rule.pattern));
+        verdict.skipped = rule.skip;
+        verdict.mode = rule.mode.clone().unwrap_or(verdict.mode);
+    } else if config.is_bot_author(author) {
+        verdict.author_rule = Some("bot_patterns".to_string());
+        verdict.skipped = true;
+    }
+
+    // Group per package; without scoping every file is in package `None`
+    let mut scopes: Vec<(Option<String>, Vec<Concern>)> = Vec::new();
+    for file in &files {
+        let Some(concern) = &file.concern else { continue };
+        let index = match scopes.iter().position(|(package, _)| package == &file.package) {
+            Some(index) => index,
+            None => {
+                scopes.push((file.package.clone(), Vec::new()));
+                scopes.len() - 1
+            }
+        };
+        if !scopes[index].1.contains(concern) {
+            scopes[index].1.push(concern.clone());
+        }
+    }
+    scopes.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (package, mut concerns) in scopes {
+        concerns.sort_by_key(|c| c.to_string());
+        verdict.groups.extend(
+            group_concerns(concerns, &config.allowed_combinations)
+                .into_iter()
+                .map(|concerns| ConcernGroup { package: package.clone(), concerns }),
+        );
+    }
+    verdict.score = verdict.groups.iter()
+        .map(|group| group.concerns.iter().map(|c| config.weight(c)).fold(0.0, f64::max))
+        .sum();
+    verdict.files = files;
+    verdict
+}
+
+/// Merge concerns of each allowed combination into one group
+pub(crate) fn group_concerns(mut remaining: Vec<Concern>, combinations: &[Vec<Concern>]) -> Vec<Vec<Concern>> {
+    let mut groups = Vec::new();
+    for combination in combinations {
+        let covered: Vec<Concern> = remaining.iter()
+            .filter(|c| combination.contains(c))
+            .cloned()
+            .collect();
+        if covered.len() > 1 {
+            remaining.retain(|c| !covered.contains(c));
+            groups.push(covered);
+        }
     }
+    groups.extend(remaining.into_iter().map(|c| vec![c]));
+    groups
 }
 
 /// Categorize a file path into a concern type
-fn categorize_file(path: &str, config: &Config) -> Concern {
+pub(crate) fn categorize_file(path: &str, config: &Config) -> Concern {
+    categorize_with_rule(path, config).0
+}
+
+/// Classify a file, naming the rule that decided
+pub fn classify_file(path: &str, config: &Config) -> FileRule {
+    if let Some(pattern) = config.ignore_pattern(path) {
+        return FileRule {
+            path: path.to_string(),
+            concern: None,
+            rule: format!("ignore_files `{}`", pattern),
+            package: None,
+        };
+    }
+    let (concern, rule) = categorize_with_rule(path, config);
+    FileRule { path: path.to_string(), concern: Some(concern), rule, package: None }
+}
+
+fn categorize_with_rule(path: &str, config: &Config) -> (Concern, String) {
     // Check custom rules first
-    for rule in &config.custom_rules {
-        if let Ok(re) = regex::Regex::new(&rule.pattern) {
-            if re.is_match(path) {
-                return rule.concern.clone();
-            }
+    for (index, rule) in config.custom_rules.iter().enumerate() {
+        if rule.pattern.is_match(path) {
+            return (rule.concern.clone(), format!("custom_rules[{}] `{}`", index, rule.pattern));
         }
     }
 
     // Built-in categorization rules
+    let builtin = |concern: Concern, rule: &str| (concern, format!("built-in: {}", rule));
 
     // Tests
     if path.starts_with("tests/") || path.starts_with("test/")
@@ -176,30 +389,30 @@ fn categorize_file(path: &str, config: &Config) -> Concern {
         || path.contains("_test.")
         || path.starts_with("test_")
     {
-        return Concern::Tests;
+        return builtin(Concern::Tests, "test path or file name");
     }
 
     // Documentation
     if path.starts_with("docs/") || path.ends_with(".md") {
-        return Concern::Docs;
+        return builtin(Concern::Docs, "docs/ or markdown");
     }
 
     // CI/Infrastructure
     if path.starts_with(".github/workflows/") || path.starts_with("scripts/") {
-        return Concern::Infra;
+        return builtin(Concern::Infra, "workflows or scripts/");
     }
 
     // Config files
     if is_config_file(path) {
-        return Concern::Config;
+        return builtin(Concern::Config, "config extension or dotfile");
     }
 
     // Source code
     if path.starts_with("src/") || path.starts_with("lib/") || is_source_file(path) {
-        return Concern::Source;
+        return builtin(Concern::Source, "source path or extension");
     }
 
-    Concern::Other
+    builtin(Concern::Other, "no rule matched")
 }
 
 /// Check if a path is a configuration file
@@ -246,6 +459,18 @@ fn is_source_file(path: &str) -> bool {
     false
 }
 
+/// Explain how the config classifies a single commit (public API for CLI)
+pub async fn explain_commit(
+    repo_path: &str,
+    sha: &str,
+    config: &Config,
+) -> Result<(CommitInfo, Verdict)> {
+    let commit = git::get_commit(repo_path, sha).await?;
+    let workspace = Workspace::load(repo_path, &config.workspace, Some(&commit.sha)).await?;
+    let verdict = evaluate(&commit.author, &commit.files, config, &workspace);
+    Ok((commit, verdict))
+}
+
 /// Analyze a single commit (public API for CLI)
 pub async fn analyze_single_commit(
     repo_path: &str,
@@ -253,7 +478,8 @@ pub async fn analyze_single_commit(
     config: &Config,
 ) -> Result<CommitAnalysis> {
     let commit = git::get_commit(repo_path, sha).await?;
-    Ok(analyze_commit(commit, config))
+    let workspace = Workspace::load(repo_path, &config.workspace, Some(&commit.sha)).await?;
+    Ok(analyze_commit(commit, config, &workspace))
 }
 
 #[cfg(test)]
@@ -302,6 +528,95 @@ mod tests {
         assert_eq!(categorize_file(".gitignore", &config), Concern::Config);
     }
 
+    #[test]
+    fn test_message_checked_against_concerns() {
+        let mut config = test_config();
+        config.commit_message.enabled = true;
+        let commit = CommitInfo {
+            sha: "abc12345".to_string(),
+            short_sha: "abc12345".to_string(),
+            author: "developer".to_string(),
+            message: "docs: explain usage".to_string(),
+            body: String::new(),
+            files: vec!["README.md".to_string(), "docs/usage.md".to_string()],
+        };
+
+        let analysis = analyze_commit(commit.clone(), &config, &Workspace::default());
+        assert!(analysis.is_atomic);
+        assert!(analysis.message_issues.is_empty());
+
+        let analysis = analyze_commit(CommitInfo { files: vec!["src/lib.rs".to_string()], ..commit }, &config, &Workspace::default());
+        assert!(analysis.is_atomic);
+        assert_eq!(analysis.message_issues[0].rule, "type-concerns");
+    }
+
+    #[test]
+    fn test_combinations_and_weights() {
+        let files = |list: &[&str]| -> Vec<String> { list.iter().map(|f| f.to_string()).collect() };
+        let none = Workspace::default();
+        let mut config = test_config();
+        let feature = files(&["src/parser.rs", "tests/parser.rs", "Cargo.lock"]);
+        assert!(!evaluate("dev", &feature, &config, &none).is_atomic());
+
+        config.allowed_combinations = vec![vec![Concern::Source, Concern::Tests]];
+        let verdict = evaluate("dev", &feature, &config, &none);
+        assert!(verdict.is_atomic());
+        assert_eq!(verdict.groups, vec![ConcernGroup { package: None, concerns: vec![Concern::Source, Concern::Tests] }]);
+        assert_eq!(verdict.files[2].rule, "ignore_files `*.lock`");
+
+        let with_docs = files(&["src/parser.rs", "tests/parser.rs", "README.md"]);
+        assert_eq!(evaluate("dev", &with_docs, &config, &none).score, 2.0);
+        config.weights.insert(Concern::Docs, 0.0);
+        assert!(evaluate("dev", &with_docs, &config, &none).is_atomic());
+    }
+
+    #[test]
+    fn test_workspace_scoping() {
+        use crate::workspace::{Package, PackageKind};
+
+        let config = test_config();
+        let workspace = Workspace::new(["core", "cli"].iter().map(|name| Package {
+            path: format!("crates/{}", name),
+            name: name.to_string(),
+            kind: PackageKind::Cargo,
+        }).collect());
+        let files = vec!["crates/core/src/lib.rs".to_string(), "crates/cli/src/main.rs".to_string()];
+
+        assert!(evaluate("dev", &files, &config, &Workspace::default()).is_atomic());
+        let verdict = evaluate("dev", &files, &config, &workspace);
+        assert!(!verdict.is_atomic());
+        let groups: Vec<String> = verdict.groups.iter().map(|g| g.to_string()).collect();
+        assert_eq!(groups, vec!["cli:source", "core:source"]);
+    }
+
+    #[test]
+    fn test_overrides_and_author_modes() {
+        let none = Workspace::default();
+        let mut config = test_config();
+        config.mode = Mode::Error;
+        config.path_overrides = vec![crate::config::PathOverride {
+            pattern: crate::config::Pattern::new("^legacy/").unwrap(),
+            max_concerns: Some(3),
+            mode: Some(Mode::Warning),
+        }];
+        config.author_modes = vec![crate::config::AuthorMode {
+            pattern: crate::config::Pattern::case_insensitive("^intern-").unwrap(),
+            skip: false,
+            mode: Some(Mode::Warning),
+        }];
+
+        let legacy = vec!["legacy/a.rs".to_string(), "legacy/README.md".to_string()];
+        let verdict = evaluate("dev", &legacy, &config, &none);
+        assert!(verdict.is_atomic());
+        assert_eq!((verdict.max_concerns, verdict.mode), (3, Mode::Warning));
+        assert_eq!(verdict.path_override.as_deref(), Some("path_overrides[0] `^legacy/`"));
+
+        let mixed = vec!["src/a.rs".to_string(), "README.md".to_string()];
+        assert_eq!(evaluate("dev", &mixed, &config, &none).mode, Mode::Error);
+        assert_eq!(evaluate("intern-sam", &mixed, &config, &none).mode, Mode::Warning);
+        assert!(evaluate("dependabot[bot]", &mixed, &config, &none).skipped);
+    }
+
     #[test]
     fn test_categorize_infra() {
         let config = test_config();
@@ -3,7 +3,9 @@
 //! Reads YAML config from `.github/atomicity-config.yml`
 
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 /// Execution mode
@@ -61,9 +63,9 @@ pub struct Config {
     #[serde(default = "default_max_concerns")]
     pub max_concerns: usize,
 
-    /// Bot author patterns (regex)
-    #[serde(default = "default_bot_patterns")]
-    pub bot_patterns: Vec<String>,
+    /// Bot author patterns (regex, case-insensitive)
+    #[serde(default = "default_bot_patterns", deserialize_with = "case_insensitive_patterns")]
+    pub bot_patterns: Vec<Pattern>,
 
     /// Files to always ignore (glob patterns)
     #[serde(default = "default_ignore_files")]
@@ -72,17 +74,277 @@ pub struct Config {
     /// Custom concern rules
     #[serde(default)]
     pub custom_rules: Vec<ConcernRule>,
+
+    /// Concern combinations that count as a single concern (e.g. source + tests)
+    #[serde(default)]
+    pub allowed_combinations: Vec<Vec<Concern>>,
+
+    /// Weight of each concern when counting against `max_concerns` (default 1.0)
+    #[serde(default)]
+    pub weights: HashMap<Concern, f64>,
+
+    /// Limits and modes for commits whose files all match a path pattern
+    #[serde(default)]
+    pub path_overrides: Vec<PathOverride>,
+
+    /// Modes per author type, first match wins over `ignore_bots`
+    #[serde(default)]
+    pub author_modes: Vec<AuthorMode>,
+
+    /// Conventional Commit message validation
+    #[serde(default)]
+    pub commit_message: CommitMessageConfig,
+
+    /// Workspace package scoping
+    #[serde(default)]
+    pub workspace: WorkspaceConfig,
+
+    /// Local git hook (`atomicity-checker hook`)
+    #[serde(default)]
+    pub hook: HookConfig,
+}
+
+/// How the local git hook reacts to problems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HookMode {
+    /// Warn and allow the commit
+    #[default]
+    Solo,
+    /// Block the commit (bypass patterns and `--no-verify` still work)
+    Team,
+    /// Block the commit, bypass patterns are not honored
+    Strict,
+}
+
+impl std::str::FromStr for HookMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "solo" => Ok(HookMode::Solo),
+            "team" => Ok(HookMode::Team),
+            "strict" => Ok(HookMode::Strict),
+            other => anyhow::bail!("Unknown hook mode: {} (expected solo, team or strict)", other),
+        }
+    }
+}
+
+/// Local git hook settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookConfig {
+    /// Hook mode
+    #[serde(default)]
+    pub mode: HookMode,
+
+    /// Maximum staged files per commit (0 for no limit)
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+
+    /// Commit messages matching one of these (regex, case-insensitive) skip the hook
+    #[serde(default = "default_bypass_patterns", deserialize_with = "case_insensitive_patterns")]
+    pub bypass_patterns: Vec<Pattern>,
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self {
+            mode: HookMode::default(),
+            max_files: default_max_files(),
+            bypass_patterns: default_bypass_patterns(),
+        }
+    }
+}
+
+fn default_max_files() -> usize {
+    10
+}
+
+fn default_bypass_patterns() -> Vec<Pattern> {
+    ["^Merge (branch|pull request|remote-tracking)", "^chore: initial commit", "^Revert \""]
+        .iter()
+        .map(|p| Pattern::case_insensitive(p).expect("valid default bypass pattern"))
+        .collect()
+}
+
+/// Workspace package scoping: the same concern in two packages counts twice
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    /// Whether changes are scoped by package
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Package directories in addition to the detected ones
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
+/// A regex from the config file, compiled once when the config is parsed
+///
+/// An invalid regex fails `Config::load` instead of silently never matching.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    source: String,
+    regex: Regex,
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Self> {
+        Self::build(source, false)
+    }
+
+    pub fn case_insensitive(source: &str) -> Result<Self> {
+        Self::build(source, true)
+    }
+
+    fn build(source: &str, case_insensitive: bool) -> Result<Self> {
+        let regex = RegexBuilder::new(source)
+            .case_insensitive(case_insensitive)
+            .build()
+            .with_context(|| format!("Invalid regex `{}`", source))?;
+        Ok(Self { source: source.to_string(), regex })
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Pattern::new(&source).map_err(|e| serde::de::Error::custom(format!("{:#}", e)))
+    }
+}
+
+fn case_insensitive_pattern<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Pattern, D::Error> {
+    let source = String::deserialize(deserializer)?;
+    Pattern::case_insensitive(&source).map_err(|e| serde::de::Error::custom(format!("{:#}", e)))
+}
+
+fn case_insensitive_patterns<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<Pattern>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|source| Pattern::case_insensitive(source).map_err(|e| serde::de::Error::custom(format!("{:#}", e))))
+        .collect()
 }
 
 /// Custom rule for file categorization
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConcernRule {
     /// Regex pattern for file paths
-    pub pattern: String,
+    pub pattern: Pattern,
     /// Concern category to assign
     pub concern: Concern,
 }
 
+/// Override for commits that only touch matching paths
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathOverride {
+    /// Regex every counted file of the commit must match
+    pub pattern: Pattern,
+    /// Maximum concerns for these commits
+    #[serde(default)]
+    pub max_concerns: Option<usize>,
+    /// Mode for these commits
+    #[serde(default)]
+    pub mode: Option<Mode>,
+}
+
+/// Mode for commits from matching authors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorMode {
+    /// Regex for the author name (case-insensitive)
+    #[serde(deserialize_with = "case_insensitive_pattern")]
+    pub pattern: Pattern,
+    /// Skip these commits entirely
+    #[serde(default)]
+    pub skip: bool,
+    /// Mode for these commits
+    #[serde(default)]
+    pub mode: Option<Mode>,
+}
+
+/// Conventional Commit message rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitMessageConfig {
+    /// Whether messages are validated
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Allowed commit types
+    #[serde(default = "default_types")]
+    pub types: Vec<String>,
+
+    /// Allowed scopes (empty allows any)
+    #[serde(default)]
+    pub scopes: Vec<String>,
+
+    /// Whether a scope is mandatory when `scopes` is set
+    #[serde(default)]
+    pub require_scope: bool,
+
+    /// Whether the subject or body must reference an issue
+    #[serde(default)]
+    pub require_issue: bool,
+
+    /// Regex matching an issue reference
+    #[serde(default = "default_issue_pattern")]
+    pub issue_pattern: Pattern,
+
+    /// Concerns a commit type may touch (types not listed are unrestricted)
+    #[serde(default = "default_type_concerns")]
+    pub type_concerns: BTreeMap<String, Vec<Concern>>,
+}
+
+impl Default for CommitMessageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            types: default_types(),
+            scopes: Vec::new(),
+            require_scope: false,
+            require_issue: false,
+            issue_pattern: default_issue_pattern(),
+            type_concerns: default_type_concerns(),
+        }
+    }
+}
+
+fn default_types() -> Vec<String> {
+    [
+        "feat", "fix", "docs", "style", "refactor", "perf",
+        "test", "build", "ci", "chore", "revert",
+    ]
+    .iter()
+    .map(|t| t.to_string())
+    .collect()
+}
+
+fn default_issue_pattern() -> Pattern {
+    Pattern::new(r"(#\d+|[A-Z][A-Z0-9]+-\d+)").expect("valid default issue pattern")
+}
+
+fn default_type_concerns() -> BTreeMap<String, Vec<Concern>> {
+    BTreeMap::from([
+        ("docs".to_string(), vec![Concern::Docs]),
+        ("test".to_string(), vec![Concern::Tests]),
+        ("ci".to_string(), vec![Concern::Infra]),
+    ])
+}
+
 fn default_enabled() -> bool {
     true
 }
@@ -95,16 +357,11 @@ fn default_max_concerns() -> usize {
     1
 }
 
-fn default_bot_patterns() -> Vec<String> {
-    vec![
-        r"github-actions".to_string(),
-        r"dependabot".to_string(),
-        r"copilot".to_string(),
-        r"jules".to_string(),
-        r"renovate".to_string(),
-        r"bot$".to_string(),
-        r"\[bot\]".to_string(),
-    ]
+fn default_bot_patterns() -> Vec<Pattern> {
+    [r"github-actions", r"dependabot", r"copilot", r"jules", r"renovate", r"bot$", r"\[bot\]"]
+        .iter()
+        .map(|p| Pattern::case_insensitive(p).expect("valid default bot pattern"))
+        .collect()
 }
 
 fn default_ignore_files() -> Vec<String> {
@@ -127,6 +384,13 @@ impl Default for Config {
             bot_patterns: default_bot_patterns(),
             ignore_files: default_ignore_files(),
             custom_rules: Vec::new(),
+            allowed_combinations: Vec::new(),
+            weights: HashMap::new(),
+            path_overrides: Vec::new(),
+            author_modes: Vec::new(),
+            commit_message: CommitMessageConfig::default(),
+            workspace: WorkspaceConfig::default(),
+            hook: HookConfig::default(),
         }
     }
 }
@@ -152,18 +416,40 @@ impl Config {
         tracing::debug!("   mode: {:?}", config.mode);
         tracing::debug!("   ignore_bots: {}", config.ignore_bots);
         tracing::debug!("   max_concerns: {}", config.max_concerns);
+        tracing::debug!("   commit_message: {}", config.commit_message.enabled);
 
         Ok(config)
     }
 
     /// Check if a file should be ignored based on patterns
     pub fn should_ignore_file(&self, path: &str) -> bool {
-        for pattern in &self.ignore_files {
-            if glob_match(pattern, path) {
-                return true;
-            }
+        self.ignore_pattern(path).is_some()
+    }
+
+    /// The `ignore_files` pattern matching a path, if any
+    pub fn ignore_pattern(&self, path: &str) -> Option<&str> {
+        self.ignore_files.iter()
+            .find(|pattern| glob_match(pattern, path))
+            .map(String::as_str)
+    }
+
+    /// Weight of a concern when counting against `max_concerns`
+    pub fn weight(&self, concern: &Concern) -> f64 {
+        self.weights.get(concern).copied().unwrap_or(1.0)
+    }
+
+    /// First `author_modes` entry matching an author, with its index
+    pub fn author_mode(&self, author: &str) -> Option<(usize, &AuthorMode)> {
+        self.author_modes.iter().enumerate().find(|(_, rule)| rule.pattern.is_match(author))
+    }
+
+    /// First `path_overrides` entry matching every file, with its index
+    pub fn path_override(&self, files: &[&str]) -> Option<(usize, &PathOverride)> {
+        if files.is_empty() {
+            return None;
         }
-        false
+        self.path_overrides.iter().enumerate()
+            .find(|(_, rule)| files.iter().all(|f| rule.pattern.is_match(f)))
     }
 
     /// Check if an author is a bot
@@ -172,17 +458,7 @@ impl Config {
             return false;
         }
 
-        for pattern in &self.bot_patterns {
-            if let Ok(re) = regex::RegexBuilder::new(pattern)
-                .case_insensitive(true)
-                .build()
-            {
-                if re.is_match(author) {
-                    return true;
-                }
-            }
-        }
-        false
+        self.bot_patterns.iter().any(|pattern| pattern.is_match(author))
     }
 }
 
@@ -216,6 +492,68 @@ mod tests {
         assert_eq!(config.mode, Mode::Warning);
         assert!(config.ignore_bots);
         assert_eq!(config.max_concerns, 1);
+        assert!(!config.commit_message.enabled);
+        assert_eq!(config.hook.mode, HookMode::Solo);
+        assert_eq!(config.hook.max_files, 10);
+    }
+
+    #[test]
+    fn test_combinations_overrides_and_author_modes() {
+        let config: Config = serde_yaml::from_str(r#"
+allowed_combinations:
+  - [source, tests]
+weights:
+  docs: 0.5
+path_overrides:
+  - pattern: "^legacy/"
+    max_concerns: 3
+    mode: warning
+author_modes:
+  - pattern: "^renovate"
+    skip: true
+"#).unwrap();
+
+        assert_eq!(config.allowed_combinations, vec![vec![Concern::Source, Concern::Tests]]);
+        assert_eq!(config.weight(&Concern::Docs), 0.5);
+        assert_eq!(config.weight(&Concern::Source), 1.0);
+
+        let (index, rule) = config.path_override(&["legacy/a.rs", "legacy/README.md"]).unwrap();
+        assert_eq!((index, rule.max_concerns), (0, Some(3)));
+        assert!(config.path_override(&["legacy/a.rs", "src/b.rs"]).is_none());
+        assert!(config.path_override(&[]).is_none());
+
+        assert!(config.author_mode("Renovate Bot").unwrap().1.skip);
+        assert!(config.author_mode("jane").is_none());
+        assert_eq!(config.ignore_pattern("web/yarn.lock"), Some("*.lock"));
+    }
+
+    #[test]
+    fn test_invalid_patterns_fail_to_load() {
+        for yaml in [
+            "commit_message:\n  issue_pattern: '(#\\d+'\n",
+            "path_overrides:\n  - pattern: '^legacy/['\n",
+            "author_modes:\n  - pattern: '*bot'\n",
+            "hook:\n  bypass_patterns: ['^Merge (']\n",
+            "bot_patterns: ['[bot']\n",
+            "custom_rules:\n  - pattern: '^infra/('\n    concern: config\n",
+        ] {
+            let path = std::env::temp_dir().join(format!("atomicity-config-{}.yml", std::process::id()));
+            std::fs::write(&path, yaml).unwrap();
+            let error = format!("{:#}", Config::load(&path).unwrap_err());
+            std::fs::remove_file(&path).unwrap();
+            assert!(error.contains("Invalid regex"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_commit_message_config() {
+        let config: Config = serde_yaml::from_str(
+            "commit_message:\n  enabled: true\n  scopes: [cli]\n  type_concerns:\n    docs: [docs]\n",
+        ).unwrap();
+        assert!(config.commit_message.enabled);
+        assert_eq!(config.commit_message.scopes, vec!["cli"]);
+        assert!(config.commit_message.types.contains(&"feat".to_string()));
+        assert_eq!(config.commit_message.type_concerns.len(), 1);
     }
 
     #[test]
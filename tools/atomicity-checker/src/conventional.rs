@@ -0,0 +1,185 @@
+//! Conventional Commit message validation.
+//!
+//! Parses `type(scope)!: description` subjects and checks them against the
+//! `commit_message` section of the config:
+//!
+//! | Rule | Checks |
+//! |------|--------|
+//! | `format` | Subject follows the Conventional Commits grammar |
+//! | `type` | Type is in `types` |
+//! | `scope` | Scope is in `scopes` (when the list is not empty) |
+//! | `issue-reference` | Subject or body references an issue (`require_issue`) |
+//! | `type-concerns` | Concerns touched are allowed for the type (`docs:` only docs, ...) |
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use crate::config::{CommitMessageConfig, Concern};
+
+/// Parsed Conventional Commit subject
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+/// A problem with a commit message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MessageIssue {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl MessageIssue {
+    fn new(rule: &'static str, message: String) -> Self {
+        Self { rule, message }
+    }
+}
+
+fn subject_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^([a-zA-Z]+)(?:\(([^()\s]+)\))?(!)?: (\S.*)$").unwrap())
+}
+
+/// Parse a commit subject; `None` when it is not a Conventional Commit
+pub fn parse(subject: &str) -> Option<ConventionalCommit> {
+    let caps = subject_regex().captures(subject.trim())?;
+    Some(ConventionalCommit {
+        kind: caps[1].to_lowercase(),
+        scope: caps.get(2).map(|m| m.as_str().to_string()),
+        breaking: caps.get(3).is_some(),
+        description: caps[4].to_string(),
+    })
+}
+
+/// Subjects git writes itself (merges, reverts, fixups) are not validated
+fn is_generated(subject: &str) -> bool {
+    ["Merge ", "Revert \"", "fixup! ", "squash! ", "amend! "].iter().any(|p| subject.starts_with(p))
+}
+
+/// Validate a commit message against the config and the concerns it touches
+pub fn validate(
+    subject: &str,
+    body: &str,
+    concerns: &HashSet<Concern>,
+    config: &CommitMessageConfig,
+) -> Vec<MessageIssue> {
+    if !config.enabled || is_generated(subject) {
+        return Vec::new();
+    }
+
+    let Some(commit) = parse(subject) else {
+        return vec![MessageIssue::new(
+            "format",
+            "Subject is not a Conventional Commit (`type(scope): description`)".to_string(),
+        )];
+    };
+
+    let mut issues = Vec::new();
+    if !config.types.iter().any(|t| t == &commit.kind) {
+        issues.push(MessageIssue::new(
+            "type",
+            format!("Type `{}` is not allowed (allowed: {})", commit.kind, config.types.join(", ")),
+        ));
+    }
+
+    if !config.scopes.is_empty() {
+        match &commit.scope {
+            Some(scope) if !config.scopes.contains(scope) => issues.push(MessageIssue::new(
+                "scope",
+                format!("Scope `{}` is not allowed (allowed: {})", scope, config.scopes.join(", ")),
+            )),
+            None if config.require_scope => {
+                issues.push(MessageIssue::new("scope", "A scope is required".to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    if config.require_issue {
+        let referenced = config.issue_pattern.is_match(subject) || config.issue_pattern.is_match(body);
+        if !referenced {
+            issues.push(MessageIssue::new(
+                "issue-reference",
+                format!("No issue reference matching `{}`", config.issue_pattern),
+            ));
+        }
+    }
+
+    if let Some(allowed) = config.type_concerns.get(&commit.kind) {
+        let mut outside: Vec<String> = concerns.iter()
+            .filter(|c| !allowed.contains(c))
+            .map(|c| c.to_string())
+            .collect();
+        outside.sort();
+        if !outside.is_empty() {
+            let allowed: Vec<String> = allowed.iter().map(|c| c.to_string()).collect();
+            issues.push(MessageIssue::new(
+                "type-concerns",
+                format!(
+                    "`{}` commit touches {} (only {} expected)",
+                    commit.kind,
+                    outside.join(", "),
+                    allowed.join(", ")
+                ),
+            ));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn concerns(list: &[Concern]) -> HashSet<Concern> {
+        list.iter().cloned().collect()
+    }
+
+    fn enabled() -> CommitMessageConfig {
+        CommitMessageConfig { enabled: true, ..CommitMessageConfig::default() }
+    }
+
+    #[test]
+    fn test_parse_subjects() {
+        assert_eq!(parse("feat(parser)!: support groups"), Some(ConventionalCommit {
+            kind: "feat".to_string(),
+            scope: Some("parser".to_string()),
+            breaking: true,
+            description: "support groups".to_string(),
+        }));
+        assert_eq!(parse("fix: handle empty input").unwrap().scope, None);
+        assert!(parse("Update README").is_none());
+        assert!(parse("feat:missing space").is_none());
+        assert!(parse("feat(): empty scope").is_none());
+    }
+
+    #[test]
+    fn test_validate_rules() {
+        let config = CommitMessageConfig {
+            scopes: vec!["parser".to_string()],
+            require_issue: true,
+            ..enabled()
+        };
+
+        assert!(validate("fix(parser): handle empty input", "Closes #12", &concerns(&[Concern::Source]), &config).is_empty());
+
+        let rules = |subject: &str, body: &str, touched: &[Concern]| -> Vec<&'static str> {
+            validate(subject, body, &concerns(touched), &config).into_iter().map(|i| i.rule).collect()
+        };
+        assert_eq!(rules("Update stuff", "", &[Concern::Source]), vec!["format"]);
+        assert_eq!(rules("wip(cli): things #3", "", &[Concern::Source]), vec!["type", "scope"]);
+        assert_eq!(rules("docs(parser): explain groups", "", &[Concern::Docs]), vec!["issue-reference"]);
+        assert_eq!(rules("docs(parser): explain groups #4", "", &[Concern::Docs, Concern::Source]), vec!["type-concerns"]);
+        assert_eq!(rules("test(parser): cover groups #4", "", &[Concern::Tests]), Vec::<&str>::new());
+        assert!(rules("Merge branch 'main' into feature", "", &[Concern::Source]).is_empty());
+
+        // Disabled by default
+        assert!(validate("Update stuff", "", &concerns(&[Concern::Source]), &CommitMessageConfig::default()).is_empty());
+    }
+}
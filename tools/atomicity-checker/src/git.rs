@@ -21,10 +21,25 @@ pub struct CommitInfo {
     pub author: String,
     /// Commit message (first line)
     pub message: String,
+    /// Commit message body (after the first line)
+    pub body: String,
     /// Files changed in this commit
     pub files: Vec<String>,
 }
 
+/// Log format: fields separated by \x00, records by \x1e (bodies span lines)
+const LOG_FORMAT: &str = "--format=%H%x00%an%x00%s%x00%b%x1e";
+
+/// Split one log record into (sha, author, subject, body)
+fn parse_log_record(record: &str) -> Option<(String, String, String, String)> {
+    let mut parts = record.trim_start_matches('\n').splitn(4, '\x00');
+    let sha = parts.next().filter(|s| !s.is_empty())?.to_string();
+    let author = parts.next()?.to_string();
+    let subject = parts.next()?.to_string();
+    let body = parts.next().unwrap_or("").trim().to_string();
+    Some((sha, author, subject, body))
+}
+
 /// Get commits between two refs
 pub async fn get_commits_between<P: AsRef<Path>>(
     repo_path: P,
@@ -33,13 +48,11 @@ pub async fn get_commits_between<P: AsRef<Path>>(
 ) -> Result<Vec<CommitInfo>> {
     let repo_path = repo_path.as_ref();
 
-    // Format: SHA AUTHOR_NAME
-    // We use \x00 as delimiter to handle spaces in author names
     let output = Command::new("git")
         .current_dir(repo_path)
         .args([
             "log",
-            "--format=%H%x00%an%x00%s",
+            LOG_FORMAT,
             &format!("{}..{}", base, head),
         ])
         .output()
@@ -52,7 +65,7 @@ pub async fn get_commits_between<P: AsRef<Path>>(
             .current_dir(repo_path)
             .args([
                 "log",
-                "--format=%H%x00%an%x00%s",
+                LOG_FORMAT,
                 &format!("origin/{}..{}", base, head),
             ])
             .output()
@@ -75,19 +88,10 @@ async fn parse_log_output(output: &[u8], repo_path: &Path) -> Result<Vec<CommitI
     let stdout = String::from_utf8_lossy(output);
     let mut commits = Vec::new();
 
-    for line in stdout.lines() {
-        if line.is_empty() {
-            continue;
-        }
-
-        let parts: Vec<&str> = line.split('\x00').collect();
-        if parts.len() < 3 {
+    for record in stdout.split('\x1e') {
+        let Some((sha, author, message, body)) = parse_log_record(record) else {
             continue;
-        }
-
-        let sha = parts[0].to_string();
-        let author = parts[1].to_string();
-        let message = parts[2].to_string();
+        };
         let short_sha = sha.chars().take(8).collect();
 
         // Get files changed in this commit
@@ -98,6 +102,7 @@ async fn parse_log_output(output: &[u8], repo_path: &Path) -> Result<Vec<CommitI
             short_sha,
             author,
             message,
+            body,
             files,
         });
     }
@@ -135,7 +140,7 @@ pub async fn get_commit<P: AsRef<Path>>(repo_path: P, sha: &str) -> Result<Commi
 
     let output = Command::new("git")
         .current_dir(repo_path)
-        .args(["log", "-1", "--format=%H%x00%an%x00%s", sha])
+        .args(["log", "-1", LOG_FORMAT, sha])
         .output()
         .await
         .context("Failed to get commit info")?;
@@ -146,25 +151,80 @@ pub async fn get_commit<P: AsRef<Path>>(repo_path: P, sha: &str) -> Result<Commi
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let line = stdout.lines().next().context("No commit found")?;
-
-    let parts: Vec<&str> = line.split('\x00').collect();
-    if parts.len() < 3 {
-        bail!("Invalid git log format");
-    }
-
-    let full_sha = parts[0].to_string();
+    let record = stdout.split('\x1e').next().context("No commit found")?;
+    let (full_sha, author, message, body) = parse_log_record(record).context("Invalid git log format")?;
     let files = get_commit_files(repo_path, &full_sha).await?;
 
     Ok(CommitInfo {
-        sha: full_sha.clone(),
         short_sha: full_sha.chars().take(8).collect(),
-        author: parts[1].to_string(),
-        message: parts[2].to_string(),
+        sha: full_sha,
+        author,
+        message,
+        body,
         files,
     })
 }
 
+/// Run a git command and return its trimmed stdout
+pub async fn run_git<P: AsRef<Path>>(repo_path: P, args: &[&str]) -> Result<String> {
+    run_git_with_env(repo_path, args, &[]).await
+}
+
+/// Run a git command with extra environment variables (identity, index file)
+pub async fn run_git_with_env<P: AsRef<Path>>(
+    repo_path: P,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(repo_path.as_ref())
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .await
+        .with_context(|| format!("Failed to execute git {}", args.first().unwrap_or(&"")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git {} failed: {}", args.join(" "), stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Run a git command feeding `input` on stdin (e.g. `update-index -z --index-info`)
+pub async fn run_git_with_input<P: AsRef<Path>>(
+    repo_path: P,
+    args: &[&str],
+    envs: &[(&str, &str)],
+    input: &[u8],
+) -> Result<String> {
+    use std::process::Stdio;
+    use tokio::io::AsyncWriteExt;
+
+    let mut child = Command::new("git")
+        .current_dir(repo_path.as_ref())
+        .args(args)
+        .envs(envs.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to execute git {}", args.first().unwrap_or(&"")))?;
+
+    let mut stdin = child.stdin.take().context("Failed to open git stdin")?;
+    stdin.write_all(input).await?;
+    drop(stdin);
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git {} failed: {}", args.join(" "), stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
 /// Get the default branch name
 pub async fn get_default_branch<P: AsRef<Path>>(repo_path: P) -> Result<String> {
     let output = Command::new("git")
@@ -200,6 +260,17 @@ pub async fn get_default_branch<P: AsRef<Path>>(repo_path: P) -> Result<String>
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_log_record() {
+        let (sha, author, subject, body) =
+            parse_log_record("\nabc123\x00Jane Doe\x00fix: handle input\x00Details\n\nCloses #7\n").unwrap();
+        assert_eq!(sha, "abc123");
+        assert_eq!(author, "Jane Doe");
+        assert_eq!(subject, "fix: handle input");
+        assert_eq!(body, "Details\n\nCloses #7");
+        assert!(parse_log_record("\n").is_none());
+    }
+
     #[tokio::test]
     async fn test_get_default_branch() {
         // This test will work in any git repo
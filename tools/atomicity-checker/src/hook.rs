@@ -0,0 +1,351 @@
+//! Local git hooks.
+//!
+//! `hook install` writes `pre-commit` and `commit-msg` hooks that call back
+//! into this binary, so local commits are checked with the same rules and
+//! config as CI:
+//!
+//! - `pre-commit` checks the staged files: concerns (with combinations,
+//!   weights, overrides and workspace scoping) and `hook.max_files`.
+//! - `commit-msg` checks the message against `commit_message` and the staged
+//!   concerns, also when nothing is staged (`--allow-empty`, rewording).
+//!   Messages matching `hook.bypass_patterns` skip it, except in strict mode.
+//!
+//! Hooks only see the index against `HEAD`: for `git commit --amend` that is
+//! what the amend adds, not the files of the whole amended commit. `check` on
+//! the pushed range covers the complete commit.
+//!
+//! In solo mode problems are warnings; team and strict block the commit.
+
+use anyhow::{Context, Result};
+use colored::*;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::{evaluate, Verdict};
+use crate::config::{Config, HookMode, Pattern};
+use crate::conventional;
+use crate::git::run_git;
+use crate::workspace::Workspace;
+
+/// Marker identifying hooks written by this tool (shared with `scripts/hooks/install-hooks.sh`)
+pub const HOOK_MARKER: &str = "git-core-protocol";
+
+/// Legacy config of the Bash hook, no longer read
+const LEGACY_CONFIG: &str = ".git-atomize.yml";
+
+/// Git hook stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Stage {
+    PreCommit,
+    CommitMsg,
+}
+
+impl Stage {
+    const ALL: [Stage; 2] = [Stage::PreCommit, Stage::CommitMsg];
+
+    /// File name of the hook in the hooks directory
+    pub fn hook_name(&self) -> &'static str {
+        match self {
+            Stage::PreCommit => "pre-commit",
+            Stage::CommitMsg => "commit-msg",
+        }
+    }
+}
+
+/// Directory git reads hooks from (honors `core.hooksPath`)
+async fn hooks_dir(repo: &Path) -> Result<PathBuf> {
+    let dir = run_git(repo, &["rev-parse", "--git-path", "hooks"]).await?;
+    Ok(repo.join(dir))
+}
+
+fn hook_script(stage: Stage, exe: &Path, config_path: &str) -> String {
+    format!(
+        "#!/bin/sh\n\
+         # {marker}: installed by atomicity-checker hook install\n\
+         # Bypass with: git commit --no-verify\n\
+         ATOMICITY_CHECKER=\"${{ATOMICITY_CHECKER:-{exe}}}\"\n\
+         exec \"$ATOMICITY_CHECKER\" --config \"{config}\" hook run {stage} \"$@\"\n",
+        marker = HOOK_MARKER,
+        exe = exe.display(),
+        config = config_path,
+        stage = stage.hook_name(),
+    )
+}
+
+/// Install the hooks, backing up foreign hooks; returns the written paths
+pub async fn install<P: AsRef<Path>>(repo_path: P, config_path: &str) -> Result<Vec<PathBuf>> {
+    let dir = hooks_dir(repo_path.as_ref()).await?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let exe = std::env::current_exe().context("Failed to locate the atomicity-checker binary")?;
+
+    let mut written = Vec::new();
+    for stage in Stage::ALL {
+        let path = dir.join(stage.hook_name());
+        if let Ok(existing) = std::fs::read_to_string(&path) {
+            if !existing.contains(HOOK_MARKER) {
+                let backup = dir.join(format!("{}.backup.{}", stage.hook_name(), unix_time()));
+                std::fs::rename(&path, &backup)?;
+                println!("💾 Existing {} hook saved as {:?}", stage.hook_name(), backup);
+            }
+        }
+
+        std::fs::write(&path, hook_script(stage, &exe, config_path))
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        }
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Remove the hooks written by `install`, restoring the latest backups
+pub async fn uninstall<P: AsRef<Path>>(repo_path: P) -> Result<()> {
+    let dir = hooks_dir(repo_path.as_ref()).await?;
+    for stage in Stage::ALL {
+        let path = dir.join(stage.hook_name());
+        match std::fs::read_to_string(&path) {
+            Ok(content) if content.contains(HOOK_MARKER) => {
+                std::fs::remove_file(&path)?;
+                println!("🗑️ Removed {} hook", stage.hook_name());
+            }
+            Ok(_) => {
+                println!("⚠️ {} hook was not installed by atomicity-checker, left in place", stage.hook_name());
+                continue;
+            }
+            Err(_) => continue,
+        }
+
+        let prefix = format!("{}.backup.", stage.hook_name());
+        let latest = std::fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with(&prefix))
+            .max_by_key(|name| name[prefix.len()..].parse::<u64>().unwrap_or(0));
+        if let Some(backup) = latest {
+            std::fs::rename(dir.join(&backup), &path)?;
+            println!("♻️ Restored {} from {}", stage.hook_name(), backup);
+        }
+    }
+    Ok(())
+}
+
+fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Run a hook stage; returns whether the commit may proceed
+pub async fn run<P: AsRef<Path>>(
+    stage: Stage,
+    message_file: Option<&str>,
+    repo_path: P,
+    config: &Config,
+    mode: HookMode,
+) -> Result<bool> {
+    let repo = repo_path.as_ref();
+    let root = PathBuf::from(run_git(repo, &["rev-parse", "--show-toplevel"]).await?);
+    if root.join(LEGACY_CONFIG).exists() {
+        println!(
+            "{}",
+            format!("⚠️  {} is no longer read; move its settings to the `hook:` section of the atomicity config", LEGACY_CONFIG).yellow()
+        );
+    }
+
+    // Concluding a merge commits the other branch's changes as they are
+    let merge_head = run_git(repo, &["rev-parse", "--git-path", "MERGE_HEAD"]).await?;
+    if repo.join(merge_head).exists() {
+        return Ok(true);
+    }
+
+    let files: Vec<String> = run_git(repo, &["diff", "--cached", "--name-only", "--no-renames"])
+        .await?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    // An empty commit has no concerns, but its message is still checked
+    if files.is_empty() && stage == Stage::PreCommit {
+        return Ok(true);
+    }
+
+    let ident = run_git(repo, &["var", "GIT_AUTHOR_IDENT"]).await?;
+    let author = ident.split(" <").next().unwrap_or_default();
+    let workspace = Workspace::load(repo, &config.workspace, None).await?;
+    let verdict = evaluate(author, &files, config, &workspace);
+    if verdict.skipped {
+        return Ok(true);
+    }
+
+    let problems = match stage {
+        Stage::PreCommit => check_staged(&verdict, files.len(), config.hook.max_files),
+        Stage::CommitMsg => {
+            let path = message_file.context("commit-msg stage needs the message file")?;
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read commit message {:?}", path))?;
+            let (subject, body) = parse_message(&content);
+            if subject.is_empty() {
+                return Ok(true);
+            }
+            if mode != HookMode::Strict && is_bypassed(&subject, &config.hook.bypass_patterns) {
+                println!("{}", "⏭️  Commit message matches a bypass pattern".dimmed());
+                return Ok(true);
+            }
+            conventional::validate(&subject, &body, &verdict.concerns(), &config.commit_message)
+                .into_iter()
+                .map(|issue| format!("{} [{}]", issue.message, issue.rule))
+                .collect()
+        }
+    };
+
+    Ok(report(stage, &verdict, files.len(), config.hook.max_files, &problems, mode))
+}
+
+/// Problems of the staged files
+fn check_staged(verdict: &Verdict, file_count: usize, max_files: usize) -> Vec<String> {
+    let mut problems = Vec::new();
+    if !verdict.is_atomic() {
+        let groups: Vec<String> = verdict.groups.iter().map(|g| g.to_string()).collect();
+        problems.push(format!(
+            "Mixes {} concerns: {} (score {}, max {})",
+            groups.len(),
+            groups.join(", "),
+            verdict.score,
+            verdict.max_concerns
+        ));
+    }
+    if max_files > 0 && file_count > max_files {
+        problems.push(format!("{} files staged (max {})", file_count, max_files));
+    }
+    problems
+}
+
+/// Subject and body of a commit message file, without comments
+fn parse_message(content: &str) -> (String, String) {
+    let text: Vec<&str> = content.lines()
+        .take_while(|line| !line.starts_with("# ------------------------ >8 ------------------------"))
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+    let text = text.join("\n");
+    let text = text.trim();
+    let (subject, body) = text.split_once('\n').unwrap_or((text, ""));
+    (subject.trim().to_string(), body.trim().to_string())
+}
+
+fn is_bypassed(subject: &str, patterns: &[Pattern]) -> bool {
+    patterns.iter().any(|pattern| pattern.is_match(subject))
+}
+
+/// Print the outcome; returns whether the commit may proceed
+fn report(stage: Stage, verdict: &Verdict, file_count: usize, max_files: usize, problems: &[String], mode: HookMode) -> bool {
+    if problems.is_empty() {
+        if stage == Stage::PreCommit {
+            println!("{}", "✅ Commit passes atomicity check".green());
+            let limit = if max_files > 0 { max_files.to_string() } else { "-".to_string() };
+            println!("   Files: {}/{} | Concerns: {}/{}", file_count, limit, verdict.score, verdict.max_concerns);
+        }
+        return true;
+    }
+
+    let blocking = mode != HookMode::Solo;
+    if blocking {
+        println!("\n{}\n", "❌ Commit rejected: violates the atomicity policy".red().bold());
+    } else {
+        println!("\n{}\n", "⚠️  Commit does not follow the atomicity policy".yellow().bold());
+    }
+    for problem in problems {
+        println!("   - {}", problem);
+    }
+
+    match stage {
+        Stage::PreCommit => {
+            println!("\n{} Split your changes into smaller, focused commits.", "💡".bold());
+            println!("   Each commit should address only one concern.");
+        }
+        Stage::CommitMsg => {
+            println!("\n{} Use a Conventional Commit subject matching the staged changes.", "💡".bold());
+        }
+    }
+    if mode == HookMode::Team {
+        println!("{}", "   For emergency bypass: git commit --no-verify".yellow());
+    }
+    println!();
+    !blocking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CommitMessageConfig;
+
+    async fn git(repo: &Path, args: &[&str]) -> String {
+        run_git(repo, args).await.unwrap()
+    }
+
+    #[test]
+    fn test_parse_message_and_bypass() {
+        let content = "fix(cli): handle empty input\n\nCloses #7\n# Please enter the commit message\n\
+                       # ------------------------ >8 ------------------------\ndiff --git a/x b/x\n";
+        assert_eq!(parse_message(content), ("fix(cli): handle empty input".to_string(), "Closes #7".to_string()));
+        assert_eq!(parse_message("# only comments\n").0, "");
+
+        let patterns = crate::config::HookConfig::default().bypass_patterns;
+        assert!(is_bypassed("Merge branch 'main' into feature", &patterns));
+        assert!(!is_bypassed("feat: merge branches in the UI", &patterns));
+    }
+
+    #[tokio::test]
+    async fn test_install_and_run_stages() {
+        let repo = std::env::temp_dir().join(format!("atomicity-hook-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]).await;
+        git(&repo, &["config", "user.name", "Dev"]).await;
+        git(&repo, &["config", "user.email", "dev@example.com"]).await;
+
+        let hooks = repo.join(".git/hooks");
+        std::fs::create_dir_all(&hooks).unwrap();
+        std::fs::write(hooks.join("pre-commit"), "#!/bin/sh\nexit 0\n").unwrap();
+        let written = install(&repo, ".github/atomicity-config.yml").await.unwrap();
+        assert_eq!(written.len(), 2);
+        let script = std::fs::read_to_string(hooks.join("commit-msg")).unwrap();
+        assert!(script.contains(HOOK_MARKER) && script.contains("hook run commit-msg \"$@\""));
+        assert!(std::fs::read_dir(&hooks).unwrap().any(|e| e.unwrap().file_name().to_string_lossy().starts_with("pre-commit.backup.")));
+
+        for (path, content) in [("src/lib.rs", "fn a() {}\n"), ("README.md", "# Demo\n")] {
+            std::fs::create_dir_all(repo.join(path).parent().unwrap()).unwrap();
+            std::fs::write(repo.join(path), content).unwrap();
+        }
+        git(&repo, &["add", "-A"]).await;
+
+        let config = Config::default();
+        assert!(run(Stage::PreCommit, None, &repo, &config, HookMode::Solo).await.unwrap());
+        assert!(!run(Stage::PreCommit, None, &repo, &config, HookMode::Team).await.unwrap());
+
+        git(&repo, &["reset", "-q", "README.md"]).await;
+        assert!(run(Stage::PreCommit, None, &repo, &config, HookMode::Strict).await.unwrap());
+
+        let config = Config {
+            commit_message: CommitMessageConfig { enabled: true, ..CommitMessageConfig::default() },
+            ..Config::default()
+        };
+        let message = repo.join(".git/COMMIT_EDITMSG");
+        std::fs::write(&message, "docs: add lib\n").unwrap();
+        let message = message.to_str().unwrap();
+        assert!(!run(Stage::CommitMsg, Some(message), &repo, &config, HookMode::Team).await.unwrap());
+        std::fs::write(message, "feat: add lib\n").unwrap();
+        assert!(run(Stage::CommitMsg, Some(message), &repo, &config, HookMode::Team).await.unwrap());
+
+        // Nothing staged (`--allow-empty`): the message is still validated
+        git(&repo, &["reset", "-q"]).await;
+        assert!(run(Stage::PreCommit, None, &repo, &config, HookMode::Team).await.unwrap());
+        std::fs::write(message, "added stuff\n").unwrap();
+        assert!(!run(Stage::CommitMsg, Some(message), &repo, &config, HookMode::Team).await.unwrap());
+
+        uninstall(&repo).await.unwrap();
+        assert_eq!(std::fs::read_to_string(hooks.join("pre-commit")).unwrap(), "#!/bin/sh\nexit 0\n");
+        assert!(!hooks.join("commit-msg").exists());
+    }
+}
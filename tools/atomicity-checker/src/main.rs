@@ -8,6 +8,9 @@
 //! ```bash
 //! atomicity-checker check --base main --head feature-branch
 //! atomicity-checker report --format markdown
+//! atomicity-checker split --base main --dry-run
+//! atomicity-checker config explain HEAD
+//! atomicity-checker hook install
 //! ```
 
 use clap::{Parser, Subcommand};
@@ -16,9 +19,13 @@ use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
 mod config;
+mod conventional;
 mod git;
+mod hook;
 mod analyzer;
 mod reporter;
+mod split;
+mod workspace;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -36,7 +43,7 @@ struct Cli {
     #[arg(short, long, default_value = "false")]
     verbose: bool,
 
-    /// Output format (terminal, markdown, json)
+    /// Output format (terminal, markdown, json, sarif, junit)
     #[arg(short, long, default_value = "terminal")]
     output: String,
 
@@ -91,6 +98,89 @@ enum Commands {
         #[arg(short = 'f', long)]
         file: Option<String>,
     },
+
+    /// Split non-atomic commits of base..HEAD into one commit per concern
+    Split {
+        /// Base ref (e.g., main, origin/main)
+        #[arg(short, long, env = "GITHUB_BASE_REF")]
+        base: Option<String>,
+
+        /// Split only this commit (must be in base..HEAD)
+        #[arg(short = 'C', long)]
+        commit: Option<String>,
+
+        /// Repository path
+        #[arg(short, long, default_value = ".")]
+        repo: String,
+
+        /// Show the plan without rewriting history
+        #[arg(short = 'n', long, default_value = "false")]
+        dry_run: bool,
+
+        /// Ref saving the old HEAD (default: refs/atomicity/backup/<branch>-<timestamp>)
+        #[arg(long)]
+        backup_ref: Option<String>,
+    },
+
+    /// Inspect how the configuration applies
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Install or run the local git hooks
+    Hook {
+        #[command(subcommand)]
+        command: HookCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HookCommands {
+    /// Install pre-commit and commit-msg hooks calling this binary
+    Install {
+        /// Repository path
+        #[arg(short, long, default_value = ".")]
+        repo: String,
+    },
+
+    /// Remove the installed hooks, restoring previous ones
+    Uninstall {
+        /// Repository path
+        #[arg(short, long, default_value = ".")]
+        repo: String,
+    },
+
+    /// Run a hook stage (called by the installed hooks)
+    Run {
+        /// Hook stage
+        #[arg(value_enum)]
+        stage: hook::Stage,
+
+        /// Commit message file (commit-msg stage)
+        message_file: Option<String>,
+
+        /// Repository path
+        #[arg(short, long, default_value = ".")]
+        repo: String,
+
+        /// Override hook.mode (solo, team, strict)
+        #[arg(long, env = "ATOMICITY_HOOK_MODE")]
+        mode: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Show which rule classified each file of a commit
+    Explain {
+        /// Commit to explain
+        commit: String,
+
+        /// Repository path
+        #[arg(short, long, default_value = ".")]
+        repo: String,
+    },
 }
 
 #[tokio::main]
@@ -104,6 +194,7 @@ async fn main() -> Result<()> {
     FmtSubscriber::builder()
         .with_max_level(level)
         .with_target(false)
+        .with_writer(std::io::stderr)
         .compact()
         .init();
 
@@ -112,7 +203,11 @@ async fn main() -> Result<()> {
     // Load configuration
     let config = config::Config::load(&cli.config)?;
 
-    if !config.enabled {
+    let always_runs = matches!(
+        cli.command,
+        Commands::Config { .. } | Commands::Hook { command: HookCommands::Install { .. } | HookCommands::Uninstall { .. } }
+    );
+    if !config.enabled && !always_runs {
         info!("⏭️ Atomicity check is disabled in configuration");
         return Ok(());
     }
@@ -123,10 +218,10 @@ async fn main() -> Result<()> {
                 .unwrap_or_else(|| "main".to_string());
 
             let result = analyzer::check_atomicity(&repo, &base_ref, &head, &config).await?;
-            reporter::print_result(&result, &cli.output, &config)?;
+            reporter::print_result(&result, &cli.output, &config, &cli.config)?;
 
-            // Exit with error code if issues found and mode is error
-            if result.has_issues && config.mode == config::Mode::Error {
+            // Exit with error code if a commit whose mode is error has issues
+            if result.should_fail {
                 std::process::exit(1);
             }
         }
@@ -139,7 +234,50 @@ async fn main() -> Result<()> {
                 .unwrap_or_else(|| "main".to_string());
 
             let result = analyzer::check_atomicity(&repo, &base_ref, &head, &config).await?;
-            reporter::generate_report(&result, &cli.output, file.as_deref(), &config)?;
+            reporter::generate_report(&result, &cli.output, file.as_deref(), &config, &cli.config)?;
+        }
+        Commands::Split { base, commit, repo, dry_run, backup_ref } => {
+            // A single commit splits against its parent unless a base is given
+            let base_ref = match (&base, &commit) {
+                (Some(base), _) => base.clone(),
+                (None, Some(commit)) => format!("{}^", commit),
+                (None, None) => git::get_default_branch(&repo).await?,
+            };
+
+            let plan = split::plan_split(&repo, &base_ref, commit.as_deref(), &config).await?;
+            split::print_plan(&plan, &cli.output)?;
+
+            if !dry_run && plan.split_count() > 0 {
+                let backup_ref = match backup_ref {
+                    Some(backup_ref) => backup_ref,
+                    None => split::default_backup_ref(&repo).await?,
+                };
+                split::apply_split(&repo, &plan, &backup_ref).await?;
+                println!("💾 Previous HEAD saved as {} (git reset --hard {} to undo)", backup_ref, backup_ref);
+            }
+        }
+        Commands::Config { command: ConfigCommands::Explain { commit, repo } } => {
+            let (commit, verdict) = analyzer::explain_commit(&repo, &commit, &config).await?;
+            reporter::print_explanation(&commit, &verdict, &cli.output)?;
+        }
+        Commands::Hook { command: HookCommands::Install { repo } } => {
+            for path in hook::install(&repo, &cli.config).await? {
+                println!("🪝 Installed {}", path.display());
+            }
+            println!("   Mode: {:?} (set hook.mode in {})", config.hook.mode, cli.config);
+            println!("   Bypass with: git commit --no-verify");
+        }
+        Commands::Hook { command: HookCommands::Uninstall { repo } } => {
+            hook::uninstall(&repo).await?;
+        }
+        Commands::Hook { command: HookCommands::Run { stage, message_file, repo, mode } } => {
+            let mode = match mode {
+                Some(mode) => mode.parse()?,
+                None => config.hook.mode,
+            };
+            if !hook::run(stage, message_file.as_deref(), &repo, &config, mode).await? {
+                std::process::exit(1);
+            }
         }
     }
 
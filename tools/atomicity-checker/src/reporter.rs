@@ -4,14 +4,17 @@
 //! - terminal: Colored terminal output
 //! - markdown: GitHub-flavored Markdown
 //! - json: Machine-readable JSON
+//! - sarif / junit: Shared Git-Core finding model (`gc-report`)
 
 use anyhow::Result;
 use colored::*;
 use serde::Serialize;
 use std::io::Write;
 
-use crate::analyzer::{AtomicityResult, CommitAnalysis};
+use crate::analyzer::{AtomicityResult, CommitAnalysis, Verdict};
 use crate::config::{Config, Mode};
+use crate::git::CommitInfo;
+use crate::conventional::MessageIssue;
 
 /// JSON-serializable result structure
 #[derive(Debug, Serialize)]
@@ -20,6 +23,7 @@ struct JsonResult {
     atomic_commits: usize,
     non_atomic_commits: usize,
     skipped_commits: usize,
+    invalid_messages: usize,
     has_issues: bool,
     mode: String,
     commits: Vec<JsonCommit>,
@@ -31,15 +35,63 @@ struct JsonCommit {
     message: String,
     author: String,
     concerns: Vec<String>,
+    groups: Vec<String>,
     is_atomic: bool,
     skipped: bool,
+    score: f64,
+    mode: Mode,
+    message_issues: Vec<MessageIssue>,
+}
+
+impl JsonCommit {
+    fn from_analysis(a: &CommitAnalysis) -> Self {
+        Self {
+            sha: a.commit.sha.clone(),
+            message: a.commit.message.clone(),
+            author: a.commit.author.clone(),
+            concerns: a.concerns.iter().map(|c| c.to_string()).collect(),
+            groups: a.groups.iter().map(|g| g.to_string()).collect(),
+            is_atomic: a.is_atomic,
+            skipped: a.skipped,
+            score: a.score,
+            mode: a.mode.clone(),
+            message_issues: a.message_issues.clone(),
+        }
+    }
+}
+
+/// Headline for the result: mixed concerns first, then message problems
+fn headline(result: &AtomicityResult) -> &'static str {
+    if result.non_atomic_commits > 0 {
+        "⚠️  Some commits mix multiple concerns"
+    } else if result.invalid_messages > 0 {
+        "✏️  Some commit messages need fixing"
+    } else {
+        "✅ All commits are atomic"
+    }
+}
+
+fn severity(mode: &Mode) -> gc_report::Severity {
+    match mode {
+        Mode::Error => gc_report::Severity::Error,
+        Mode::Warning => gc_report::Severity::Warning,
+    }
+}
+
+/// Rule id of a message issue in the shared finding model
+fn message_rule(issue: &MessageIssue) -> &'static str {
+    match issue.rule {
+        "type-concerns" => "atomicity/type-mismatch",
+        _ => "atomicity/commit-message",
+    }
 }
 
 /// Print result to terminal/stdout
-pub fn print_result(result: &AtomicityResult, format: &str, config: &Config) -> Result<()> {
+pub fn print_result(result: &AtomicityResult, format: &str, config: &Config, config_path: &str) -> Result<()> {
     match format {
         "json" => print_json(result)?,
         "markdown" => print_markdown(result, config)?,
+        "sarif" | "junit" => println!("{}", to_report(result, config, config_path).render(format)?),
         _ => print_terminal(result, config),
     }
     Ok(())
@@ -49,14 +101,7 @@ pub fn print_result(result: &AtomicityResult, format: &str, config: &Config) ->
 pub fn print_commit_analysis(analysis: &CommitAnalysis, format: &str) -> Result<()> {
     match format {
         "json" => {
-            let json = serde_json::to_string_pretty(&JsonCommit {
-                sha: analysis.commit.sha.clone(),
-                message: analysis.commit.message.clone(),
-                author: analysis.commit.author.clone(),
-                concerns: analysis.concerns.iter().map(|c| c.to_string()).collect(),
-                is_atomic: analysis.is_atomic,
-                skipped: analysis.skipped,
-            })?;
+            let json = serde_json::to_string_pretty(&JsonCommit::from_analysis(analysis))?;
             println!("{}", json);
         }
         _ => {
@@ -77,7 +122,7 @@ pub fn print_commit_analysis(analysis: &CommitAnalysis, format: &str) -> Result<
                     concerns.join(", ").dimmed()
                 );
             } else {
-                let concerns: Vec<String> = analysis.concerns.iter().map(|c| c.to_string()).collect();
+                let concerns: Vec<String> = analysis.groups.iter().map(|g| g.to_string()).collect();
                 println!(
                     "{} {} {}",
                     "⚠️".yellow(),
@@ -86,10 +131,13 @@ pub fn print_commit_analysis(analysis: &CommitAnalysis, format: &str) -> Result<
                 );
                 println!(
                     "   └─ Mixes {} concerns: {}",
-                    analysis.concerns.len().to_string().red(),
+                    concerns.len().to_string().red(),
                     concerns.join(", ").yellow()
                 );
             }
+            for issue in &analysis.message_issues {
+                println!("   └─ ✏️ {} {}", issue.message.yellow(), format!("[{}]", issue.rule).dimmed());
+            }
         }
     }
     Ok(())
@@ -101,10 +149,12 @@ pub fn generate_report(
     format: &str,
     output_file: Option<&str>,
     config: &Config,
+    config_path: &str,
 ) -> Result<()> {
     let content = match format {
         "json" => generate_json(result)?,
         "markdown" | "md" => generate_markdown(result, config),
+        "sarif" | "junit" => to_report(result, config, config_path).render(format)?,
         _ => generate_terminal_string(result, config),
     };
 
@@ -126,9 +176,9 @@ fn print_terminal(result: &AtomicityResult, config: &Config) {
     println!();
 
     if result.has_issues {
-        println!("{}", "⚠️  Some commits mix multiple concerns".yellow().bold());
+        println!("{}", headline(result).yellow().bold());
     } else {
-        println!("{}", "✅ All commits are atomic".green().bold());
+        println!("{}", headline(result).green().bold());
     }
 
     println!();
@@ -139,6 +189,9 @@ fn print_terminal(result: &AtomicityResult, config: &Config) {
     println!("   {} Atomic:         {}", "✅".green(), result.atomic_commits);
     println!("   {} Non-atomic:     {}", "⚠️".yellow(), result.non_atomic_commits);
     println!("   ⏭️  Skipped (bots): {}", result.skipped_commits);
+    if config.commit_message.enabled {
+        println!("   ✏️  Invalid messages: {}", result.invalid_messages);
+    }
     println!();
     println!(
         "   Mode: {} | Max concerns: {}",
@@ -174,25 +227,100 @@ fn generate_json(result: &AtomicityResult) -> Result<String> {
         atomic_commits: result.atomic_commits,
         non_atomic_commits: result.non_atomic_commits,
         skipped_commits: result.skipped_commits,
+        invalid_messages: result.invalid_messages,
         has_issues: result.has_issues,
         mode: "check".to_string(),
-        commits: result
-            .analyses
-            .iter()
-            .map(|a| JsonCommit {
-                sha: a.commit.sha.clone(),
-                message: a.commit.message.clone(),
-                author: a.commit.author.clone(),
-                concerns: a.concerns.iter().map(|c| c.to_string()).collect(),
-                is_atomic: a.is_atomic,
-                skipped: a.skipped,
-            })
-            .collect(),
+        commits: result.analyses.iter().map(JsonCommit::from_analysis).collect(),
     };
 
     Ok(serde_json::to_string_pretty(&json_result)?)
 }
 
+/// Map non-atomic commits and message issues to the shared finding model (SARIF / JUnit output)
+///
+/// Commit findings are about the history, not about one of the commit's files;
+/// they point at the config file (`config_path`) since code scanning needs a location.
+/// Severity follows the mode of each commit (path overrides, author modes).
+pub fn to_report(result: &AtomicityResult, config: &Config, config_path: &str) -> gc_report::Report {
+    let findings = result.analyses.iter()
+        .filter(|a| !a.skipped && !a.is_atomic)
+        .map(|a| {
+            let concerns: Vec<String> = a.groups.iter().map(|g| g.to_string()).collect();
+            gc_report::Finding::new(
+                "atomicity/mixed-concerns",
+                severity(&a.mode),
+                format!("{} {} mixes {} concerns: {}", a.commit.short_sha, a.commit.message, concerns.len(), concerns.join(", ")),
+            )
+            .with_fix(format!("Split the commit into one commit per concern ({})", concerns.join(", ")))
+        });
+
+    let message_findings = result.analyses.iter()
+        .flat_map(|a| a.message_issues.iter().map(move |issue| (a, issue)))
+        .map(|(a, issue)| {
+            gc_report::Finding::new(
+                message_rule(issue),
+                severity(&a.mode),
+                format!("{} {}: {}", a.commit.short_sha, a.commit.message, issue.message),
+            )
+        });
+
+    let findings = findings.chain(message_findings)
+        .map(|finding| finding.at(config_path, None));
+
+    let mut report = gc_report::Report::new("atomicity-checker", env!("CARGO_PKG_VERSION"))
+        .with_rule(
+            "atomicity/mixed-concerns",
+            &format!("A commit touches more than {} concern(s)", config.max_concerns),
+        );
+    if config.commit_message.enabled {
+        report = report
+            .with_rule("atomicity/commit-message", "The commit message breaks the Conventional Commit rules")
+            .with_rule("atomicity/type-mismatch", "The commit type does not match the concerns touched");
+    }
+    report.with_findings(findings)
+}
+
+/// Print which rules classified each file of a commit and the resulting verdict
+pub fn print_explanation(commit: &CommitInfo, verdict: &Verdict, format: &str) -> Result<()> {
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(verdict)?);
+        return Ok(());
+    }
+
+    println!("{} {} {}", "🔎".bold(), commit.short_sha.cyan(), commit.message);
+    println!("   Author: {}", commit.author);
+    println!();
+
+    let width = verdict.files.iter().map(|f| f.path.len()).max().unwrap_or(0);
+    for file in &verdict.files {
+        let concern = match &file.concern {
+            Some(concern) => concern.to_string().normal(),
+            None => "ignored".dimmed(),
+        };
+        let package = file.package.as_deref().map(|p| format!(" (package {})", p)).unwrap_or_default();
+        println!("   {:width$}  {:8}  {}{}", file.path, concern, file.rule.dimmed(), package, width = width);
+    }
+    println!();
+
+    println!("   Author rule:   {}", verdict.author_rule.as_deref().unwrap_or("none"));
+    println!("   Path override: {}", verdict.path_override.as_deref().unwrap_or("none"));
+    if verdict.skipped {
+        println!("   Verdict:       {}", "skipped".dimmed());
+        return Ok(());
+    }
+
+    let groups: Vec<String> = verdict.groups.iter().map(|g| g.to_string()).collect();
+    println!("   Concerns:      {}", groups.join(", "));
+    println!("   Score:         {} (max {})", verdict.score, verdict.max_concerns);
+    println!("   Mode:          {:?}", verdict.mode);
+    if verdict.is_atomic() {
+        println!("   Verdict:       {}", "✅ atomic".green());
+    } else {
+        println!("   Verdict:       {}", "⚠️ non-atomic".yellow());
+    }
+    Ok(())
+}
+
 fn print_markdown(result: &AtomicityResult, config: &Config) -> Result<()> {
     let md = generate_markdown(result, config);
     println!("{}", md);
@@ -204,11 +332,8 @@ fn generate_markdown(result: &AtomicityResult, config: &Config) -> String {
 
     md.push_str("## 🔍 Commit Atomicity Check\n\n");
 
-    if result.has_issues {
-        md.push_str("⚠️ **Some commits mix multiple concerns**\n\n");
-    } else {
-        md.push_str("✅ **All commits are atomic**\n\n");
-    }
+    let (icon, text) = headline(result).split_once(' ').unwrap_or(("", ""));
+    md.push_str(&format!("{} **{}**\n\n", icon, text.trim()));
 
     md.push_str("### 📊 Summary\n\n");
     md.push_str("| Metric | Count |\n");
@@ -217,6 +342,9 @@ fn generate_markdown(result: &AtomicityResult, config: &Config) -> String {
     md.push_str(&format!("| ✅ Atomic | {} |\n", result.atomic_commits));
     md.push_str(&format!("| ⚠️ Non-atomic | {} |\n", result.non_atomic_commits));
     md.push_str(&format!("| ⏭️ Skipped (bots) | {} |\n", result.skipped_commits));
+    if config.commit_message.enabled {
+        md.push_str(&format!("| ✏️ Invalid messages | {} |\n", result.invalid_messages));
+    }
     md.push_str("\n");
 
     if !result.analyses.is_empty() {
@@ -234,12 +362,17 @@ fn generate_markdown(result: &AtomicityResult, config: &Config) -> String {
                 "⚠️"
             };
 
+            let issues: String = analysis.message_issues.iter()
+                .map(|i| format!("<br>✏️ {}", i.message.replace('|', "\\|")))
+                .collect();
+
             md.push_str(&format!(
-                "| `{}` | {} | {} {} |\n",
+                "| `{}` | {} | {} {}{} |\n",
                 analysis.commit.short_sha,
                 analysis.commit.message.replace('|', "\\|"),
                 status,
-                concerns.join(", ")
+                concerns.join(", "),
+                issues
             ));
         }
         md.push_str("\n");
@@ -263,11 +396,8 @@ fn generate_terminal_string(result: &AtomicityResult, config: &Config) -> String
 
     output.push_str(&format!("\n"));
 
-    if result.has_issues {
-        output.push_str("⚠️  Some commits mix multiple concerns\n");
-    } else {
-        output.push_str("✅ All commits are atomic\n");
-    }
+    output.push_str(headline(result));
+    output.push('\n');
 
     output.push_str(&format!("\n{}\n", "━".repeat(50)));
     output.push_str("📊 Summary\n");
@@ -276,6 +406,9 @@ fn generate_terminal_string(result: &AtomicityResult, config: &Config) -> String
     output.push_str(&format!("   ✅ Atomic:         {}\n", result.atomic_commits));
     output.push_str(&format!("   ⚠️ Non-atomic:     {}\n", result.non_atomic_commits));
     output.push_str(&format!("   ⏭️  Skipped (bots): {}\n", result.skipped_commits));
+    if config.commit_message.enabled {
+        output.push_str(&format!("   ✏️  Invalid messages: {}\n", result.invalid_messages));
+    }
     output.push_str(&format!(
         "\n   Mode: {:?} | Max concerns: {}\n\n",
         config.mode, config.max_concerns
@@ -287,6 +420,7 @@ fn generate_terminal_string(result: &AtomicityResult, config: &Config) -> String
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analyzer::ConcernGroup;
     use crate::config::Concern;
     use crate::git::CommitInfo;
     use std::collections::HashSet;
@@ -300,19 +434,26 @@ mod tests {
             atomic_commits: 2,
             non_atomic_commits: 1,
             skipped_commits: 0,
+            invalid_messages: 0,
             has_issues: true,
+            should_fail: false,
             analyses: vec![CommitAnalysis {
                 commit: CommitInfo {
                     sha: "abc12345".to_string(),
                     short_sha: "abc12345".to_string(),
                     author: "developer".to_string(),
                     message: "feat: add feature".to_string(),
+                    body: String::new(),
                     files: vec!["src/main.rs".to_string()],
                 },
                 concerns,
+                groups: vec![ConcernGroup { package: None, concerns: vec![Concern::Source] }],
                 is_atomic: true,
                 skipped: false,
                 skip_reason: None,
+                message_issues: Vec::new(),
+                score: 1.0,
+                mode: Mode::Warning,
             }],
         }
     }
@@ -333,4 +474,42 @@ mod tests {
         assert!(md.contains("## 🔍 Commit Atomicity Check"));
         assert!(md.contains("| Total commits | 3 |"));
     }
+
+    #[test]
+    fn test_non_atomic_commits_become_findings() {
+        let mut result = sample_result();
+        let mut mixed = result.analyses[0].clone();
+        mixed.commit.files = vec!["src/lib.rs".to_string(), "README.md".to_string()];
+        mixed.concerns.insert(Concern::Docs);
+        mixed.groups.insert(0, ConcernGroup { package: None, concerns: vec![Concern::Docs] });
+        mixed.is_atomic = false;
+        mixed.mode = Mode::Error;
+        result.analyses.push(mixed);
+        let config = Config::default();
+
+        let report = to_report(&result, &config, ".github/atomicity-config.yml");
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].severity, gc_report::Severity::Error);
+        assert!(report.findings[0].message.ends_with("mixes 2 concerns: docs, source"));
+        assert_eq!(report.findings[0].location.as_ref().unwrap().path, ".github/atomicity-config.yml");
+        assert!(report.to_junit().contains("failures=\"1\""));
+    }
+
+    #[test]
+    fn test_message_issues_become_findings() {
+        let mut result = sample_result();
+        result.analyses[0].message_issues = vec![MessageIssue {
+            rule: "type-concerns",
+            message: "`docs` commit touches source (only docs expected)".to_string(),
+        }];
+        result.invalid_messages = 1;
+        let mut config = Config::default();
+        config.commit_message.enabled = true;
+
+        let report = to_report(&result, &config, ".github/atomicity-config.yml");
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].rule_id, "atomicity/type-mismatch");
+        assert!(report.findings[0].location.is_some());
+        assert!(generate_markdown(&result, &config).contains("| ✏️ Invalid messages | 1 |"));
+    }
 }
@@ -0,0 +1,555 @@
+//! Commit splitting.
+//!
+//! Rewrites non-atomic commits into one commit per concern, using the same
+//! categorization as the checker. History is rebuilt with git plumbing
+//! (`read-tree`, `update-index`, `write-tree`, `commit-tree`) in a scratch
+//! index, so the working tree and the real index are never touched: the last
+//! rewritten commit has exactly the tree of the old head.
+//!
+//! - Parts keep the author, author email and author date of the original commit.
+//! - Messages are Conventional Commits derived from the original subject, each
+//!   with a `Split-from:` trailer.
+//! - With workspace scoping, a commit is split per package first; parts of a
+//!   package without a scope in the original subject get the package as scope.
+//! - The old head is saved under a backup ref before `HEAD` moves.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tracing::info;
+
+use crate::analyzer::{categorize_file, evaluate};
+use crate::config::{Concern, Config};
+use crate::conventional;
+use crate::git::{run_git, run_git_with_env, run_git_with_input};
+use crate::workspace::{Workspace, WorkspaceLoader};
+
+/// Order of the parts of a split commit: dependencies first, docs last
+const SPLIT_ORDER: [Concern; 6] = [
+    Concern::Config,
+    Concern::Source,
+    Concern::Tests,
+    Concern::Infra,
+    Concern::Docs,
+    Concern::Other,
+];
+
+/// Prefix of the backup refs written before rewriting
+pub const BACKUP_REF_PREFIX: &str = "refs/atomicity/backup";
+
+/// One commit of a split
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitPart {
+    pub concern: Concern,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    pub files: Vec<String>,
+    pub message: String,
+}
+
+/// What happens to one commit of the range
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitPlan {
+    pub sha: String,
+    pub short_sha: String,
+    pub subject: String,
+    /// New commits replacing this one; empty when it is kept as is
+    pub parts: Vec<SplitPart>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitPlan {
+    /// Parent of the first rewritten commit
+    pub base: String,
+    /// Head the plan was made for
+    pub head: String,
+    pub commits: Vec<CommitPlan>,
+}
+
+impl SplitPlan {
+    /// Number of commits that will be split
+    pub fn split_count(&self) -> usize {
+        self.commits.iter().filter(|c| !c.parts.is_empty()).count()
+    }
+}
+
+/// Group changed files by concern in split order
+///
+/// Ignored files (lock files, ...) follow the first group, so a lock file lands
+/// in the same commit as the manifest or code that changed it. Concerns of an
+/// allowed combination stay together under the first of them.
+pub fn group_files(files: &[String], config: &Config) -> Vec<(Concern, Vec<String>)> {
+    let mut by_concern: HashMap<Concern, Vec<String>> = HashMap::new();
+    let mut ignored = Vec::new();
+    for file in files {
+        if config.should_ignore_file(file) {
+            ignored.push(file.clone());
+        } else {
+            by_concern.entry(categorize_file(file, config)).or_default().push(file.clone());
+        }
+    }
+
+    let mut groups: Vec<(Concern, Vec<String>)> = SPLIT_ORDER.iter()
+        .filter_map(|concern| by_concern.remove(concern).map(|files| (concern.clone(), files)))
+        .collect();
+    for combination in &config.allowed_combinations {
+        let mut members = groups.iter().enumerate().filter(|(_, (c, _))| combination.contains(c)).map(|(i, _)| i);
+        let Some(first) = members.next() else { continue };
+        let rest: Vec<usize> = members.collect();
+        for index in rest.into_iter().rev() {
+            let (_, files) = groups.remove(index);
+            groups[first].1.extend(files);
+        }
+    }
+    if let Some((_, files)) = groups.first_mut() {
+        files.extend(ignored);
+    }
+    groups
+}
+
+/// Group changed files by package, then by concern within each package
+///
+/// Packages come in name order, files outside any package last. Ignored files
+/// of a package without other changes join the first part.
+pub fn group_package_files(files: &[String], config: &Config, workspace: &Workspace) -> Vec<(Option<String>, Concern, Vec<String>)> {
+    let mut by_package: BTreeMap<(bool, Option<String>), Vec<String>> = BTreeMap::new();
+    for file in files {
+        let package = workspace.package_of(file).map(|p| p.name.clone());
+        by_package.entry((package.is_none(), package)).or_default().push(file.clone());
+    }
+
+    let mut parts = Vec::new();
+    let mut leftovers = Vec::new();
+    for ((_, package), files) in by_package {
+        let groups = group_files(&files, config);
+        if groups.is_empty() {
+            leftovers.extend(files);
+        }
+        parts.extend(groups.into_iter().map(|(concern, files)| (package.clone(), concern, files)));
+    }
+    if let Some((_, _, files)) = parts.first_mut() {
+        files.extend(leftovers);
+    }
+    parts
+}
+
+/// Conventional Commit type for the part of a split commit
+fn part_type<'a>(concern: &Concern, original: Option<&'a str>) -> &'a str {
+    match concern {
+        Concern::Tests => "test",
+        Concern::Docs => "docs",
+        Concern::Infra => "ci",
+        Concern::Config | Concern::Other => "chore",
+        Concern::Source => match original {
+            Some(t) if !matches!(t, "test" | "docs" | "ci" | "chore" | "style" | "build") => t,
+            _ => "feat",
+        },
+    }
+}
+
+/// Message of one part: the original subject retyped for its concern
+///
+/// The original body goes with the first part.
+pub fn part_message(message: &str, concern: &Concern, package: Option<&str>, first: bool, sha: &str) -> String {
+    let (subject, body) = message.split_once('\n').unwrap_or((message, ""));
+    let subject = subject.trim();
+
+    let parsed = conventional::parse(subject);
+    let (original_type, scope, breaking, description) = match &parsed {
+        Some(c) => (Some(c.kind.as_str()), c.scope.as_deref(), c.breaking, c.description.as_str()),
+        None => (None, None, false, subject),
+    };
+
+    let mut msg = part_type(concern, original_type).to_string();
+    if let Some(scope) = scope.filter(|s| !s.is_empty()).or(package) {
+        msg.push_str(&format!("({})", scope));
+    }
+    if breaking && *concern == Concern::Source {
+        msg.push('!');
+    }
+    msg.push_str(&format!(": {}", description));
+
+    let body = body.trim();
+    if first && !body.is_empty() {
+        msg.push_str(&format!("\n\n{}", body));
+    }
+    msg.push_str(&format!("\n\nSplit-from: {}", sha));
+    msg
+}
+
+/// A path changed by a commit, with its new mode and blob (`000000` mode when deleted)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChangedEntry {
+    path: String,
+    mode: String,
+    blob: String,
+}
+
+/// Paths changed by a commit against its parent
+///
+/// Uses `-z` so paths are never C-quoted (`src/café.rs` stays as is).
+async fn changed_entries(repo: &Path, sha: &str) -> Result<Vec<ChangedEntry>> {
+    let raw = run_git(repo, &["diff-tree", "-r", "-z", "--no-commit-id", "--no-renames", sha]).await?;
+    parse_raw_diff(&raw)
+}
+
+/// Parse `diff-tree -r -z` output: `:old new old_sha new_sha status\0path\0`
+fn parse_raw_diff(raw: &str) -> Result<Vec<ChangedEntry>> {
+    let mut tokens = raw.split('\0').filter(|t| !t.is_empty());
+    let mut entries = Vec::new();
+    while let Some(meta) = tokens.next() {
+        let path = tokens.next().with_context(|| format!("Missing path after {:?}", meta))?;
+        let fields: Vec<&str> = meta.trim_start_matches(':').split_whitespace().collect();
+        if fields.len() < 5 {
+            bail!("Unexpected diff-tree record {:?}", meta);
+        }
+        entries.push(ChangedEntry {
+            path: path.to_string(),
+            mode: fields[1].to_string(),
+            blob: fields[3].to_string(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Plan the split of `base..HEAD`
+///
+/// With `only`, just that commit is split and the others are replayed as is.
+pub async fn plan_split<P: AsRef<Path>>(
+    repo_path: P,
+    base: &str,
+    only: Option<&str>,
+    config: &Config,
+) -> Result<SplitPlan> {
+    let repo = repo_path.as_ref();
+    let head = run_git(repo, &["rev-parse", "HEAD"]).await?;
+    let only = match only {
+        Some(commit) => Some(run_git(repo, &["rev-parse", "--verify", &format!("{}^{{commit}}", commit)]).await?),
+        None => None,
+    };
+
+    let mut workspaces = WorkspaceLoader::new(repo, &config.workspace).await?;
+
+    let list = run_git(repo, &["rev-list", "--reverse", "--parents", &format!("{}..HEAD", base)]).await?;
+    let mut commits = Vec::new();
+    let mut range_base = None;
+    let mut previous: Option<String> = None;
+
+    for line in list.lines().filter(|l| !l.is_empty()) {
+        let ids: Vec<&str> = line.split_whitespace().collect();
+        let (sha, parents) = (ids[0], &ids[1..]);
+        match parents {
+            [parent] => {
+                if let Some(prev) = &previous {
+                    if prev != parent {
+                        bail!("History between {} and HEAD is not linear at {}", base, &sha[..8]);
+                    }
+                } else {
+                    range_base = Some(parent.to_string());
+                }
+            }
+            [] => bail!("Cannot split the root commit {}", &sha[..8]),
+            _ => bail!("Cannot split across merge commit {}", &sha[..8]),
+        }
+        previous = Some(sha.to_string());
+
+        let message = run_git(repo, &["log", "-1", "--format=%B", sha]).await?;
+        let author = run_git(repo, &["log", "-1", "--format=%an", sha]).await?;
+        let files: Vec<String> = changed_entries(repo, sha).await?.into_iter().map(|e| e.path).collect();
+
+        let selected = only.as_deref().is_none_or(|o| o == sha);
+        let workspace = workspaces.at(Some(sha)).await?;
+        let verdict = evaluate(&author, &files, config, &workspace);
+        let parts = if selected && !verdict.is_atomic() {
+            group_package_files(&files, config, &workspace).into_iter()
+                .enumerate()
+                .map(|(i, (package, concern, files))| SplitPart {
+                    message: part_message(&message, &concern, package.as_deref(), i == 0, sha),
+                    concern,
+                    package,
+                    files,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        commits.push(CommitPlan {
+            sha: sha.to_string(),
+            short_sha: sha.chars().take(8).collect(),
+            subject: message.lines().next().unwrap_or_default().to_string(),
+            parts,
+        });
+    }
+
+    if let Some(only) = &only {
+        if !commits.iter().any(|c| &c.sha == only) {
+            bail!("Commit {} is not in {}..HEAD", &only[..8], base);
+        }
+    }
+
+    Ok(SplitPlan {
+        base: range_base.unwrap_or_else(|| head.clone()),
+        head,
+        commits,
+    })
+}
+
+/// Default backup ref for the current branch
+pub async fn default_backup_ref<P: AsRef<Path>>(repo_path: P) -> Result<String> {
+    let branch = run_git(repo_path, &["rev-parse", "--abbrev-ref", "HEAD"]).await?;
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    Ok(format!("{}/{}-{}", BACKUP_REF_PREFIX, branch, stamp))
+}
+
+/// Rewrite history according to `plan`; returns the new head
+///
+/// The old head is saved as `backup_ref` first. Refuses to run when `HEAD`
+/// moved since planning or tracked files have uncommitted changes.
+pub async fn apply_split<P: AsRef<Path>>(repo_path: P, plan: &SplitPlan, backup_ref: &str) -> Result<String> {
+    let repo = repo_path.as_ref();
+    if plan.split_count() == 0 {
+        info!("✅ Nothing to split");
+        return Ok(plan.head.clone());
+    }
+
+    let head = run_git(repo, &["rev-parse", "HEAD"]).await?;
+    if head != plan.head {
+        bail!("HEAD moved since the split was planned");
+    }
+    if !run_git(repo, &["status", "--porcelain", "--untracked-files=no"]).await?.is_empty() {
+        bail!("Uncommitted changes to tracked files; commit or stash them first");
+    }
+
+    run_git(repo, &["update-ref", backup_ref, &plan.head]).await?;
+    info!("💾 Backup of {} saved as {}", &plan.head[..8], backup_ref);
+
+    let index = run_git(repo, &["rev-parse", "--git-path", "atomicity-split.index"]).await?;
+    let result = rewrite(repo, plan, &index).await;
+    let _ = std::fs::remove_file(repo.join(&index));
+    let new_head = result?;
+
+    // The split must only regroup changes, never lose or add any
+    let new_tree = run_git(repo, &["rev-parse", &format!("{}^{{tree}}", new_head)]).await?;
+    let old_tree = run_git(repo, &["rev-parse", &format!("{}^{{tree}}", plan.head)]).await?;
+    if new_tree != old_tree {
+        bail!(
+            "Split result {} has a different tree than {}; HEAD left unchanged (backup in {})",
+            &new_head[..8], &plan.head[..8], backup_ref
+        );
+    }
+
+    run_git(repo, &["update-ref", "-m", "atomicity-checker: split", "HEAD", &new_head, &plan.head]).await?;
+    info!("✂️ Split {} commit(s); HEAD is now {}", plan.split_count(), &new_head[..8]);
+    Ok(new_head)
+}
+
+async fn rewrite(repo: &Path, plan: &SplitPlan, index: &str) -> Result<String> {
+    let mut parent = plan.base.clone();
+
+    for commit in &plan.commits {
+        let identity = run_git(repo, &["log", "-1", "--format=%an%x00%ae%x00%aI", &commit.sha]).await?;
+        let mut fields = identity.split('\0');
+        let (Some(name), Some(email), Some(date)) = (fields.next(), fields.next(), fields.next()) else {
+            bail!("Cannot read the author of {}", commit.short_sha);
+        };
+        let author = [("GIT_AUTHOR_NAME", name), ("GIT_AUTHOR_EMAIL", email), ("GIT_AUTHOR_DATE", date)];
+
+        if commit.parts.is_empty() {
+            let message = run_git(repo, &["log", "-1", "--format=%B", &commit.sha]).await?;
+            let tree = format!("{}^{{tree}}", commit.sha);
+            parent = run_git_with_env(repo, &["commit-tree", &tree, "-p", &parent, "-m", &message], &author).await?;
+            continue;
+        }
+
+        let scratch = [("GIT_INDEX_FILE", index)];
+        run_git_with_env(repo, &["read-tree", &parent], &scratch).await?;
+        let entries = changed_entries(repo, &commit.sha).await?;
+        for part in &commit.parts {
+            // Deleted paths carry mode 0, which makes `--index-info` remove them
+            let mut info = Vec::new();
+            for file in &part.files {
+                let entry = entries.iter().find(|e| &e.path == file)
+                    .with_context(|| format!("{} is not changed by {}", file, commit.short_sha))?;
+                info.extend_from_slice(format!("{} {}\t{}\0", entry.mode, entry.blob, entry.path).as_bytes());
+            }
+            run_git_with_input(repo, &["update-index", "-z", "--index-info"], &scratch, &info).await?;
+            let tree = run_git_with_env(repo, &["write-tree"], &scratch).await
+                .with_context(|| format!("Failed to write the {} part of {}", part.concern, commit.short_sha))?;
+            parent = run_git_with_env(repo, &["commit-tree", &tree, "-p", &parent, "-m", &part.message], &author).await?;
+        }
+    }
+
+    Ok(parent)
+}
+
+/// Print a split plan
+pub fn print_plan(plan: &SplitPlan, format: &str) -> Result<()> {
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(plan)?);
+        return Ok(());
+    }
+
+    use colored::*;
+    if plan.split_count() == 0 {
+        println!("{}", "✅ No commit to split".green());
+        return Ok(());
+    }
+    for commit in &plan.commits {
+        if commit.parts.is_empty() {
+            println!("   {} {}", commit.short_sha.dimmed(), commit.subject.dimmed());
+            continue;
+        }
+        println!("✂️  {} {}", commit.short_sha.cyan(), commit.subject);
+        for part in &commit.parts {
+            let subject = part.message.lines().next().unwrap_or_default();
+            let label = match &part.package {
+                Some(package) => format!("{}:{}", package, part.concern),
+                None => part.concern.to_string(),
+            };
+            println!("   └─ {:<7} {} ({} file(s))", label.yellow(), subject, part.files.len());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn git(repo: &Path, args: &[&str]) -> String {
+        run_git(repo, args).await.unwrap()
+    }
+
+    async fn commit(repo: &Path, files: &[(&str, &str)], message: &str) {
+        for (path, content) in files {
+            let path = repo.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        git(repo, &["add", "-A"]).await;
+        run_git_with_env(repo, &["commit", "-q", "-m", message], &[("GIT_AUTHOR_DATE", "2025-01-02T03:04:05+00:00")])
+            .await
+            .unwrap();
+    }
+
+    async fn test_repo(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("atomicity-split-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]).await;
+        git(&dir, &["config", "user.name", "Dev"]).await;
+        git(&dir, &["config", "user.email", "dev@example.com"]).await;
+        dir
+    }
+
+    #[test]
+    fn test_part_messages_follow_concerns() {
+        let message = "feat(parser)!: support nested groups\n\nCloses #12";
+        assert_eq!(
+            part_message(message, &Concern::Source, None, true, "abc"),
+            "feat(parser)!: support nested groups\n\nCloses #12\n\nSplit-from: abc"
+        );
+        assert_eq!(
+            part_message(message, &Concern::Docs, Some("core"), false, "abc"),
+            "docs(parser): support nested groups\n\nSplit-from: abc"
+        );
+        assert_eq!(part_message("Update things", &Concern::Tests, None, false, "abc"), "test: Update things\n\nSplit-from: abc");
+        assert_eq!(part_message("Update things", &Concern::Source, Some("core"), false, "abc"), "feat(core): Update things\n\nSplit-from: abc");
+
+        let files = vec!["README.md".to_string(), "Cargo.lock".to_string(), "src/lib.rs".to_string(), "Cargo.toml".to_string()];
+        let groups = group_files(&files, &Config::default());
+        let concerns: Vec<&Concern> = groups.iter().map(|(c, _)| c).collect();
+        assert_eq!(concerns, vec![&Concern::Config, &Concern::Source, &Concern::Docs]);
+        assert_eq!(groups[0].1, vec!["Cargo.toml".to_string(), "Cargo.lock".to_string()]);
+
+        let config = Config {
+            allowed_combinations: vec![vec![Concern::Source, Concern::Tests]],
+            ..Config::default()
+        };
+        let files = vec!["tests/lib.rs".to_string(), "README.md".to_string(), "src/lib.rs".to_string()];
+        let groups = group_files(&files, &config);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], (Concern::Source, vec!["src/lib.rs".to_string(), "tests/lib.rs".to_string()]));
+
+        let workspace = Workspace::new(vec![crate::workspace::Package {
+            path: "crates/core".to_string(),
+            name: "core".to_string(),
+            kind: crate::workspace::PackageKind::Cargo,
+        }]);
+        let files = vec!["crates/core/src/lib.rs".to_string(), "src/main.rs".to_string(), "crates/core/Cargo.lock".to_string()];
+        let parts: Vec<(Option<String>, Concern, usize)> = group_package_files(&files, &config, &workspace)
+            .into_iter()
+            .map(|(package, concern, files)| (package, concern, files.len()))
+            .collect();
+        assert_eq!(parts, vec![(Some("core".to_string()), Concern::Source, 2), (None, Concern::Source, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_split_rewrites_mixed_commit_and_keeps_tree() {
+        let repo = test_repo("rewrite").await;
+        commit(&repo, &[("src/lib.rs", "fn a() {}\n"), ("README.md", "# Demo\n")], "chore: init").await;
+        let base = git(&repo, &["rev-parse", "HEAD"]).await;
+        commit(&repo, &[("src/lib.rs", "fn a() {}\nfn b() {}\n"), ("README.md", "# Demo\nb\n"), ("tests/b.rs", "#[test] fn b() {}\n")], "feat: add b").await;
+        std::fs::remove_file(repo.join("README.md")).unwrap();
+        commit(&repo, &[("docs/guide.md", "guide\n")], "docs: move readme to guide").await;
+        let old_head = git(&repo, &["rev-parse", "HEAD"]).await;
+        let old_tree = git(&repo, &["rev-parse", "HEAD^{tree}"]).await;
+
+        let config = Config::default();
+        let plan = plan_split(&repo, &base, None, &config).await.unwrap();
+        assert_eq!(plan.base, base);
+        assert_eq!(plan.split_count(), 1);
+        assert_eq!(plan.commits[0].parts.len(), 3);
+        // The rename commit only touches docs
+        assert!(plan.commits[1].parts.is_empty());
+
+        let backup = format!("{}/test", BACKUP_REF_PREFIX);
+        let new_head = apply_split(&repo, &plan, &backup).await.unwrap();
+
+        assert_eq!(git(&repo, &["rev-parse", &backup]).await, old_head);
+        assert_eq!(git(&repo, &["rev-parse", "HEAD^{tree}"]).await, old_tree);
+        assert_eq!(
+            git(&repo, &["log", "--format=%s", &format!("{}..{}", base, new_head)]).await,
+            "docs: move readme to guide\ndocs: add b\ntest: add b\nfeat: add b"
+        );
+        assert_eq!(git(&repo, &["log", "-1", "--format=%aI %an", "HEAD~2"]).await, "2025-01-02T03:04:05+00:00 Dev");
+        assert!(git(&repo, &["status", "--porcelain"]).await.is_empty());
+
+        let _ = std::fs::remove_dir_all(&repo);
+    }
+
+    #[tokio::test]
+    async fn test_split_keeps_non_ascii_paths() {
+        let repo = test_repo("unicode").await;
+        commit(&repo, &[("src/lib.rs", "fn a() {}\n"), ("old café.txt", "x\n")], "chore: init").await;
+        let base = git(&repo, &["rev-parse", "HEAD"]).await;
+        std::fs::remove_file(repo.join("old café.txt")).unwrap();
+        commit(&repo, &[("src/café.rs", "fn b() {}\n"), ("Cargo.toml", "[package]\n")], "feat: add café").await;
+        let old_tree = git(&repo, &["rev-parse", "HEAD^{tree}"]).await;
+
+        let plan = plan_split(&repo, &base, None, &Config::default()).await.unwrap();
+        assert!(plan.commits[0].parts.iter().any(|p| p.files.contains(&"src/café.rs".to_string())));
+        apply_split(&repo, &plan, &format!("{}/test", BACKUP_REF_PREFIX)).await.unwrap();
+
+        assert_eq!(git(&repo, &["rev-parse", "HEAD^{tree}"]).await, old_tree);
+        for part in ["HEAD", "HEAD~1", "HEAD~2"] {
+            let changed = git(&repo, &["diff-tree", "-r", "--no-commit-id", "--name-only", part]).await;
+            assert!(!changed.is_empty(), "{} is an empty commit", part);
+        }
+
+        let _ = std::fs::remove_dir_all(&repo);
+    }
+
+    #[test]
+    fn test_parse_raw_diff() {
+        let raw = ":100644 100644 aaa bbb M\0src/café.rs\0:100644 000000 ccc 0000 D\0gone.txt\0";
+        let entries = parse_raw_diff(raw).unwrap();
+        assert_eq!(entries[0], ChangedEntry { path: "src/café.rs".into(), mode: "100644".into(), blob: "bbb".into() });
+        assert_eq!((entries[1].mode.as_str(), entries[1].path.as_str()), ("000000", "gone.txt"));
+    }
+}
@@ -0,0 +1,521 @@
+//! Workspace package detection for concern scoping.
+//!
+//! Packages are found from the manifests of the analyzed commit (or of the
+//! index, for the pre-commit hook), not from the working tree:
+//!
+//! | Manifest | Package when |
+//! |----------|--------------|
+//! | `Cargo.toml` | It has a `[package]` section and is a member of the enclosing `[workspace]` (`members` globs minus `exclude`) |
+//! | `package.json` | It is matched by the enclosing root's `workspaces` or `pnpm-workspace.yaml` `packages` globs |
+//! | `pyproject.toml`, `setup.py`, `setup.cfg` | Always |
+//!
+//! Manifests outside any workspace root are packages of their own; manifests
+//! under a root that does not list them (fixtures, examples) are not packages.
+//!
+//! With `workspace.enabled`, each file belongs to the innermost package
+//! containing it, and the same concern in two packages counts twice.
+
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::config::WorkspaceConfig;
+use crate::git::run_git;
+
+/// File names whose content decides the packages of a tree
+const MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pnpm-workspace.yaml",
+    "pyproject.toml",
+    "setup.py",
+    "setup.cfg",
+];
+
+/// Ecosystem of a package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageKind {
+    Cargo,
+    Npm,
+    Python,
+    /// Listed in `workspace.packages`
+    Configured,
+}
+
+/// A workspace package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Package {
+    /// Directory relative to the repository root ("" for the root)
+    pub path: String,
+    pub name: String,
+    pub kind: PackageKind,
+}
+
+/// Packages of a repository, empty when scoping is disabled
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    /// Sorted deepest first so the innermost package matches
+    packages: Vec<Package>,
+}
+
+impl Workspace {
+    /// Build a workspace from packages
+    pub fn new(mut packages: Vec<Package>) -> Self {
+        packages.sort_by(|a, b| b.path.len().cmp(&a.path.len()).then(a.path.cmp(&b.path)));
+        packages.dedup_by(|a, b| a.path == b.path);
+        Self { packages }
+    }
+
+    /// Detect packages of a commit (`None`: the index) according to the config
+    pub async fn load<P: AsRef<Path>>(repo_path: P, config: &WorkspaceConfig, rev: Option<&str>) -> Result<Self> {
+        WorkspaceLoader::new(repo_path, config).await?.at(rev).await
+    }
+
+    /// Innermost package containing a file
+    pub fn package_of(&self, file: &str) -> Option<&Package> {
+        self.packages.iter().find(|p| {
+            p.path.is_empty() || file.strip_prefix(&p.path).is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+}
+
+/// Loads the workspace of each analyzed commit
+///
+/// Commits whose manifests are unchanged share one detection, and each
+/// manifest blob is read once.
+pub struct WorkspaceLoader {
+    root: PathBuf,
+    config: WorkspaceConfig,
+    /// Workspaces by the manifests (path and blob) they were detected from
+    by_manifests: HashMap<Vec<(String, String)>, Workspace>,
+    blobs: HashMap<String, String>,
+}
+
+impl WorkspaceLoader {
+    pub async fn new<P: AsRef<Path>>(repo_path: P, config: &WorkspaceConfig) -> Result<Self> {
+        let root = if config.enabled {
+            PathBuf::from(run_git(repo_path.as_ref(), &["rev-parse", "--show-toplevel"]).await?)
+        } else {
+            repo_path.as_ref().to_path_buf()
+        };
+        Ok(Self { root, config: config.clone(), by_manifests: HashMap::new(), blobs: HashMap::new() })
+    }
+
+    /// Packages of the tree of `rev`, or of the index when `None`
+    pub async fn at(&mut self, rev: Option<&str>) -> Result<Workspace> {
+        if !self.config.enabled {
+            return Ok(Workspace::default());
+        }
+
+        let manifests = self.manifests(rev).await?;
+        if let Some(workspace) = self.by_manifests.get(&manifests) {
+            return Ok(workspace.clone());
+        }
+
+        for (_, blob) in &manifests {
+            if !self.blobs.contains_key(blob) {
+                let content = run_git(&self.root, &["cat-file", "blob", blob]).await?;
+                self.blobs.insert(blob.clone(), content);
+            }
+        }
+        let blob_of: HashMap<&str, &str> = manifests.iter().map(|(path, blob)| (path.as_str(), blob.as_str())).collect();
+        let mut packages: Vec<Package> = self.config.packages.iter()
+            .map(|path| {
+                let path = path.trim_end_matches('/').to_string();
+                Package { name: dir_name(&path), path, kind: PackageKind::Configured }
+            })
+            .collect();
+        packages.extend(detect(manifests.iter().map(|(path, _)| path.as_str()), |path| {
+            blob_of.get(path).and_then(|blob| self.blobs.get(*blob)).cloned()
+        }));
+
+        let workspace = Workspace::new(packages);
+        tracing::info!("📦 Workspace scoping at {}: {} package(s)", rev.unwrap_or("index"), workspace.packages().len());
+        for package in workspace.packages() {
+            tracing::debug!("   {} ({:?}) at {}/", package.name, package.kind, package.path);
+        }
+        self.by_manifests.insert(manifests, workspace.clone());
+        Ok(workspace)
+    }
+
+    /// Manifest paths and blob ids of a tree, sorted by path
+    async fn manifests(&self, rev: Option<&str>) -> Result<Vec<(String, String)>> {
+        // -z keeps non-ASCII paths unquoted
+        let listing = match rev {
+            Some(rev) => run_git(&self.root, &["ls-tree", "-r", "-z", "--full-tree", rev]).await?,
+            None => run_git(&self.root, &["ls-files", "-s", "-z"]).await?,
+        };
+
+        // "<mode> <type> <blob>\t<path>" (ls-tree) or "<mode> <blob> <stage>\t<path>" (ls-files -s)
+        let mut manifests: Vec<(String, String)> = listing.split('\0')
+            .filter_map(|entry| {
+                let (info, path) = entry.split_once('\t')?;
+                let blob = match rev {
+                    Some(_) => info.split(' ').nth(2)?,
+                    None => info.split(' ').nth(1)?,
+                };
+                let name = path.rsplit('/').next().unwrap_or(path);
+                MANIFESTS.contains(&name).then(|| (path.to_string(), blob.to_string()))
+            })
+            .collect();
+        manifests.sort();
+        manifests.dedup_by(|a, b| a.0 == b.0);
+        Ok(manifests)
+    }
+}
+
+/// Member globs of a workspace root, relative to the root
+#[derive(Debug, Default)]
+struct Members {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl Members {
+    /// npm and pnpm style: `!` negates a glob
+    fn from_globs<'a>(globs: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut members = Self::default();
+        for glob in globs {
+            match glob.strip_prefix('!') {
+                Some(negated) => members.exclude.extend(glob_regex(negated)),
+                None => members.include.extend(glob_regex(glob)),
+            }
+        }
+        members
+    }
+
+    /// Cargo style: `exclude` lists directories, excluded with everything below them
+    fn from_cargo(members: &[String], exclude: &[String]) -> Self {
+        Self {
+            include: members.iter().filter_map(|glob| glob_regex(glob)).collect(),
+            exclude: exclude.iter()
+                .flat_map(|dir| [glob_regex(dir), glob_regex(&format!("{}/**", normalize_glob(dir)))])
+                .flatten()
+                .collect(),
+        }
+    }
+
+    fn contains(&self, dir: &str) -> bool {
+        self.include.iter().any(|r| r.is_match(dir)) && !self.exclude.iter().any(|r| r.is_match(dir))
+    }
+}
+
+/// Find packages among manifest files; `read` returns a manifest's content
+pub fn detect<'a>(files: impl Iterator<Item = &'a str>, read: impl Fn(&str) -> Option<String>) -> Vec<Package> {
+    let files: Vec<&str> = files.collect();
+
+    // Workspace roots by directory, with the member globs they declare
+    let mut cargo_roots: BTreeMap<&str, Members> = BTreeMap::new();
+    let mut npm_roots: BTreeMap<&str, Members> = BTreeMap::new();
+    for file in &files {
+        if let Some(dir) = split_manifest(file, "Cargo.toml") {
+            let content = read(file).unwrap_or_default();
+            if has_section(&content, "workspace") {
+                let members = section_array(&content, "workspace", "members");
+                let exclude = section_array(&content, "workspace", "exclude");
+                cargo_roots.insert(dir, Members::from_cargo(&members, &exclude));
+            }
+        } else if let Some(dir) = split_manifest(file, "package.json") {
+            let json: serde_json::Value = read(file)
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            // `workspaces` is a list of globs, or `{ "packages": [...] }` (yarn)
+            let workspaces = json.get("workspaces").map(|w| w.get("packages").unwrap_or(w));
+            if let Some(workspaces) = workspaces {
+                let globs = workspaces.as_array().into_iter().flatten().filter_map(|g| g.as_str());
+                npm_roots.entry(dir).or_default().include.extend(Members::from_globs(globs).include);
+            }
+        } else if let Some(dir) = split_manifest(file, "pnpm-workspace.yaml") {
+            let yaml: serde_yaml::Value = read(file)
+                .and_then(|content| serde_yaml::from_str(&content).ok())
+                .unwrap_or_default();
+            let globs = yaml.get("packages").and_then(|p| p.as_sequence()).into_iter().flatten().filter_map(|g| g.as_str());
+            let members = Members::from_globs(globs);
+            let root = npm_roots.entry(dir).or_default();
+            root.include.extend(members.include);
+            root.exclude.extend(members.exclude);
+        }
+    }
+
+    // Python packages may have several manifests in one directory
+    let mut packages: BTreeMap<String, Package> = BTreeMap::new();
+    for file in &files {
+        let package = if let Some(dir) = split_manifest(file, "Cargo.toml") {
+            let content = read(file).unwrap_or_default();
+            let Some(name) = section_value(&content, "package", "name").or_else(|| {
+                // Unreadable manifests still mark a package; workspace-only roots do not
+                (content.is_empty() || !has_section(&content, "workspace")).then(|| dir_name(dir))
+            }) else {
+                continue;
+            };
+            if !is_member(&cargo_roots, dir) {
+                continue;
+            }
+            Package { path: dir.to_string(), name, kind: PackageKind::Cargo }
+        } else if let Some(dir) = split_manifest(file, "package.json") {
+            if dir.split('/').any(|part| part == "node_modules") || npm_roots.contains_key(dir) || !is_member(&npm_roots, dir) {
+                continue;
+            }
+            let json: serde_json::Value = read(file)
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            Package {
+                path: dir.to_string(),
+                name: json.get("name").and_then(|n| n.as_str()).map(str::to_string).unwrap_or_else(|| dir_name(dir)),
+                kind: PackageKind::Npm,
+            }
+        } else if let Some(dir) = ["pyproject.toml", "setup.py", "setup.cfg"].iter().find_map(|m| split_manifest(file, m)) {
+            let name = file.ends_with("pyproject.toml")
+                .then(|| read(file).unwrap_or_default())
+                .and_then(|content| section_value(&content, "project", "name").or_else(|| section_value(&content, "tool.poetry", "name")));
+            if let Some(existing) = packages.get_mut(dir) {
+                if let Some(name) = name {
+                    existing.name = name;
+                }
+                continue;
+            }
+            Package {
+                path: dir.to_string(),
+                name: name.unwrap_or_else(|| dir_name(dir)),
+                kind: PackageKind::Python,
+            }
+        } else {
+            continue;
+        };
+        packages.entry(package.path.clone()).or_insert(package);
+    }
+    packages.into_values().collect()
+}
+
+/// Whether `dir` is a member of every workspace root enclosing it
+///
+/// A root nested in another root must itself be a member of the outer one.
+fn is_member(roots: &BTreeMap<&str, Members>, dir: &str) -> bool {
+    let mut dir = dir;
+    loop {
+        let enclosing = roots.iter()
+            .filter(|(root, _)| root.len() < dir.len() && (root.is_empty() || dir.strip_prefix(*root).is_some_and(|rest| rest.starts_with('/'))))
+            .max_by_key(|(root, _)| root.len());
+        let Some((root, members)) = enclosing else {
+            return true;
+        };
+        let relative = if root.is_empty() { dir } else { &dir[root.len() + 1..] };
+        if !members.contains(relative) {
+            return false;
+        }
+        dir = root;
+    }
+}
+
+/// Strip `./` and trailing slashes from a member glob
+fn normalize_glob(glob: &str) -> &str {
+    glob.trim().trim_start_matches("./").trim_end_matches('/')
+}
+
+/// Regex for a directory glob: `*` and `?` stay within a path segment, `**` spans segments
+fn glob_regex(glob: &str) -> Option<Regex> {
+    let glob = normalize_glob(glob);
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` also matches no directory at all
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
+/// Directory of a manifest file named `manifest` ("" at the root)
+fn split_manifest<'a>(file: &'a str, manifest: &str) -> Option<&'a str> {
+    if file == manifest {
+        return Some("");
+    }
+    file.strip_suffix(manifest)?.strip_suffix('/')
+}
+
+fn dir_name(dir: &str) -> String {
+    match dir.rsplit('/').next() {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => "root".to_string(),
+    }
+}
+
+fn has_section(content: &str, section: &str) -> bool {
+    let header = format!("[{}]", section);
+    content.lines().any(|line| line.trim() == header)
+}
+
+/// `key = ["a", "b"]` inside a `[section]` of a TOML file; the array may span lines
+fn section_array(content: &str, section: &str, key: &str) -> Vec<String> {
+    let header = format!("[{}]", section);
+    let mut in_section = false;
+    let mut lines = content.lines().map(|line| line.split('#').next().unwrap_or_default().trim());
+    while let Some(line) = lines.next() {
+        if line.starts_with('[') {
+            in_section = line == header;
+            continue;
+        }
+        let Some((_, v)) = line.split_once('=').filter(|(k, _)| in_section && k.trim() == key) else {
+            continue;
+        };
+        let mut array = v.trim().to_string();
+        while !array.contains(']') {
+            match lines.next() {
+                Some(next) => array.push_str(next),
+                None => break,
+            }
+        }
+        let array = array.split(']').next().unwrap_or_default();
+        return array.split(',')
+            .map(|item| item.trim().trim_start_matches('[').trim().trim_matches('"').trim_matches('\'').to_string())
+            .filter(|item| !item.is_empty())
+            .collect();
+    }
+    Vec::new()
+}
+
+/// `key = "value"` inside a `[section]` of a TOML file
+fn section_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let header = format!("[{}]", section);
+    let mut in_section = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line == header;
+        } else if in_section {
+            if let Some((k, v)) = line.split_once('=') {
+                if k.trim() == key {
+                    return Some(v.trim().trim_matches('"').trim_matches('\'').to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_packages() {
+        let files = [
+            "Cargo.toml",
+            "crates/core/Cargo.toml",
+            "crates/cli/Cargo.toml",
+            "crates/cli/tests/fixtures/broken/Cargo.toml",
+            "crates/legacy/Cargo.toml",
+            "examples/demo/Cargo.toml",
+            "web/package.json",
+            "web/pnpm-workspace.yaml",
+            "web/packages/ui/package.json",
+            "web/packages/ui/test/fixture/package.json",
+            "web/node_modules/left-pad/package.json",
+            "tools/script/package.json",
+            "py/tool/pyproject.toml",
+            "py/tool/setup.py",
+        ];
+        let read = |path: &str| -> Option<String> {
+            Some(match path {
+                "Cargo.toml" => "[workspace]\nmembers = [\n    \"crates/*\",  # all crates\n]\nexclude = [\"crates/legacy\"]\n".to_string(),
+                "crates/core/Cargo.toml" => "[package]\nname = \"demo-core\"\n".to_string(),
+                "crates/cli/tests/fixtures/broken/Cargo.toml" => "[package]\nname = \"broken\"\n[workspace]\n".to_string(),
+                "web/package.json" => r#"{"name": "web", "private": true}"#.to_string(),
+                "web/pnpm-workspace.yaml" => "packages:\n  - 'packages/*'\n  - '!**/test/**'\n".to_string(),
+                "web/packages/ui/package.json" => r#"{"name": "@demo/ui"}"#.to_string(),
+                "py/tool/pyproject.toml" => "[project]\nname = \"demo-tool\"\n".to_string(),
+                _ => String::new(),
+            })
+        };
+
+        let workspace = Workspace::new(detect(files.iter().copied(), read));
+        let names: Vec<&str> = workspace.packages().iter().map(|p| p.name.as_str()).collect();
+        // Fixtures, examples and excluded crates under the Cargo root are not members
+        assert_eq!(names, vec!["@demo/ui", "script", "demo-core", "cli", "demo-tool"]);
+
+        assert_eq!(workspace.package_of("crates/core/src/lib.rs").unwrap().name, "demo-core");
+        assert_eq!(workspace.package_of("crates/cli/tests/fixtures/broken/src/lib.rs").unwrap().name, "cli");
+        assert_eq!(workspace.package_of("web/packages/ui/src/button.ts").unwrap().kind, PackageKind::Npm);
+        assert!(workspace.package_of("crates/core-extra/lib.rs").is_none());
+        assert!(workspace.package_of("examples/demo/src/main.rs").is_none());
+        assert!(workspace.package_of("README.md").is_none());
+    }
+
+    #[test]
+    fn test_member_globs() {
+        let members = Members::from_globs(["packages/**", "!packages/**/fixtures/*", "./apps/*/"]);
+        assert!(members.contains("packages/ui"));
+        assert!(members.contains("packages/tools/cli"));
+        assert!(!members.contains("packages/ui/fixtures/app"));
+        assert!(members.contains("apps/web"));
+        assert!(!members.contains("apps/web/nested"));
+
+        let cargo = Members::from_cargo(&["crates/*".to_string()], &["crates/old".to_string()]);
+        assert!(cargo.contains("crates/new"));
+        assert!(!cargo.contains("crates/old"));
+    }
+
+    #[tokio::test]
+    async fn test_loader_reads_each_commit() {
+        let repo = std::env::temp_dir().join(format!("atomicity-workspace-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(&repo).unwrap();
+        let git = |args: &'static [&'static str]| {
+            let repo = repo.clone();
+            async move { run_git(&repo, args).await.unwrap() }
+        };
+        let write = |path: &str, content: &str| {
+            let path = repo.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        git(&["init", "-q", "-b", "main"]).await;
+        git(&["config", "user.name", "Dev"]).await;
+        git(&["config", "user.email", "dev@example.com"]).await;
+
+        write("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n");
+        write("crates/caf\u{e9}/Cargo.toml", "[package]\nname = \"cafe\"\n");
+        git(&["add", "-A"]).await;
+        git(&["commit", "-q", "-m", "first"]).await;
+        let first = git(&["rev-parse", "HEAD"]).await;
+
+        write("crates/core/Cargo.toml", "[package]\nname = \"core\"\n");
+        git(&["add", "-A"]).await;
+        git(&["commit", "-q", "-m", "second"]).await;
+        // Working tree changes do not count; the index does
+        write("crates/wip/Cargo.toml", "[package]\nname = \"wip\"\n");
+
+        let config = WorkspaceConfig { enabled: true, packages: Vec::new() };
+        let mut loader = WorkspaceLoader::new(&repo, &config).await.unwrap();
+        let names = |workspace: &Workspace| -> Vec<String> { workspace.packages().iter().map(|p| p.name.clone()).collect() };
+
+        let at_first = loader.at(Some(&first)).await.unwrap();
+        assert_eq!(names(&at_first), vec!["cafe"]);
+        assert_eq!(at_first.package_of("crates/caf\u{e9}/src/lib.rs").unwrap().name, "cafe");
+        assert_eq!(names(&loader.at(Some("HEAD")).await.unwrap()), vec!["cafe", "core"]);
+        assert_eq!(names(&loader.at(None).await.unwrap()), vec!["cafe", "core"]);
+
+        git(&["add", "-A"]).await;
+        assert_eq!(names(&loader.at(None).await.unwrap()), vec!["cafe", "core", "wip"]);
+        let _ = std::fs::remove_dir_all(&repo);
+    }
+}
@@ -8,9 +8,14 @@ pub struct OctocrabGitHub {
     client: Octocrab,
 }
 
+/// The GitHub token gc authenticates with
+pub fn token() -> Option<String> {
+    std::env::var("GITHUB_TOKEN").ok()
+}
+
 impl OctocrabGitHub {
     pub fn new() -> Self {
-        let token = std::env::var("GITHUB_TOKEN").ok();
+        let token = token();
         let builder = Octocrab::builder();
         let client = if let Some(token) = token {
             builder.personal_token(token).build().unwrap_or_else(|_| Octocrab::default())
@@ -19,6 +24,11 @@ impl OctocrabGitHub {
         };
         Self { client }
     }
+
+    /// The underlying client, for gc crates that call GitHub APIs the port does not cover
+    pub fn client(&self) -> &Octocrab {
+        &self.client
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,185 @@
+use clap::{Args, Subcommand};
+use color_eyre::Result;
+use console::style;
+use gc_core::ports::SystemPort;
+use gc_validator::ci_run::{self, FollowEvent};
+use gc_validator::github::GitHubClient;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::guardian::{parse_repo_from_url, split_repo};
+
+#[derive(Args, Debug)]
+pub struct CiArgs {
+    #[command(subcommand)]
+    pub command: CiCommands,
+
+    /// Repository (owner/repo); defaults to the origin remote
+    #[arg(long, global = true, env = "GITHUB_REPOSITORY")]
+    pub repo: Option<String>,
+
+    /// Seconds between status polls
+    #[arg(long, global = true, default_value = "5")]
+    pub poll: u64,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CiCommands {
+    /// Trigger a workflow_dispatch run and follow it
+    ///
+    /// Exit codes: 0 success, 2 failure, 3 cancelled, 4 timed out, 5 other, 1 error.
+    Run {
+        /// Workflow file name (ci.yml), path or ID
+        workflow: String,
+
+        /// Branch or tag to run on; defaults to the current branch
+        #[arg(long = "ref")]
+        git_ref: Option<String>,
+
+        /// Workflow input as key=value (repeatable)
+        #[arg(long = "input", value_parser = parse_input)]
+        inputs: Vec<(String, String)>,
+
+        /// Also stream job logs
+        #[arg(long)]
+        logs: bool,
+
+        /// Print the run URL and return without waiting for it to finish
+        #[arg(long)]
+        no_follow: bool,
+    },
+    /// Print the logs of a run's jobs
+    Logs {
+        /// Workflow run ID
+        run_id: u64,
+
+        /// Keep tailing in-progress jobs; exit with the run's conclusion (codes as for `run`)
+        #[arg(long, short)]
+        follow: bool,
+    },
+}
+
+pub async fn execute(args: CiArgs, system: &impl SystemPort) -> Result<()> {
+    let (owner, repo) = match args.repo {
+        Some(repo) => split_repo(&repo)?,
+        None => {
+            let output = system.run_command_output("git", &["remote", "get-url", "origin"].map(|s| s.to_string())).await?;
+            parse_repo_from_url(&output)?
+        }
+    };
+    let token = gc_adapter_github::token().ok_or_else(|| color_eyre::eyre::eyre!("GITHUB_TOKEN required for gc ci"))?;
+    let client = GitHubClient::new(&token, &format!("{}/{}", owner, repo), 10);
+    let poll = Duration::from_secs(args.poll);
+
+    match args.command {
+        CiCommands::Run { workflow, git_ref, inputs, logs, no_follow } => {
+            let git_ref = match git_ref {
+                Some(git_ref) => git_ref,
+                None => system
+                    .run_command_output("git", &["rev-parse", "--abbrev-ref", "HEAD"].map(|s| s.to_string()))
+                    .await?
+                    .trim()
+                    .to_string(),
+            };
+            let inputs: BTreeMap<String, String> = inputs.into_iter().collect();
+
+            println!("{}", style(format!("🚀 Dispatching {} on {}", workflow, git_ref)).dim());
+            let run = ci_run::dispatch_and_wait(&client, &workflow, &git_ref, &inputs, poll, ci_run::DEFAULT_DISPATCH_TIMEOUT)
+                .await
+                .map_err(|e| color_eyre::eyre::eyre!(e))?;
+            println!("{} #{} {}", style("▶").cyan().bold(), run.id, style(&run.html_url).underlined());
+
+            if no_follow {
+                return Ok(());
+            }
+            follow(&client, run.id, logs, poll).await?;
+        }
+        CiCommands::Logs { run_id, follow: true } => {
+            follow(&client, run_id, true, poll).await?;
+        }
+        CiCommands::Logs { run_id, follow: false } => {
+            let jobs = client.get_jobs(run_id).await.map_err(|e| color_eyre::eyre::eyre!(e))?;
+            // Jobs that never ran have no log
+            let started = jobs.iter()
+                .filter(|j| j.status != "queued" && j.status != "waiting")
+                .filter(|j| j.conclusion.as_deref() != Some("skipped"));
+            for job in started {
+                println!("{}", style(format!("── {} ({})", job.name, job.conclusion.as_deref().unwrap_or(&job.status))).bold());
+                let log = match client.get_job_logs(job.id).await {
+                    Ok(log) => log,
+                    Err(e) => {
+                        eprintln!("{} Log of {} unavailable: {}", style("⚠️").yellow(), job.name, e);
+                        continue;
+                    }
+                };
+                for line in log.lines() {
+                    println!("{}", gc_validator::log_parser::clean_line(line));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Follow a run to completion and exit with its conclusion
+async fn follow(client: &GitHubClient, run_id: u64, logs: bool, poll: Duration) -> Result<()> {
+    let run = ci_run::follow_run(client, run_id, logs, poll, print_event)
+        .await
+        .map_err(|e| color_eyre::eyre::eyre!(e))?;
+
+    let conclusion = run.conclusion.as_deref();
+    let summary = format!("{} #{}: {}", run.name, run.id, conclusion.unwrap_or("unknown"));
+    match ci_run::exit_code(conclusion) {
+        ci_run::EXIT_SUCCESS => println!("{}", style(format!("✅ {}", summary)).green().bold()),
+        _ => println!("{}", style(format!("❌ {}", summary)).red().bold()),
+    }
+    std::process::exit(ci_run::exit_code(conclusion));
+}
+
+fn print_event(event: FollowEvent) {
+    match event {
+        FollowEvent::Job { job, status, conclusion } => {
+            println!("{} {} {}", status_icon(&status, conclusion.as_deref()), style(job).bold(), style(conclusion.unwrap_or(status)).dim());
+        }
+        FollowEvent::Step { job, step, status, conclusion } => {
+            println!("  {} {} › {}", status_icon(&status, conclusion.as_deref()), style(job).dim(), step);
+        }
+        FollowEvent::Log { job, line } => {
+            println!("{} {}", style(format!("{} │", job)).dim(), line);
+        }
+    }
+}
+
+fn status_icon(status: &str, conclusion: Option<&str>) -> console::StyledObject<&'static str> {
+    match (status, conclusion) {
+        ("completed", Some("success")) => style("✓").green(),
+        ("completed", Some("skipped")) | ("completed", Some("neutral")) => style("○").dim(),
+        ("completed", Some("cancelled")) => style("⏹").yellow(),
+        ("completed", _) => style("✗").red(),
+        ("in_progress", _) => style("●").yellow(),
+        _ => style("…").dim(),
+    }
+}
+
+/// Parse a `key=value` workflow input
+fn parse_input(input: &str) -> std::result::Result<(String, String), String> {
+    input
+        .split_once('=')
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected key=value, got '{}'", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_input("version=1.2.0"), Ok(("version".to_string(), "1.2.0".to_string())));
+        assert_eq!(parse_input("notes=a=b"), Ok(("notes".to_string(), "a=b".to_string())));
+        assert!(parse_input("=x").is_err());
+        assert!(parse_input("flag").is_err());
+    }
+}
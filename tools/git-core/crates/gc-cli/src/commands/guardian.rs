@@ -0,0 +1,281 @@
+use clap::{Args, Subcommand};
+use color_eyre::Result;
+use console::style;
+use gc_adapter_github::OctocrabGitHub;
+use gc_core::ports::SystemPort;
+use gc_validator::github::GitHubClient;
+use gc_validator::guardian_core::{Decision, GuardianCore};
+use gc_validator::guardian_local::LocalPrSource;
+use gc_validator::guardian_report::DecisionReport;
+use gc_validator::guardian_watch;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Args, Debug)]
+pub struct GuardianArgs {
+    #[command(subcommand)]
+    pub command: GuardianCommands,
+
+    /// Repository (owner/repo); defaults to the origin remote
+    #[arg(long, global = true, env = "GITHUB_REPOSITORY")]
+    pub repo: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum GuardianCommands {
+    /// Evaluate a PR for auto-merge
+    ///
+    /// Exit codes: 0 auto-merge, 2 escalate, 3 blocked, 1 error.
+    Evaluate {
+        /// Pull Request number
+        pr: u64,
+
+        /// Confidence threshold (0-100)
+        #[arg(long, default_value = "70")]
+        threshold: u8,
+
+        /// Path to risk-map.json
+        #[arg(long, default_value = ".gitcore/risk-map.json")]
+        risk_map: String,
+
+        /// Path to guardian-policy.json
+        #[arg(long, default_value = ".gitcore/guardian-policy.json")]
+        policy: String,
+
+        /// Merge method (squash, merge, rebase)
+        #[arg(long)]
+        merge_method: Option<String>,
+
+        /// Only report, never merge or label (default outside CI)
+        #[arg(long, conflicts_with = "execute")]
+        dry_run: bool,
+
+        /// Merge or escalate (default in CI)
+        #[arg(long)]
+        execute: bool,
+
+        /// Wait up to N seconds for pending checks
+        #[arg(long)]
+        wait: Option<u64>,
+
+        /// Output the decision report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Score the local branch (git diff base...head) before a PR exists
+    ///
+    /// Never calls GitHub. Exit codes as for `evaluate`.
+    Local {
+        /// Base ref
+        #[arg(long, default_value = "main")]
+        base: String,
+
+        /// Head ref
+        #[arg(long, default_value = "HEAD")]
+        head: String,
+
+        /// CI result JSON file (`{"checks": [{"name", "conclusion"}]}`)
+        #[arg(long)]
+        ci_result: Option<PathBuf>,
+
+        /// Do not assume an approving review
+        #[arg(long)]
+        require_review: bool,
+
+        /// Confidence threshold (0-100)
+        #[arg(long, default_value = "70")]
+        threshold: u8,
+
+        /// Path to risk-map.json
+        #[arg(long, default_value = ".gitcore/risk-map.json")]
+        risk_map: String,
+
+        /// Path to guardian-policy.json
+        #[arg(long, default_value = ".gitcore/guardian-policy.json")]
+        policy: String,
+
+        /// Output the decision report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Verify auto-merged PRs on the default branch and revert breakages
+    Watch {
+        /// Only report, never label or revert (default outside CI)
+        #[arg(long, conflicts_with = "execute")]
+        dry_run: bool,
+
+        /// Label and revert (default in CI)
+        #[arg(long)]
+        execute: bool,
+
+        /// Output the outcomes as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub async fn execute(args: GuardianArgs, system: &impl SystemPort, github: &OctocrabGitHub) -> Result<()> {
+
+    match args.command {
+        GuardianCommands::Evaluate { pr, threshold, risk_map, policy, merge_method, dry_run, execute, wait, json } => {
+            let (owner, repo) = resolve_repo(args.repo, system).await?;
+            let dry_run = resolve_dry_run(dry_run, execute);
+
+            let mut guardian = GuardianCore::new(github.client().clone(), owner.clone(), repo.clone())
+                .with_threshold(threshold)
+                .with_repo_config(&risk_map, &policy)
+                .map_err(|e| color_eyre::eyre::eyre!(e))?;
+            if let Some(method) = merge_method {
+                guardian = guardian.with_merge_method(method.parse().map_err(|e| color_eyre::eyre::eyre!("{}", e))?);
+            }
+            if let Some(seconds) = wait {
+                guardian = guardian.with_wait(Duration::from_secs(seconds), Duration::from_secs(30));
+            }
+
+            if !json {
+                println!("{}", style(format!("🛡️ Evaluating {}/{}#{}{}", owner, repo, pr, if dry_run { " (dry run)" } else { "" })).dim());
+            }
+
+            let report = guardian.evaluate_pr_report(pr, dry_run).await
+                .map_err(|e| color_eyre::eyre::eyre!(e))?;
+
+            if json {
+                println!("{}", report.to_json().map_err(|e| color_eyre::eyre::eyre!(e))?);
+            } else {
+                print_report(&report);
+            }
+
+            std::process::exit(report.decision.exit_code());
+        }
+        GuardianCommands::Watch { dry_run, execute, json } => {
+            let (owner, repo) = resolve_repo(args.repo, system).await?;
+            let dry_run = resolve_dry_run(dry_run, execute);
+            let token = gc_adapter_github::token().ok_or_else(|| color_eyre::eyre::eyre!("GITHUB_TOKEN required for guardian watch"))?;
+            let client = GitHubClient::new(&token, &format!("{}/{}", owner, repo), 10);
+
+            let outcomes = guardian_watch::run_watch(&client, dry_run).await
+                .map_err(|e| color_eyre::eyre::eyre!(e))?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&outcomes)?);
+            } else {
+                for outcome in &outcomes {
+                    println!("#{} {} {:?}", style(outcome.pr_number).green().bold(), style(&outcome.merge_sha).dim(), outcome.health);
+                }
+            }
+        }
+        GuardianCommands::Local { base, head, ci_result, require_review, threshold, risk_map, policy, json } => {
+            // Local scoring needs neither a repository nor a token
+            let guardian = GuardianCore::local()
+                .with_threshold(threshold)
+                .with_repo_config(&risk_map, &policy)
+                .map_err(|e| color_eyre::eyre::eyre!(e))?;
+            let pr_data = LocalPrSource::new(&base, &head)
+                .with_ci_result(ci_result)
+                .with_require_review(require_review)
+                .with_neutral_is_success(guardian.policy().neutral_is_success)
+                .with_required_checks(&guardian.policy().required_checks)
+                .collect()
+                .await
+                .map_err(|e| color_eyre::eyre::eyre!(e))?;
+
+            let report = guardian.evaluate(&pr_data);
+            if json {
+                println!("{}", report.to_json().map_err(|e| color_eyre::eyre::eyre!(e))?);
+            } else {
+                println!("{}", style(format!("🛡️ Evaluating {}...{} locally", base, head)).dim());
+                print_report(&report);
+            }
+
+            std::process::exit(report.decision.exit_code());
+        }
+    }
+
+    Ok(())
+}
+
+/// `--repo`/`GITHUB_REPOSITORY`, or the origin remote
+async fn resolve_repo(repo: Option<String>, system: &impl SystemPort) -> Result<(String, String)> {
+    match repo {
+        Some(repo) => split_repo(&repo),
+        None => {
+            let output = system.run_command_output("git", &["remote", "get-url", "origin"].map(|s| s.to_string())).await?;
+            parse_repo_from_url(&output)
+        }
+    }
+}
+
+/// Explicit flags win; otherwise act only inside CI
+fn resolve_dry_run(dry_run: bool, execute: bool) -> bool {
+    if dry_run {
+        return true;
+    }
+    if execute {
+        return false;
+    }
+    !in_ci()
+}
+
+fn in_ci() -> bool {
+    std::env::var("CI").map(|v| v == "true" || v == "1").unwrap_or(false)
+        || std::env::var("GITHUB_ACTIONS").is_ok()
+}
+
+fn print_report(report: &DecisionReport) {
+    let headline = match &report.decision {
+        Decision::AutoMerge { .. } => style("✅ AUTO-MERGE".to_string()).green().bold(),
+        Decision::Escalate { reason, .. } => style(format!("⚠️  ESCALATE: {}", reason)).yellow().bold(),
+        Decision::Blocked { reason } => style(format!("⛔ BLOCKED: {}", reason)).red().bold(),
+    };
+    println!("{}", headline);
+    println!("Confidence: {}% (threshold {}%)", report.confidence, report.threshold);
+
+    for signal in &report.signals {
+        println!("  {:<10} {:<20} {:+}", signal.name, signal.value, signal.contribution);
+    }
+    for gap in &report.missing_owner_approvals {
+        println!("  {} {} needs {}", style("👥").yellow(), gap.pattern, gap.owners.join(", "));
+    }
+}
+
+pub(crate) fn split_repo(repo: &str) -> Result<(String, String)> {
+    repo.split_once('/')
+        .map(|(owner, repo)| (owner.to_string(), repo.to_string()))
+        .ok_or_else(|| color_eyre::eyre::eyre!("Invalid repo format (expected owner/repo): {}", repo))
+}
+
+pub(crate) fn parse_repo_from_url(url: &str) -> Result<(String, String)> {
+    let url = url.trim();
+    let parts: Vec<&str> = if url.starts_with("git@") {
+        url.split(':').nth(1).unwrap_or("").split('/').collect()
+    } else {
+        url.split("github.com/").nth(1).unwrap_or("").split('/').collect()
+    };
+
+    if parts.len() < 2 {
+        return Err(color_eyre::eyre::eyre!("Could not parse repo from URL: {}", url));
+    }
+
+    Ok((parts[0].to_string(), parts[1].trim_end_matches(".git").to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_flags_override_ci_detection() {
+        assert!(resolve_dry_run(true, false));
+        assert!(!resolve_dry_run(false, true));
+    }
+
+    #[test]
+    fn test_repo_parsing() {
+        assert_eq!(split_repo("octo/cat").unwrap(), ("octo".to_string(), "cat".to_string()));
+        assert!(split_repo("octocat").is_err());
+        assert_eq!(
+            parse_repo_from_url("git@github.com:octo/cat.git\n").unwrap(),
+            ("octo".to_string(), "cat".to_string())
+        );
+    }
+}
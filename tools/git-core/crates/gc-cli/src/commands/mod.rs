@@ -16,6 +16,8 @@ pub mod update;
 pub mod workflow;
 pub mod dispatch;
 pub mod analyze;
+pub mod guardian;
+pub mod ci;
 
 pub use init::InitArgs;
 pub use context::ContextCmd;
@@ -35,6 +37,8 @@ pub use update::UpdateArgs;
 pub use workflow::WorkflowArgs;
 pub use dispatch::DispatchArgs;
 pub use analyze::AnalyzeArgs;
+pub use guardian::GuardianArgs;
+pub use ci::CiArgs;
 
 #[cfg(test)]
 pub mod mocks;
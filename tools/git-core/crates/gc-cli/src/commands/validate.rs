@@ -1,5 +1,5 @@
 use clap::Subcommand;
-use gc_validator::{github, validator, analyzer};
+use gc_validator::{github, validator, analyzer, workflow_lint};
 use color_eyre::Result;
 
 #[derive(Subcommand, Debug)]
@@ -28,29 +28,59 @@ pub enum ValidateCmd {
         #[arg(long, default_value = "false")]
         include_success: bool,
     },
+    /// Statically lint workflow files
+    Lint {
+        /// Directory containing workflow files
+        #[arg(long, default_value = ".github/workflows")]
+        path: String,
+
+        /// Schedule mode from `gc ci-detect` (aggressive, moderate, conservative)
+        #[arg(long, env = "SCHEDULE_MODE")]
+        schedule_mode: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 pub async fn execute(cmd: ValidateCmd) -> Result<()> {
-    // Determine token and repo
-    let token = std::env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN required");
-    let repo = std::env::var("GITHUB_REPOSITORY").expect("GITHUB_REPOSITORY required"); // Or pass as global arg?
-    // For local dev, maybe fallback to git detection?
-    // But gc-validator uses GITHUB_REPOSITORY env var logic usually.
-    // Let's rely on env vars for now as per original code.
-
-    // Max parallel hardcoded or from config? Default 10.
-    let client = github::GitHubClient::new(&token, &repo, 10);
+    // Determine token and repo (linting reads local files only and needs neither)
+    let client = || {
+        let token = std::env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN required");
+        let repo = std::env::var("GITHUB_REPOSITORY").expect("GITHUB_REPOSITORY required");
+        github::GitHubClient::new(&token, &repo, 10)
+    };
 
     match cmd {
         ValidateCmd::Run { run_id, last_hours, create_pr } => {
-            validator::run_validation(&client, &run_id, last_hours, create_pr, "terminal").await
+            validator::run_validation(&client(), None, &github::RunFilter::default(), &run_id, last_hours, create_pr, "terminal").await
                 .map_err(|e| color_eyre::eyre::eyre!(e))?;
         }
         ValidateCmd::Analyze { types, include_success } => {
-            analyzer::run_analysis(&client, &types, include_success, "terminal").await
+            analyzer::run_analysis(&client(), None, &github::RunFilter::default(), &types, include_success, "terminal").await
                 .map_err(|e| color_eyre::eyre::eyre!(e))?;
         }
+        ValidateCmd::Lint { path, schedule_mode, json } => {
+            lint(&path, schedule_mode, json)?;
+        }
     }
 
     Ok(())
 }
+
+fn lint(path: &str, schedule_mode: Option<String>, json: bool) -> Result<()> {
+    let mode = schedule_mode
+        .map(|m| m.parse::<workflow_lint::ScheduleMode>())
+        .transpose()
+        .map_err(|e| color_eyre::eyre::eyre!(e))?;
+    let findings = workflow_lint::lint_dir(path, mode).map_err(|e| color_eyre::eyre::eyre!(e))?;
+
+    let format = if json { "json" } else { "terminal" };
+    println!("{}", workflow_lint::render(&findings, format).map_err(|e| color_eyre::eyre::eyre!(e))?);
+
+    if workflow_lint::has_errors(&findings) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
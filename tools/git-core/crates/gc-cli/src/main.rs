@@ -8,7 +8,7 @@ pub struct Cli {
 }
 
 mod commands;
-use commands::{InitArgs, ContextCmd, ReportCmd, ValidateCmd, TelemetryArgs, CiDetectArgs, TaskArgs, FinishArgs, IssueArgs, PrArgs, GitArgs, InfoArgs, CheckArgs, NextArgs, WorkflowArgs, UpdateArgs, DispatchArgs, AnalyzeArgs};
+use commands::{InitArgs, ContextCmd, ReportCmd, ValidateCmd, TelemetryArgs, CiDetectArgs, TaskArgs, FinishArgs, IssueArgs, PrArgs, GitArgs, InfoArgs, CheckArgs, NextArgs, WorkflowArgs, UpdateArgs, DispatchArgs, AnalyzeArgs, GuardianArgs, CiArgs};
 
 #[derive(Subcommand)]
 pub enum Commands {
@@ -54,6 +54,10 @@ pub enum Commands {
     Dispatch(DispatchArgs),
     /// Analyze Architecture & Generate Prompt
     Analyze(AnalyzeArgs),
+    /// Guardian auto-merge decisions
+    Guardian(GuardianArgs),
+    /// Trigger and follow workflow runs
+    Ci(CiArgs),
 }
 
 #[tokio::main]
@@ -153,6 +157,15 @@ async fn main() -> color_eyre::Result<()> {
         Commands::Analyze(args) => {
             commands::analyze::execute(args).await?;
         }
+        Commands::Guardian(args) => {
+            let system = gc_adapter_system::TokioSystem;
+            let github = gc_adapter_github::OctocrabGitHub::new();
+            commands::guardian::execute(args, &system, &github).await?;
+        }
+        Commands::Ci(args) => {
+            let system = gc_adapter_system::TokioSystem;
+            commands::ci::execute(args, &system).await?;
+        }
     }
 
     Ok(())
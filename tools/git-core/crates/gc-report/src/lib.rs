@@ -0,0 +1,401 @@
+//! # gc-report
+//!
+//! Common finding model for the Git-Core checkers (`atomicity-checker`,
+//! `gc-validator`, `structure-validator`) and two machine-readable renderings:
+//!
+//! - **SARIF 2.1.0** for `github/codeql-action/upload-sarif` (code scanning)
+//! - **JUnit XML** for test reporters; each finding is a failing test case,
+//!   registered rules without findings are passing ones
+//!
+//! ```
+//! use gc_report::{Finding, Report, Severity};
+//!
+//! let report = Report::new("structure-validator", "1.0.0")
+//!     .with_rule("FORBIDDEN_FILE", "Planning files must not live in the repository root")
+//!     .with_finding(
+//!         Finding::new("FORBIDDEN_FILE", Severity::Error, "Forbidden file 'TODO.md' found in root")
+//!             .at("TODO.md", None)
+//!             .with_fix("Move the content to GitHub Issues"),
+//!     );
+//!
+//! assert!(report.render("sarif").unwrap().contains("\"version\": \"2.1.0\""));
+//! assert!(report.render("junit").unwrap().contains("failures=\"1\""));
+//! ```
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Formats `Report::render` understands
+pub const FORMATS: [&str; 2] = ["sarif", "junit"];
+
+/// Why a report could not be rendered
+#[derive(Debug)]
+pub enum RenderError {
+    /// Not one of `FORMATS`
+    UnknownFormat(String),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::UnknownFormat(format) => {
+                write!(f, "unknown report format '{}' (expected one of: {})", format, FORMATS.join(", "))
+            }
+            RenderError::Json(e) => write!(f, "failed to serialize SARIF: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RenderError::Json(e) => Some(e),
+            RenderError::UnknownFormat(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Map the severity strings used by the checkers ("info", "warning", "error", ...)
+    pub fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "error" | "critical" | "high" => Severity::Error,
+            "warning" | "warn" | "medium" => Severity::Warning,
+            _ => Severity::Note,
+        }
+    }
+
+    fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Where a finding applies; paths are relative to the repository root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub path: String,
+    pub line: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub rule_id: String,
+    pub severity: Severity,
+    pub message: String,
+    pub location: Option<Location>,
+    /// Human-readable remediation
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    pub fn new(rule_id: &str, severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            rule_id: rule_id.to_string(),
+            severity,
+            message: message.into(),
+            location: None,
+            fix: None,
+        }
+    }
+
+    pub fn at(mut self, path: &str, line: Option<usize>) -> Self {
+        self.location = Some(Location {
+            path: path.trim_start_matches("./").to_string(),
+            line: line.filter(|l| *l > 0),
+        });
+        self
+    }
+
+    pub fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Rule {
+    pub id: String,
+    pub description: String,
+    pub help_uri: Option<String>,
+}
+
+/// Findings of one tool run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub tool: String,
+    pub version: String,
+    pub information_uri: Option<String>,
+    pub rules: Vec<Rule>,
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    pub fn new(tool: &str, version: &str) -> Self {
+        Self {
+            tool: tool.to_string(),
+            version: version.to_string(),
+            information_uri: None,
+            rules: Vec::new(),
+            findings: Vec::new(),
+        }
+    }
+
+    pub fn with_information_uri(mut self, uri: &str) -> Self {
+        self.information_uri = Some(uri.to_string());
+        self
+    }
+
+    /// Register a rule; rules are also registered implicitly by their findings
+    pub fn with_rule(mut self, id: &str, description: &str) -> Self {
+        if !self.rules.iter().any(|r| r.id == id) {
+            self.rules.push(Rule { id: id.to_string(), description: description.to_string(), help_uri: None });
+        }
+        self
+    }
+
+    pub fn with_rule_help(mut self, id: &str, help_uri: &str) -> Self {
+        if let Some(rule) = self.rules.iter_mut().find(|r| r.id == id) {
+            rule.help_uri = Some(help_uri.to_string());
+        }
+        self
+    }
+
+    pub fn with_finding(mut self, finding: Finding) -> Self {
+        self.findings.push(finding);
+        self
+    }
+
+    pub fn with_findings(mut self, findings: impl IntoIterator<Item = Finding>) -> Self {
+        self.findings.extend(findings);
+        self
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+
+    /// Render as `sarif` or `junit`
+    pub fn render(&self, format: &str) -> Result<String, RenderError> {
+        match format {
+            "sarif" => serde_json::to_string_pretty(&self.to_sarif()).map_err(RenderError::Json),
+            "junit" => Ok(self.to_junit()),
+            _ => Err(RenderError::UnknownFormat(format.to_string())),
+        }
+    }
+
+    /// Registered rules followed by rules only seen in findings
+    fn all_rules(&self) -> Vec<Rule> {
+        let mut rules = self.rules.clone();
+        for finding in &self.findings {
+            if !rules.iter().any(|r| r.id == finding.rule_id) {
+                rules.push(Rule { id: finding.rule_id.clone(), description: finding.rule_id.clone(), help_uri: None });
+            }
+        }
+        rules
+    }
+
+    /// SARIF 2.1.0 log with a single run
+    pub fn to_sarif(&self) -> Value {
+        let rules = self.all_rules();
+        let rule_descriptors: Vec<Value> = rules.iter()
+            .map(|r| {
+                let mut rule = json!({
+                    "id": r.id,
+                    "shortDescription": { "text": r.description },
+                });
+                if let Some(uri) = &r.help_uri {
+                    rule["helpUri"] = json!(uri);
+                }
+                rule
+            })
+            .collect();
+
+        let results: Vec<Value> = self.findings.iter()
+            .map(|f| {
+                let mut result = json!({
+                    "ruleId": f.rule_id,
+                    "ruleIndex": rules.iter().position(|r| r.id == f.rule_id).unwrap_or_default(),
+                    "level": f.severity.sarif_level(),
+                    "message": { "text": f.message },
+                });
+                if let Some(location) = &f.location {
+                    let mut physical = json!({ "artifactLocation": { "uri": location.path } });
+                    if let Some(line) = location.line {
+                        physical["region"] = json!({ "startLine": line });
+                    }
+                    result["locations"] = json!([{ "physicalLocation": physical }]);
+                }
+                if let Some(fix) = &f.fix {
+                    // SARIF `fixes` need concrete replacements; a textual fix goes into the properties
+                    result["properties"] = json!({ "fix": fix });
+                }
+                result
+            })
+            .collect();
+
+        let mut driver = json!({
+            "name": self.tool,
+            "version": self.version,
+            "rules": rule_descriptors,
+        });
+        if let Some(uri) = &self.information_uri {
+            driver["informationUri"] = json!(uri);
+        }
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": { "driver": driver },
+                "results": results,
+            }],
+        })
+    }
+
+    /// JUnit XML with one test suite per tool
+    pub fn to_junit(&self) -> String {
+        let failing = |f: &&Finding| f.severity != Severity::Note;
+        let failures = self.findings.iter().filter(failing).count();
+        let passing_rules: Vec<Rule> = self.all_rules().into_iter()
+            .filter(|r| !self.findings.iter().any(|f| f.rule_id == r.id))
+            .collect();
+        let tests = self.findings.len() + passing_rules.len();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+            escape(&self.tool), tests, failures
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">\n",
+            escape(&self.tool), tests, failures
+        ));
+
+        for finding in &self.findings {
+            let name = match &finding.location {
+                Some(Location { path, line: Some(line) }) => format!("{}:{}", path, line),
+                Some(Location { path, line: None }) => path.clone(),
+                None => finding.message.clone(),
+            };
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                escape(&finding.rule_id), escape(&name)
+            ));
+            if let Some(Location { path, .. }) = &finding.location {
+                xml.push_str(&format!(" file=\"{}\"", escape(path)));
+            }
+            xml.push_str(">\n");
+
+            let mut detail = finding.message.clone();
+            if let Some(fix) = &finding.fix {
+                detail.push_str(&format!("\nFix: {}", fix));
+            }
+            if failing(&finding) {
+                xml.push_str(&format!(
+                    "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                    finding.severity.sarif_level(), escape(&finding.message), escape(&detail)
+                ));
+            } else {
+                xml.push_str(&format!("      <system-out>{}</system-out>\n", escape(&detail)));
+            }
+            xml.push_str("    </testcase>\n");
+        }
+
+        for rule in passing_rules {
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\"/>\n",
+                escape(&rule.id), escape(&rule.description)
+            ));
+        }
+
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+/// Escape text for XML (and HTML) attributes and content
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab/newline are not allowed in XML 1.0
+            c if c.is_control() && c != '\n' && c != '\t' && c != '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        Report::new("checker", "1.0.0")
+            .with_rule("missing-timeout", "Jobs need timeout-minutes")
+            .with_rule("unused", "Never triggered")
+            .with_finding(
+                Finding::new("missing-timeout", Severity::Warning, "Job `build` has no timeout")
+                    .at("./.github/workflows/ci.yml", Some(12))
+                    .with_fix("Add timeout-minutes"),
+            )
+            .with_finding(Finding::new("mixed-concerns", Severity::Note, "Commit <abc> mixes \"feat\" & \"docs\""))
+    }
+
+    #[test]
+    fn test_sarif_results_reference_rules() {
+        let sarif = report().to_sarif();
+        let run = &sarif["runs"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+        let result = &run["results"][0];
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["ruleIndex"], 0);
+        assert_eq!(result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], ".github/workflows/ci.yml");
+        assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 12);
+        assert_eq!(result["properties"]["fix"], "Add timeout-minutes");
+        assert_eq!(run["results"][1]["ruleIndex"], 2);
+        assert!(run["results"][1].get("locations").is_none());
+        assert!(matches!(report().render("html"), Err(RenderError::UnknownFormat(_))));
+    }
+
+    #[test]
+    fn test_junit_counts_and_escaping() {
+        let xml = report().to_junit();
+
+        // 2 findings + 1 rule without findings; notes do not fail
+        assert!(xml.contains("<testsuite name=\"checker\" tests=\"3\" failures=\"1\""));
+        assert!(xml.contains("name=\".github/workflows/ci.yml:12\""));
+        assert!(xml.contains("Fix: Add timeout-minutes"));
+        assert!(xml.contains("Commit &lt;abc&gt; mixes &quot;feat&quot; &amp; &quot;docs&quot;"));
+        assert!(xml.contains("<testcase classname=\"unused\" name=\"Never triggered\"/>"));
+    }
+
+    #[test]
+    fn test_severity_parse() {
+        assert_eq!(Severity::parse("ERROR"), Severity::Error);
+        assert_eq!(Severity::parse("warning"), Severity::Warning);
+        assert_eq!(Severity::parse("info"), Severity::Note);
+    }
+}
@@ -4,7 +4,7 @@
 //! dispatcher agent compared to the PowerShell baseline.
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use workflow_orchestrator::dispatcher_core::{Strategy, Agent};
+use gc_validator::dispatcher_core::{Strategy, Agent};
 
 /// Benchmark: Strategy parsing from strings
 fn bench_strategy_parsing(c: &mut Criterion) {
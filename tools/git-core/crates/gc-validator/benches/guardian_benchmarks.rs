@@ -3,7 +3,7 @@
 //! Compares Rust implementation performance against PowerShell baseline
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use workflow_orchestrator::guardian_core::{GuardianCore, Decision};
+use gc_validator::guardian_core::{GuardianCore, Decision};
 use octocrab::Octocrab;
 use tokio::runtime::Runtime;
 
@@ -1,11 +1,19 @@
 //! Workflow analyzer with parallel execution
 
-use crate::github::{GitHubClient, WorkflowAnalysis, WorkflowRun};
+use crate::critical_path;
+use crate::github::{GitHubClient, Job, RunFilter, WorkflowAnalysis, WorkflowRun};
+use crate::history::{self, HistoryStore};
+use crate::log_parser::LogFailure;
 use anyhow::Result;
+use serde::Serialize;
 use futures::future::join_all;
+use gc_report::{Finding, Report, Severity};
 use std::collections::HashMap;
 use tracing::{info, warn};
 
+/// Location of CI findings whose workflow file is unknown
+const WORKFLOWS_DIR: &str = ".github/workflows";
+
 #[derive(Debug, Clone)]
 pub struct AnalysisResult {
     pub total_runs: usize,
@@ -18,14 +26,26 @@ pub struct AnalysisResult {
     pub recommendations: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+/// A failure signature grouped across runs
+#[derive(Debug, Clone, Serialize)]
 pub struct ErrorReport {
     pub workflow_name: String,
+    /// Workflow file of the run (`.github/workflows/ci.yml`), empty when unknown
+    pub workflow_path: String,
+    /// First run the failure was seen in
     pub run_id: u64,
     pub job_name: String,
     pub step_name: Option<String>,
+    /// Failure kind (`compile`, `test`, `npm`, `exit`), `job` when no failure was parsed
+    pub kind: String,
     pub error_message: String,
+    /// Stable signature identifying identical failures across runs
+    pub fingerprint: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    /// Number of runs the failure occurred in
     pub frequency: u32,
+    pub run_ids: Vec<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -45,9 +65,11 @@ pub struct SecurityReport {
     pub supply_chain_risks: Vec<String>,
 }
 
-/// Run parallel analysis on all workflows
+/// Run parallel analysis on the latest 50 workflow runs matching `filter`
 pub async fn run_analysis(
     client: &GitHubClient,
+    store: Option<&HistoryStore>,
+    filter: &RunFilter,
     analysis_types: &[String],
     include_success: bool,
     output_format: &str,
@@ -55,8 +77,7 @@ pub async fn run_analysis(
     info!("🔍 Starting parallel workflow analysis...");
     info!("📋 Analysis types: {:?}", analysis_types);
 
-    // Fetch all runs in parallel batches
-    let runs = client.get_workflow_runs(50).await?;
+    let runs = client.collect_runs(&filter.clone().with_limit(50)).await?;
 
     // Filter runs based on criteria
     let runs_to_analyze: Vec<WorkflowRun> = if include_success {
@@ -70,7 +91,7 @@ pub async fn run_analysis(
     info!("📊 Analyzing {} workflow runs...", runs_to_analyze.len());
 
     // Parallel analysis
-    let analyses = client.analyze_runs_parallel(runs_to_analyze).await?;
+    let analyses = history::analyze_runs(client, store, runs_to_analyze).await?;
 
     // Build result
     let result = build_analysis_result(&analyses, analysis_types).await;
@@ -83,12 +104,16 @@ pub async fn run_analysis(
                 "successful": result.successful,
                 "failed": result.failed,
                 "errors": result.errors.len(),
+                "failures": result.errors,
                 "recommendations": result.recommendations,
             }))?);
         }
         "markdown" => {
             print_markdown_report(&result);
         }
+        "sarif" | "junit" => {
+            println!("{}", to_report(&result).render(output_format)?);
+        }
         _ => {
             print_terminal_report(&result);
         }
@@ -97,8 +122,8 @@ pub async fn run_analysis(
     Ok(result)
 }
 
-/// Health check for all workflows
-pub async fn health_check(client: &GitHubClient, quick: bool) -> Result<()> {
+/// Health check for all workflows, over the latest 100 runs matching `filter`
+pub async fn health_check(client: &GitHubClient, filter: &RunFilter, quick: bool) -> Result<()> {
     info!("🏥 Running workflow health check...");
 
     let workflows = client.get_workflows().await?;
@@ -116,7 +141,7 @@ pub async fn health_check(client: &GitHubClient, quick: bool) -> Result<()> {
         }
     } else {
         // Deep check - analyze recent runs for each workflow
-        let runs = client.get_workflow_runs(100).await?;
+        let runs = client.collect_runs(&filter.clone().with_limit(100)).await?;
 
         // Group runs by workflow
         let mut workflow_runs: HashMap<u64, Vec<&WorkflowRun>> = HashMap::new();
@@ -177,32 +202,8 @@ async fn build_analysis_result(
         .filter(|a| a.run.conclusion.as_deref() == Some("cancelled"))
         .count();
 
-    // Collect errors
-    let mut errors = Vec::new();
-    let mut error_freq: HashMap<String, u32> = HashMap::new();
-
-    for analysis in analyses {
-        for error in &analysis.errors {
-            *error_freq.entry(error.clone()).or_insert(0) += 1;
-
-            for job in &analysis.jobs {
-                if job.conclusion.as_deref() == Some("failure") {
-                    errors.push(ErrorReport {
-                        workflow_name: analysis.run.name.clone(),
-                        run_id: analysis.run.id,
-                        job_name: job.name.clone(),
-                        step_name: job.steps.as_ref().and_then(|steps| {
-                            steps.iter()
-                                .find(|s| s.conclusion.as_deref() == Some("failure"))
-                                .map(|s| s.name.clone())
-                        }),
-                        error_message: error.clone(),
-                        frequency: *error_freq.get(error).unwrap_or(&1),
-                    });
-                }
-            }
-        }
-    }
+    // Group failures across runs by fingerprint
+    let errors = group_errors(analyses);
 
     // Performance analysis
     let durations: Vec<i64> = analyses.iter()
@@ -275,31 +276,118 @@ async fn build_analysis_result(
     }
 }
 
-fn calculate_parallel_efficiency(analyses: &[WorkflowAnalysis]) -> f64 {
-    // Calculate how well jobs are parallelized
-    // Efficiency = (sum of job durations) / (total workflow duration * num_jobs)
-
-    let mut total_efficiency = 0.0;
-    let mut count = 0;
+/// Group parsed log failures across runs by fingerprint, most frequent first
+///
+/// Each failure is attributed to the job whose log contained it and that
+/// job's failed step. Failed jobs without any recognised failure (including
+/// jobs whose log could not be downloaded) fall back to one `Job 'x' failed`
+/// signature each so they still show up.
+pub fn group_errors(analyses: &[WorkflowAnalysis]) -> Vec<ErrorReport> {
+    let mut errors: Vec<ErrorReport> = Vec::new();
 
     for analysis in analyses {
-        if let Some(workflow_duration) = analysis.duration_seconds {
-            let job_count = analysis.jobs.len();
-            if job_count > 1 && workflow_duration > 0 {
-                // Estimate: if all jobs run in parallel, efficiency = 1.0
-                // if all sequential, efficiency = 1/num_jobs
-                let avg_job_time = workflow_duration as f64 / job_count as f64;
-                let efficiency = avg_job_time / workflow_duration as f64 * job_count as f64;
-                total_efficiency += efficiency.min(1.0);
-                count += 1;
+        let failed_jobs: Vec<&Job> = analysis.jobs.iter()
+            .filter(|j| j.conclusion.as_deref() == Some("failure"))
+            .collect();
+        let Some(first_failed) = failed_jobs.first().copied() else {
+            continue;
+        };
+        // Failures parsed without a job name belong to the first failed job
+        let job_of = |failure: &LogFailure| -> &Job {
+            failure.job.as_deref()
+                .and_then(|name| failed_jobs.iter().copied().find(|j| j.name == name))
+                .unwrap_or(first_failed)
+        };
+
+        let report = |job: &Job, fingerprint: String, kind: String, message: String, file: Option<String>, line: Option<u32>| ErrorReport {
+            workflow_name: analysis.run.name.clone(),
+            workflow_path: analysis.run.path.clone(),
+            run_id: analysis.run.id,
+            job_name: job.name.clone(),
+            step_name: failed_step(job),
+            kind,
+            error_message: message,
+            fingerprint,
+            file,
+            line,
+            frequency: 1,
+            run_ids: vec![analysis.run.id],
+        };
+
+        let mut found: Vec<ErrorReport> = analysis.failures.iter()
+            .map(|f| report(job_of(f), f.fingerprint.clone(), f.kind.to_string(), f.summary(), f.file.clone(), f.line))
+            .collect();
+        for job in &failed_jobs {
+            if !analysis.failures.iter().any(|f| job_of(f).id == job.id) {
+                let fingerprint = format!("job:{}:{}", analysis.run.workflow_id, job.name);
+                found.push(report(job, fingerprint, "job".to_string(), format!("Job '{}' failed", job.name), None, None));
+            }
+        }
+
+        for error in found {
+            match errors.iter_mut().find(|e| e.fingerprint == error.fingerprint) {
+                Some(existing) => {
+                    if !existing.run_ids.contains(&analysis.run.id) {
+                        existing.run_ids.push(analysis.run.id);
+                        existing.frequency += 1;
+                    }
+                }
+                None => errors.push(error),
             }
         }
     }
 
-    if count > 0 {
-        total_efficiency / count as f64
-    } else {
+    errors.sort_by_key(|e| std::cmp::Reverse(e.frequency));
+    errors
+}
+
+/// Name of the first failed step of a job
+fn failed_step(job: &Job) -> Option<String> {
+    job.steps.as_ref()?
+        .iter()
+        .find(|s| s.conclusion.as_deref() == Some("failure"))
+        .map(|s| s.name.clone())
+}
+
+/// Map grouped failures to the shared finding model, one rule per failure kind
+pub fn to_report(result: &AnalysisResult) -> Report {
+    let findings = result.errors.iter().map(|e| {
+        let step = e.step_name.as_deref().map(|s| format!(" / {}", s)).unwrap_or_default();
+        let mut finding = Finding::new(
+            &format!("ci/{}", e.kind),
+            Severity::Error,
+            format!("{}: {}{} failed in {} run(s): {}", e.workflow_name, e.job_name, step, e.frequency, e.error_message),
+        );
+        // Code scanning needs a location: failures without a source file point at their workflow
+        finding = match &e.file {
+            Some(file) => finding.at(file, e.line.map(|l| l as usize)),
+            None if !e.workflow_path.is_empty() => finding.at(&e.workflow_path, None),
+            None => finding.at(WORKFLOWS_DIR, None),
+        };
+        finding
+    });
+
+    Report::new("gc-validator", env!("CARGO_PKG_VERSION"))
+        .with_rule("ci/compile", "Compilation failed in CI")
+        .with_rule("ci/test", "Tests failed in CI")
+        .with_rule("ci/npm", "npm command failed in CI")
+        .with_rule("ci/exit", "Step exited with a non-zero code")
+        .with_rule("ci/job", "Job failed without a recognised error")
+        .with_findings(findings)
+}
+
+fn calculate_parallel_efficiency(analyses: &[WorkflowAnalysis]) -> f64 {
+    // Share of each run's wall time spent on its critical path, averaged over runs
+    let efficiencies: Vec<f64> = analyses.iter()
+        .filter(|a| a.jobs.len() > 1)
+        .filter_map(critical_path::analyze_run)
+        .map(|cp| cp.efficiency())
+        .collect();
+
+    if efficiencies.is_empty() {
         1.0
+    } else {
+        efficiencies.iter().sum::<f64>() / efficiencies.len() as f64
     }
 }
 
@@ -319,6 +407,15 @@ fn print_terminal_report(result: &AnalysisResult) {
         result.performance.parallel_efficiency * 100.0);
     println!("╠════════════════════════════════════════════════════════════════╣");
 
+    if !result.errors.is_empty() {
+        println!("║ 🔍 Top Failures                                                 ║");
+        for error in result.errors.iter().take(5) {
+            let message: String = error.error_message.chars().take(55).collect();
+            println!("║   {}x {}  ", error.frequency, message);
+        }
+        println!("╠════════════════════════════════════════════════════════════════╣");
+    }
+
     if !result.recommendations.is_empty() {
         println!("║ 💡 Recommendations                                              ║");
         for rec in &result.recommendations {
@@ -347,6 +444,16 @@ fn print_markdown_report(result: &AnalysisResult) {
     println!("| Parallel Efficiency | {:.1}% |", result.performance.parallel_efficiency * 100.0);
     println!();
 
+    if !result.errors.is_empty() {
+        println!("## Failures\n");
+        println!("| Runs | Failure | Job | Fingerprint |");
+        println!("|------|---------|-----|-------------|");
+        for error in &result.errors {
+            println!("| {} | {} | {} | `{}` |", error.frequency, error.error_message, error.job_name, error.fingerprint);
+        }
+        println!();
+    }
+
     if !result.recommendations.is_empty() {
         println!("## Recommendations\n");
         for rec in &result.recommendations {
@@ -354,3 +461,125 @@ fn print_markdown_report(result: &AnalysisResult) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::Job;
+    use crate::log_parser::parse_log;
+
+    fn failed_analysis(run_id: u64, log: &str) -> WorkflowAnalysis {
+        WorkflowAnalysis {
+            run: WorkflowRun {
+                id: run_id,
+                name: "CI".to_string(),
+                status: "completed".to_string(),
+                conclusion: Some("failure".to_string()),
+                html_url: String::new(),
+                created_at: String::new(),
+                updated_at: String::new(),
+                run_attempt: 1,
+                workflow_id: 7,
+                head_branch: "main".to_string(),
+                head_sha: "abc".to_string(),
+                path: String::new(),
+                event: "push".to_string(),
+            },
+            jobs: vec![Job {
+                id: run_id * 10,
+                name: "test".to_string(),
+                status: "completed".to_string(),
+                conclusion: Some("failure".to_string()),
+                started_at: None,
+                completed_at: None,
+                steps: None,
+                labels: Vec::new(),
+            }],
+            job_logs: [(run_id * 10, log.to_string())].into(),
+            errors: vec!["Job 'test' failed".to_string()],
+            failures: parse_log(log),
+            warnings: Vec::new(),
+            duration_seconds: None,
+        }
+    }
+
+    #[test]
+    fn test_identical_failures_grouped_across_runs() {
+        let analyses = vec![
+            failed_analysis(1, "error[E0425]: cannot find value `x`\n  --> src/lib.rs:10:5\n"),
+            failed_analysis(2, "error[E0425]: cannot find value `x`\n  --> src/lib.rs:12:5\n"),
+            failed_analysis(3, "test api::login ... FAILED\n"),
+        ];
+
+        let errors = group_errors(&analyses);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].frequency, 2);
+        assert_eq!(errors[0].run_ids, vec![1, 2]);
+        assert_eq!(errors[0].file.as_deref(), Some("src/lib.rs"));
+        assert_eq!(errors[1].run_ids, vec![3]);
+    }
+
+    #[test]
+    fn test_unparsed_job_failure_falls_back_to_job_signature() {
+        let errors = group_errors(&[failed_analysis(1, "something odd\n"), failed_analysis(2, "")]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error_message, "Job 'test' failed");
+        assert_eq!(errors[0].frequency, 2);
+    }
+
+    #[test]
+    fn test_failures_attributed_to_their_job_and_step() {
+        let job = |id: u64, name: &str, step: &str| Job {
+            id,
+            name: name.to_string(),
+            status: "completed".to_string(),
+            conclusion: Some("failure".to_string()),
+            started_at: None,
+            completed_at: None,
+            steps: Some(vec![crate::github::Step {
+                name: step.to_string(),
+                status: "completed".to_string(),
+                conclusion: Some("failure".to_string()),
+                number: 2,
+                started_at: None,
+                completed_at: None,
+            }]),
+            labels: Vec::new(),
+        };
+        let mut analysis = failed_analysis(1, "");
+        analysis.jobs = vec![job(11, "lint", "Clippy"), job(12, "test", "Unit tests"), job(13, "docs", "Build docs")];
+        analysis.failures = parse_log("test api::login ... FAILED\n").into_iter().map(|f| f.in_job("test")).collect();
+
+        let errors = group_errors(&[analysis]);
+        let summary: Vec<(&str, Option<&str>, &str)> = errors.iter()
+            .map(|e| (e.job_name.as_str(), e.step_name.as_deref(), e.kind.as_str()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("test", Some("Unit tests"), "test"),
+            ("lint", Some("Clippy"), "job"),
+            ("docs", Some("Build docs"), "job"),
+        ]);
+        assert_ne!(errors[1].fingerprint, errors[2].fingerprint);
+    }
+
+    #[tokio::test]
+    async fn test_failures_map_to_sarif_findings() {
+        let analyses = vec![failed_analysis(1, "error[E0425]: cannot find value `x`\n  --> src/lib.rs:10:5\n")];
+        let result = build_analysis_result(&analyses, &["errors".to_string()]).await;
+
+        let sarif = to_report(&result).to_sarif();
+        let finding = &sarif["runs"][0]["results"][0];
+        assert_eq!(finding["ruleId"], "ci/compile");
+        assert_eq!(finding["level"], "error");
+        assert_eq!(finding["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "src/lib.rs");
+        assert_eq!(finding["locations"][0]["physicalLocation"]["region"]["startLine"], 10);
+
+        let mut analysis = failed_analysis(2, "");
+        analysis.run.path = ".github/workflows/ci.yml".to_string();
+        let result = build_analysis_result(&[analysis], &["errors".to_string()]).await;
+        let sarif = to_report(&result).to_sarif();
+        let finding = &sarif["runs"][0]["results"][0];
+        assert_eq!(finding["ruleId"], "ci/job");
+        assert_eq!(finding["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], ".github/workflows/ci.yml");
+    }
+}
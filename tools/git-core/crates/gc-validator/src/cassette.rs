@@ -0,0 +1,502 @@
+//! # Cassettes
+//!
+//! Record/replay of GitHub API traffic for offline tests and reproducible
+//! bug reports. A `CassetteServer` is a small local HTTP server that both the
+//! reqwest-based `GitHubClient` (`with_base_url`) and octocrab
+//! (`GuardianCore::from_token_at`, `Octocrab::builder().base_uri`) can point at:
+//!
+//! - **replay** serves recorded responses, matched on method and path (query
+//!   included). Identical requests consume recorded interactions in order.
+//!   Requests without an unused recording get a 404 and are listed by
+//!   `unmatched()`. A recording whose path matches but whose query differs is
+//!   still served, as the closest answer, but also listed by `unmatched()`.
+//! - **record** forwards every request to the real API and appends the
+//!   interaction to the cassette file. Request headers (tokens) are not stored.
+//!   Of the response headers, `link` (pagination), `retry-after` and the
+//!   `x-ratelimit-*` headers are kept; `link` URLs are stored relative to the
+//!   API root and served pointing back at the replay server.
+//!
+//! ```text
+//! { "interactions": [
+//!   { "request":  { "method": "GET", "path": "/repos/acme/widgets/actions/runs?per_page=50" },
+//!     "response": { "status": 200, "body": { "total_count": 0, "workflow_runs": [] } } } ] }
+//! ```
+//!
+//! JSON bodies are stored as JSON; anything else (job logs) as a string with
+//! its `content_type`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+const JSON_CONTENT_TYPE: &str = "application/json; charset=utf-8";
+
+/// Response headers worth replaying: pagination and rate limiting
+const RECORDED_HEADERS: &[&str] = &[
+    "link",
+    "retry-after",
+    "x-ratelimit-limit",
+    "x-ratelimit-remaining",
+    "x-ratelimit-reset",
+    "x-ratelimit-resource",
+    "x-ratelimit-used",
+];
+
+/// Recorded API interactions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query, e.g. `/repos/acme/widgets/actions/runs?per_page=50`
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default = "default_content_type")]
+    pub content_type: String,
+    /// Lower-case header names; `link` URLs are relative to the API root
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Value,
+}
+
+fn default_content_type() -> String {
+    JSON_CONTENT_TYPE.to_string()
+}
+
+impl RecordedResponse {
+    fn bytes(&self) -> Vec<u8> {
+        match &self.body {
+            Value::String(text) if !self.content_type.contains("json") => text.clone().into_bytes(),
+            Value::Null => Vec::new(),
+            body => body.to_string().into_bytes(),
+        }
+    }
+}
+
+impl Cassette {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read cassette {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid cassette {}", path.display()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write cassette {}", path.display()))
+    }
+
+    /// Add a JSON response for `method path`
+    #[cfg(test)]
+    pub fn with(mut self, method: &str, path: &str, status: u16, body: Value) -> Self {
+        self.interactions.push(Interaction {
+            request: RecordedRequest { method: method.to_string(), path: path.to_string(), body: None },
+            response: RecordedResponse { status, content_type: default_content_type(), headers: BTreeMap::new(), body },
+        });
+        self
+    }
+}
+
+enum Mode {
+    Replay { used: Vec<bool> },
+    Record { upstream: String, file: PathBuf, client: reqwest::Client },
+}
+
+struct State {
+    cassette: Cassette,
+    mode: Mode,
+    /// Base URL of this server, for `link` headers
+    url: String,
+    /// `METHOD path` of every request served
+    requests: Vec<String>,
+    unmatched: Vec<String>,
+}
+
+impl State {
+    /// Next unused interaction for the request, and whether its query matched too
+    fn replay(&mut self, method: &str, path: &str) -> Option<(RecordedResponse, bool)> {
+        let Mode::Replay { used } = &mut self.mode else {
+            return None;
+        };
+        let interactions = &self.cassette.interactions;
+        let without_query = |p: &str| p.split('?').next().unwrap_or(p).to_string();
+        let exact = |i: &Interaction| i.request.method.eq_ignore_ascii_case(method) && i.request.path == path;
+        let loose = |i: &Interaction| {
+            i.request.method.eq_ignore_ascii_case(method) && without_query(&i.request.path) == without_query(path)
+        };
+
+        let (index, exact) = (0..interactions.len()).find(|&i| !used[i] && exact(&interactions[i])).map(|i| (i, true))
+            .or_else(|| (0..interactions.len()).find(|&i| !used[i] && loose(&interactions[i])).map(|i| (i, false)))?;
+        used[index] = true;
+        Some((interactions[index].response.clone(), exact))
+    }
+}
+
+/// Local HTTP server replaying or recording a cassette
+pub struct CassetteServer {
+    url: String,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl CassetteServer {
+    /// Serve the interactions of a cassette
+    pub async fn replay(cassette: Cassette) -> Result<Self> {
+        let used = vec![false; cassette.interactions.len()];
+        Self::start(cassette, Mode::Replay { used }).await
+    }
+
+    pub async fn replay_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::replay(Cassette::load(path)?).await
+    }
+
+    /// Forward requests to `upstream` and append them to the cassette at `file`
+    pub async fn record<P: AsRef<Path>>(file: P, upstream: &str) -> Result<Self> {
+        let file = file.as_ref().to_path_buf();
+        let cassette = if file.exists() { Cassette::load(&file)? } else { Cassette::default() };
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()?;
+        Self::start(cassette, Mode::Record {
+            upstream: upstream.trim_end_matches('/').to_string(),
+            file,
+            client,
+        }).await
+    }
+
+    async fn start(cassette: Cassette, mode: Mode) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new(Mutex::new(State {
+            cassette,
+            mode,
+            url: url.clone(),
+            requests: Vec::new(),
+            unmatched: Vec::new(),
+        }));
+
+        let server_state = state.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, state).await {
+                        debug!("Cassette connection error: {:#}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { url, state, handle })
+    }
+
+    /// Base URL to give to the API clients
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// `METHOD path` of every request served so far
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().map(|s| s.requests.clone()).unwrap_or_default()
+    }
+
+    /// Requests that had no recorded interaction (replay only)
+    pub fn unmatched(&self) -> Vec<String> {
+        self.state.lock().map(|s| s.unmatched.clone()).unwrap_or_default()
+    }
+}
+
+impl Drop for CassetteServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<Mutex<State>>) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    // Keep-alive: serve requests until the client closes the connection
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).await? == 0 {
+            return Ok(());
+        }
+        let mut parts = request_line.split_whitespace();
+        let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+            return Ok(());
+        };
+        let (method, path) = (method.to_string(), path.to_string());
+
+        let mut headers = Vec::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).await?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                let (name, value) = (name.trim().to_string(), value.trim().to_string());
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.parse().unwrap_or(0);
+                }
+                headers.push((name, value));
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await?;
+
+        let response = respond(&state, &method, &path, headers, body).await;
+        let bytes = response.bytes();
+        let reason = reqwest::StatusCode::from_u16(response.status)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("");
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n",
+            response.status, reason, response.content_type, bytes.len()
+        );
+        let url = state.lock().map(|s| s.url.clone()).unwrap_or_default();
+        for (name, value) in &response.headers {
+            let value = if name == "link" { value.replace("</", &format!("<{}/", url)) } else { value.clone() };
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        let stream = reader.get_mut();
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&bytes).await?;
+        stream.flush().await?;
+    }
+}
+
+async fn respond(
+    state: &Arc<Mutex<State>>,
+    method: &str,
+    path: &str,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+) -> RecordedResponse {
+    let upstream = {
+        let mut state = state.lock().expect("cassette state poisoned");
+        state.requests.push(format!("{} {}", method, path));
+        match &state.mode {
+            Mode::Record { upstream, client, .. } => Some((upstream.clone(), client.clone())),
+            Mode::Replay { .. } => {
+                if let Some((response, exact)) = state.replay(method, path) {
+                    if !exact {
+                        warn!("📼 {} {} only matched a recording with a different query", method, path);
+                        state.unmatched.push(format!("{} {} (query differs)", method, path));
+                    }
+                    return response;
+                }
+                warn!("📼 No recorded interaction for {} {}", method, path);
+                state.unmatched.push(format!("{} {}", method, path));
+                return RecordedResponse {
+                    status: 404,
+                    content_type: default_content_type(),
+                    headers: BTreeMap::new(),
+                    body: serde_json::json!({
+                        "message": format!("No recorded interaction for {} {}", method, path),
+                        "documentation_url": null,
+                    }),
+                };
+            }
+        }
+    };
+
+    let (upstream, client) = upstream.expect("record mode");
+    let response = match forward(&client, &upstream, method, path, &headers, &body).await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("📼 Failed to forward {} {}: {:#}", method, path, e);
+            return RecordedResponse {
+                status: 502,
+                content_type: default_content_type(),
+                headers: BTreeMap::new(),
+                body: serde_json::json!({ "message": e.to_string(), "documentation_url": null }),
+            };
+        }
+    };
+
+    let mut state = state.lock().expect("cassette state poisoned");
+    state.cassette.interactions.push(Interaction {
+        request: RecordedRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: serde_json::from_slice(&body).ok(),
+        },
+        response: response.clone(),
+    });
+    if let Mode::Record { file, .. } = &state.mode {
+        if let Err(e) = state.cassette.save(file) {
+            warn!("📼 {:#}", e);
+        }
+    }
+    response
+}
+
+async fn forward(
+    client: &reqwest::Client,
+    upstream: &str,
+    method: &str,
+    path: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+) -> Result<RecordedResponse> {
+    let method = reqwest::Method::from_bytes(method.as_bytes())?;
+    let mut request = client.request(method, format!("{}{}", upstream, path));
+    for (name, value) in headers {
+        if !["host", "content-length", "connection"].contains(&name.to_ascii_lowercase().as_str()) {
+            request = request.header(name, value);
+        }
+    }
+    let response = request.body(body.to_vec()).send().await?;
+
+    let status = response.status().as_u16();
+    let content_type = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("text/plain")
+        .to_string();
+    let headers = response.headers().iter()
+        .filter(|(name, _)| RECORDED_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            // Keep pagination links independent of the upstream host
+            let value = if name.as_str() == "link" { value.replace(&format!("<{}/", upstream), "</") } else { value.to_string() };
+            Some((name.as_str().to_string(), value))
+        })
+        .collect();
+    let text = response.text().await?;
+    let body = if content_type.contains("json") {
+        serde_json::from_str(&text).unwrap_or(Value::String(text))
+    } else if text.is_empty() {
+        Value::Null
+    } else {
+        Value::String(text)
+    };
+
+    Ok(RecordedResponse { status, content_type, headers, body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::{GitHubClient, RunFilter};
+
+    #[tokio::test]
+    async fn test_replay_matches_path_and_reports_unmatched() {
+        let cassette = Cassette::default()
+            .with("GET", "/repos/acme/widgets", 200, serde_json::json!({ "default_branch": "trunk" }))
+            .with("GET", "/repos/acme/widgets/actions/runs?per_page=5", 200,
+                serde_json::json!({ "total_count": 0, "workflow_runs": [] }));
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("token", "acme/widgets", 2).with_base_url(server.url());
+
+        assert_eq!(client.get_default_branch().await.unwrap(), "trunk");
+        // Same path, different query: served, but reported
+        assert!(client.collect_runs(&RunFilter::default().with_limit(50)).await.unwrap().is_empty());
+        assert!(client.get_pull_request(1).await.is_err());
+        // Recordings are used up, not repeated
+        assert!(client.get_default_branch().await.is_err());
+
+        assert_eq!(server.unmatched(), vec![
+            "GET /repos/acme/widgets/actions/runs?per_page=50&page=1 (query differs)",
+            "GET /repos/acme/widgets/pulls/1",
+            "GET /repos/acme/widgets",
+        ]);
+        assert_eq!(server.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let upstream = CassetteServer::replay(Cassette::default()
+            .with("GET", "/repos/acme/widgets", 200, serde_json::json!({ "default_branch": "main" })))
+            .await
+            .unwrap();
+        let file = std::env::temp_dir().join(format!("gc-cassette-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+
+        {
+            let recorder = CassetteServer::record(&file, upstream.url()).await.unwrap();
+            let client = GitHubClient::new("token", "acme/widgets", 2).with_base_url(recorder.url());
+            assert_eq!(client.get_default_branch().await.unwrap(), "main");
+        }
+        drop(upstream);
+
+        let replay = CassetteServer::replay_file(&file).await.unwrap();
+        let client = GitHubClient::new("token", "acme/widgets", 2).with_base_url(replay.url());
+        assert_eq!(client.get_default_branch().await.unwrap(), "main");
+        assert!(replay.unmatched().is_empty());
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    async fn all_issues(base: &str) -> Vec<Value> {
+        let octocrab = octocrab::Octocrab::builder().base_uri(base).unwrap().build().unwrap();
+        let page: octocrab::Page<Value> = octocrab
+            .get("/repos/acme/widgets/issues?per_page=1", None::<&()>)
+            .await
+            .unwrap();
+        octocrab.all_pages(page).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_link_headers_survive_record_and_replay() {
+        let mut cassette = Cassette::default()
+            .with("GET", "/repos/acme/widgets/issues?per_page=1", 200, serde_json::json!([{ "number": 1 }]))
+            .with("GET", "/repos/acme/widgets/issues?per_page=1&page=2", 200, serde_json::json!([{ "number": 2 }]));
+        cassette.interactions[0].response.headers.insert(
+            "link".to_string(),
+            "</repos/acme/widgets/issues?per_page=1&page=2>; rel=\"next\"".to_string(),
+        );
+        cassette.interactions[0].response.headers.insert("x-ratelimit-remaining".to_string(), "4999".to_string());
+        let upstream = CassetteServer::replay(cassette).await.unwrap();
+        let file = std::env::temp_dir().join(format!("gc-cassette-link-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+
+        {
+            let recorder = CassetteServer::record(&file, upstream.url()).await.unwrap();
+            assert_eq!(all_issues(recorder.url()).await.len(), 2);
+        }
+        drop(upstream);
+
+        let recorded = Cassette::load(&file).unwrap();
+        let headers = &recorded.interactions[0].response.headers;
+        assert_eq!(headers["link"], "</repos/acme/widgets/issues?per_page=1&page=2>; rel=\"next\"");
+        assert_eq!(headers["x-ratelimit-remaining"], "4999");
+
+        let replay = CassetteServer::replay_file(&file).await.unwrap();
+        let numbers: Vec<_> = all_issues(replay.url()).await.iter().map(|i| i["number"].clone()).collect();
+        assert_eq!(numbers, vec![serde_json::json!(1), serde_json::json!(2)]);
+        assert!(replay.unmatched().is_empty());
+        std::fs::remove_file(&file).unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+//! # CI Runs
+//!
+//! Trigger `workflow_dispatch` runs and follow runs while they execute
+//! (`gc ci run` / `gc ci logs --follow`).
+//!
+//! The Actions API has no streaming endpoint, so following polls the run and
+//! its jobs: job and step status changes are reported as they are seen, and job
+//! logs are fetched again on every poll with only the new lines emitted. The
+//! job log endpoint usually answers 404 until the job completes, so in practice
+//! a job's lines arrive once it finishes. A failed download is retried on the
+//! next poll and once more after the run completes.
+
+use crate::github::{GitHubClient, Job, RunFilter, WorkflowRun};
+use crate::log_parser::clean_line;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Default delay between polls
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long to wait for a dispatched run to show up
+pub const DEFAULT_DISPATCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Something that changed in a followed run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FollowEvent {
+    Job { job: String, status: String, conclusion: Option<String> },
+    Step { job: String, step: String, status: String, conclusion: Option<String> },
+    Log { job: String, line: String },
+}
+
+/// Exit codes of a followed run; 1 is left to runtime errors, as for `gc guardian`
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 2;
+pub const EXIT_CANCELLED: i32 = 3;
+pub const EXIT_TIMED_OUT: i32 = 4;
+pub const EXIT_OTHER: i32 = 5;
+
+/// How far the runner's clock may be ahead of ours when matching a dispatched run
+const CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+/// Map a run conclusion to a process exit code
+///
+/// `success`, `neutral` and `skipped` exit 0, `failure` 2, `cancelled` 3,
+/// `timed_out` 4, anything else (`action_required`, `stale`, none) 5.
+pub fn exit_code(conclusion: Option<&str>) -> i32 {
+    match conclusion {
+        Some("success") | Some("neutral") | Some("skipped") => EXIT_SUCCESS,
+        Some("failure") => EXIT_FAILURE,
+        Some("cancelled") => EXIT_CANCELLED,
+        Some("timed_out") => EXIT_TIMED_OUT,
+        _ => EXIT_OTHER,
+    }
+}
+
+/// Workflow file name or ID as the API expects it (`.github/workflows/ci.yml` → `ci.yml`)
+pub fn workflow_id(workflow: &str) -> &str {
+    workflow.rsplit('/').next().unwrap_or(workflow)
+}
+
+/// Dispatch a workflow and wait for the run it created
+///
+/// The API does not return the created run, so it is looked up among the
+/// runs of the workflow for the same commit and user: the newest one that did
+/// not exist before the dispatch. Runs older than the dispatch (less
+/// [`CLOCK_SKEW`]) are never taken.
+pub async fn dispatch_and_wait(
+    client: &GitHubClient,
+    workflow: &str,
+    git_ref: &str,
+    inputs: &BTreeMap<String, String>,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<WorkflowRun> {
+    let workflow = workflow_id(workflow);
+    let head_sha = client.get_commit_sha(git_ref).await?;
+    // Installation tokens have no user; the commit alone then narrows the runs
+    let actor = match client.get_authenticated_user().await {
+        Ok(login) => Some(login),
+        Err(e) => {
+            debug!("Not filtering dispatched runs by actor: {}", e);
+            None
+        }
+    };
+    let filter = RunFilter {
+        workflow: Some(workflow.to_string()),
+        branch: Some(git_ref.to_string()),
+        event: Some("workflow_dispatch".to_string()),
+        actor,
+        head_sha: Some(head_sha),
+        limit: Some(10),
+        ..Default::default()
+    };
+
+    let existing: HashSet<u64> = client.collect_runs(&filter).await?.iter().map(|r| r.id).collect();
+    let dispatched_at = Utc::now() - chrono::Duration::from_std(CLOCK_SKEW)?;
+    client.dispatch_workflow(workflow, git_ref, inputs).await?;
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let runs = client.collect_runs(&filter).await?;
+        let created = runs.into_iter()
+            .filter(|r| !existing.contains(&r.id))
+            .filter(|r| {
+                DateTime::parse_from_rfc3339(&r.created_at)
+                    .map(|dt| dt >= dispatched_at)
+                    .unwrap_or(false)
+            })
+            .max_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        if let Some(run) = created {
+            return Ok(run);
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            bail!("No run of {} on {} appeared within {}s", workflow, git_ref, timeout.as_secs());
+        }
+        debug!("Waiting for the dispatched run of {}...", workflow);
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// State of a followed run between polls
+#[derive(Debug, Default)]
+struct Tracker {
+    jobs: HashMap<u64, (String, Option<String>)>,
+    steps: HashMap<(u64, u32), (String, Option<String>)>,
+    /// Log lines already emitted per job
+    log_lines: HashMap<u64, usize>,
+    /// Jobs whose complete log has been emitted
+    logs_done: HashSet<u64>,
+}
+
+impl Tracker {
+    fn status_events(&mut self, jobs: &[Job]) -> Vec<FollowEvent> {
+        let mut events = Vec::new();
+        for job in jobs {
+            let state = (job.status.clone(), job.conclusion.clone());
+            if self.jobs.get(&job.id) != Some(&state) {
+                events.push(FollowEvent::Job {
+                    job: job.name.clone(),
+                    status: job.status.clone(),
+                    conclusion: job.conclusion.clone(),
+                });
+                self.jobs.insert(job.id, state);
+            }
+
+            for step in job.steps.iter().flatten() {
+                let state = (step.status.clone(), step.conclusion.clone());
+                // Queued steps are noise; report them once they start
+                if step.status == "queued" || self.steps.get(&(job.id, step.number)) == Some(&state) {
+                    continue;
+                }
+                events.push(FollowEvent::Step {
+                    job: job.name.clone(),
+                    step: step.name.clone(),
+                    status: step.status.clone(),
+                    conclusion: step.conclusion.clone(),
+                });
+                self.steps.insert((job.id, step.number), state);
+            }
+        }
+        events
+    }
+
+    /// New lines of a job log since the last poll
+    fn log_events(&mut self, job: &Job, log: &str) -> Vec<FollowEvent> {
+        let seen = self.log_lines.entry(job.id).or_default();
+        let lines: Vec<&str> = log.lines().collect();
+        let events = lines.iter()
+            .skip(*seen)
+            .map(|line| FollowEvent::Log { job: job.name.clone(), line: clean_line(line) })
+            .collect();
+        *seen = (*seen).max(lines.len());
+        if job.status == "completed" {
+            self.logs_done.insert(job.id);
+        }
+        events
+    }
+}
+
+/// Follow a run until it completes, reporting changes to `on_event`
+///
+/// With `logs` unset only job and step status changes are reported.
+pub async fn follow_run<F>(
+    client: &GitHubClient,
+    run_id: u64,
+    logs: bool,
+    poll_interval: Duration,
+    mut on_event: F,
+) -> Result<WorkflowRun>
+where
+    F: FnMut(FollowEvent),
+{
+    let mut tracker = Tracker::default();
+
+    loop {
+        // Read the run first so jobs fetched afterwards are at least as recent
+        let run = client.get_workflow_run(run_id).await?;
+        let jobs = client.get_jobs(run_id).await?;
+
+        for event in tracker.status_events(&jobs) {
+            on_event(event);
+        }
+
+        if logs {
+            fetch_logs(client, &jobs, &mut tracker, &mut on_event).await;
+        }
+
+        if run.status == "completed" {
+            if logs {
+                // Last try for logs whose download failed above
+                fetch_logs(client, &jobs, &mut tracker, &mut on_event).await;
+            }
+            return Ok(run);
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// Emit new log lines of started jobs whose complete log has not been emitted yet
+///
+/// A job whose log cannot be downloaded is left pending for the next call.
+async fn fetch_logs<F>(client: &GitHubClient, jobs: &[Job], tracker: &mut Tracker, on_event: &mut F)
+where
+    F: FnMut(FollowEvent),
+{
+    let pending: Vec<&Job> = jobs.iter()
+        .filter(|j| j.status != "queued" && j.status != "waiting")
+        .filter(|j| j.conclusion.as_deref() != Some("skipped"))
+        .filter(|j| !tracker.logs_done.contains(&j.id))
+        .collect();
+    for job in pending {
+        match client.get_job_logs(job.id).await {
+            Ok(log) => {
+                for event in tracker.log_events(job, &log) {
+                    on_event(event);
+                }
+            }
+            Err(e) => warn!("Failed to download log of job {} ({}): {}", job.name, job.id, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cassette::{Cassette, CassetteServer};
+    use serde_json::json;
+
+    fn run(status: &str, conclusion: Option<&str>) -> serde_json::Value {
+        run_created(500, Utc::now(), status, conclusion)
+    }
+
+    fn run_created(id: u64, created_at: DateTime<Utc>, status: &str, conclusion: Option<&str>) -> serde_json::Value {
+        json!({
+            "id": id, "name": "Release", "status": status, "conclusion": conclusion,
+            "html_url": format!("https://github.com/acme/widgets/actions/runs/{}", id),
+            "created_at": created_at.to_rfc3339(), "updated_at": created_at.to_rfc3339(),
+            "run_attempt": 1, "workflow_id": 3, "head_branch": "main", "head_sha": "abc"
+        })
+    }
+
+    fn jobs(status: &str, conclusion: Option<&str>, step_status: &str) -> serde_json::Value {
+        json!({ "total_count": 1, "jobs": [{
+            "id": 600, "name": "build", "status": status, "conclusion": conclusion,
+            "started_at": null, "completed_at": null,
+            "steps": [
+                { "name": "Checkout", "status": "completed", "conclusion": "success", "number": 1 },
+                { "name": "Build", "status": step_status, "conclusion": conclusion, "number": 2 }
+            ]
+        }]})
+    }
+
+    #[test]
+    fn test_exit_codes_and_workflow_ids() {
+        assert_eq!(exit_code(Some("success")), 0);
+        assert_eq!(exit_code(Some("failure")), 2);
+        assert_eq!(exit_code(Some("cancelled")), 3);
+        assert_eq!(exit_code(Some("timed_out")), 4);
+        assert_eq!(exit_code(None), 5);
+        assert_eq!(workflow_id(".github/workflows/release.yml"), "release.yml");
+        assert_eq!(workflow_id("42"), "42");
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_then_follow_until_completed() {
+        let runs = "/repos/acme/widgets/actions/workflows/release.yml/runs?branch=main&event=workflow_dispatch&actor=octo&head_sha=abc&per_page=10&page=1";
+        let cassette = Cassette::default()
+            .with("GET", "/repos/acme/widgets/commits/main", 200, json!({ "sha": "abc" }))
+            .with("GET", "/user", 200, json!({ "login": "octo" }))
+            .with("GET", runs, 200, json!({ "total_count": 0, "workflow_runs": [] }))
+            .with("POST", "/repos/acme/widgets/actions/workflows/release.yml/dispatches", 204, json!(null))
+            .with("GET", runs, 200, json!({ "total_count": 1, "workflow_runs": [run("queued", None)] }))
+            .with("GET", "/repos/acme/widgets/actions/runs/500", 200, run("in_progress", None))
+            .with("GET", "/repos/acme/widgets/actions/runs/500", 200, run("completed", Some("failure")))
+            .with("GET", "/repos/acme/widgets/actions/runs/500/jobs?per_page=100&page=1", 200, jobs("in_progress", None, "in_progress"))
+            .with("GET", "/repos/acme/widgets/actions/runs/500/jobs?per_page=100&page=1", 200, jobs("completed", Some("failure"), "completed"))
+            .with("GET", "/repos/acme/widgets/actions/jobs/600/logs", 200, json!("line 1\nline 2\n"))
+            .with("GET", "/repos/acme/widgets/actions/jobs/600/logs", 200, json!("line 1\nline 2\nerror: boom\n"));
+        let mut cassette = cassette;
+        for interaction in cassette.interactions.iter_mut().filter(|i| i.request.path.ends_with("/logs")) {
+            interaction.response.content_type = "text/plain".to_string();
+        }
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("test-token", "acme/widgets", 4).with_base_url(server.url());
+
+        let inputs = BTreeMap::from([("version".to_string(), "1.2.0".to_string())]);
+        let created = dispatch_and_wait(&client, ".github/workflows/release.yml", "main", &inputs, Duration::ZERO, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(created.id, 500);
+
+        let mut events = Vec::new();
+        let finished = follow_run(&client, created.id, true, Duration::ZERO, |e| events.push(e)).await.unwrap();
+
+        assert_eq!(exit_code(finished.conclusion.as_deref()), EXIT_FAILURE);
+        let logs: Vec<&str> = events.iter()
+            .filter_map(|e| match e {
+                FollowEvent::Log { line, .. } => Some(line.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(logs, vec!["line 1", "line 2", "error: boom"]);
+        assert!(events.contains(&FollowEvent::Step {
+            job: "build".to_string(),
+            step: "Build".to_string(),
+            status: "completed".to_string(),
+            conclusion: Some("failure".to_string()),
+        }));
+        // Checkout is reported once, not on every poll
+        assert_eq!(events.iter().filter(|e| matches!(e, FollowEvent::Step { step, .. } if step == "Checkout")).count(), 1);
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+    }
+
+    #[tokio::test]
+    async fn test_follow_retries_failed_log_downloads() {
+        let cassette = Cassette::default()
+            .with("GET", "/repos/acme/widgets/actions/runs/500", 200, run("completed", Some("success")))
+            .with("GET", "/repos/acme/widgets/actions/runs/500/jobs?per_page=100&page=1", 200, jobs("completed", Some("success"), "completed"))
+            .with("GET", "/repos/acme/widgets/actions/jobs/600/logs", 404, json!({ "message": "Not Found" }))
+            .with("GET", "/repos/acme/widgets/actions/jobs/600/logs", 200, json!("line 1\nline 2\n"));
+        let mut cassette = cassette;
+        if let Some(interaction) = cassette.interactions.last_mut() {
+            interaction.response.content_type = "text/plain".to_string();
+        }
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("test-token", "acme/widgets", 4).with_base_url(server.url());
+
+        let mut events = Vec::new();
+        follow_run(&client, 500, true, Duration::ZERO, |e| events.push(e)).await.unwrap();
+
+        let logs: Vec<&str> = events.iter()
+            .filter_map(|e| match e {
+                FollowEvent::Log { line, .. } => Some(line.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(logs, vec!["line 1", "line 2"]);
+        assert_eq!(server.requests().iter().filter(|r| r.ends_with("/logs")).count(), 2);
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_takes_the_newest_new_run() {
+        let runs = "/repos/acme/widgets/actions/workflows/release.yml/runs?branch=main&event=workflow_dispatch&actor=octo&head_sha=abc&per_page=10&page=1";
+        let now = Utc::now();
+        // 499 was already running, 400 predates the dispatch; 501 and 502 both appeared since
+        let before = json!({ "total_count": 1, "workflow_runs": [run_created(499, now, "in_progress", None)] });
+        let after = json!({ "total_count": 4, "workflow_runs": [
+            run_created(502, now + chrono::Duration::seconds(1), "queued", None),
+            run_created(501, now, "queued", None),
+            run_created(499, now, "in_progress", None),
+            run_created(400, now - chrono::Duration::hours(1), "completed", Some("success")),
+        ]});
+        let cassette = Cassette::default()
+            .with("GET", "/repos/acme/widgets/commits/main", 200, json!({ "sha": "abc" }))
+            .with("GET", "/user", 200, json!({ "login": "octo" }))
+            .with("GET", runs, 200, before)
+            .with("POST", "/repos/acme/widgets/actions/workflows/release.yml/dispatches", 204, json!(null))
+            .with("GET", runs, 200, after);
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("test-token", "acme/widgets", 4).with_base_url(server.url());
+
+        let created = dispatch_and_wait(&client, "release.yml", "main", &BTreeMap::new(), Duration::ZERO, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(created.id, 502);
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+    }
+}
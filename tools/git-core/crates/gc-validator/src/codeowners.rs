@@ -0,0 +1,248 @@
+//! # CODEOWNERS Support
+//!
+//! Parses GitHub `CODEOWNERS` files and works out which owners still need to
+//! approve a change. Follows GitHub semantics:
+//!
+//! - The file is looked up in `.github/`, the repository root, then `docs/`
+//! - The last matching pattern wins
+//! - Patterns use gitignore-style syntax (`*`, `**`, `?`, leading `/`,
+//!   trailing `/`)
+//!
+//! Owners are `@user` or `@org/team` handles. Email owners cannot be mapped
+//! to GitHub accounts without extra API calls, so they are ignored; a rule
+//! listing only email owners never requires an approval.
+
+use regex::Regex;
+use serde::Serialize;
+
+/// Locations GitHub reads CODEOWNERS from, in priority order
+pub const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// A single `pattern owner...` line
+#[derive(Debug, Clone)]
+pub struct OwnerRule {
+    pub pattern: String,
+    pub owners: Vec<String>,
+    regex: Regex,
+}
+
+impl OwnerRule {
+    pub fn matches(&self, file: &str) -> bool {
+        self.regex.is_match(file.trim_start_matches('/'))
+    }
+}
+
+/// An owned path that has no approval from any of its owners
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OwnerApprovalGap {
+    pub pattern: String,
+    pub owners: Vec<String>,
+    pub files: Vec<String>,
+}
+
+/// Parsed CODEOWNERS file
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    pub rules: Vec<OwnerRule>,
+}
+
+impl CodeOwners {
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                // Strip trailing comments
+                let line = line.split(" #").next().unwrap_or(line);
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?.to_string();
+                let owners: Vec<String> = parts
+                    .filter(|o| o.starts_with('@'))
+                    .map(String::from)
+                    .collect();
+                let regex = pattern_to_regex(&pattern)?;
+                Some(OwnerRule { pattern, owners, regex })
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Rule owning `file` (last match wins)
+    pub fn rule_for(&self, file: &str) -> Option<&OwnerRule> {
+        self.rules.iter().rev().find(|rule| rule.matches(file))
+    }
+
+    /// Owned paths touched by `files` that none of the `approvers` own
+    ///
+    /// `approvers` are owner handles that approved the PR, i.e. `@login` for
+    /// each approving reviewer plus `@org/team` for teams they belong to.
+    pub fn missing_approvals(&self, files: &[String], approvers: &[String]) -> Vec<OwnerApprovalGap> {
+        let mut gaps: Vec<OwnerApprovalGap> = Vec::new();
+
+        for file in files {
+            let Some(rule) = self.rule_for(file) else {
+                continue;
+            };
+
+            if rule.owners.is_empty() {
+                continue;
+            }
+
+            let approved = rule.owners.iter().any(|owner| {
+                approvers.iter().any(|a| a.eq_ignore_ascii_case(owner))
+            });
+            if approved {
+                continue;
+            }
+
+            match gaps.iter_mut().find(|g| g.pattern == rule.pattern) {
+                Some(gap) => gap.files.push(file.clone()),
+                None => gaps.push(OwnerApprovalGap {
+                    pattern: rule.pattern.clone(),
+                    owners: rule.owners.clone(),
+                    files: vec![file.clone()],
+                }),
+            }
+        }
+
+        gaps
+    }
+
+    /// Team owners (`@org/team`) of the given files
+    pub fn teams_for(&self, files: &[String]) -> Vec<String> {
+        let mut teams: Vec<String> = files
+            .iter()
+            .filter_map(|f| self.rule_for(f))
+            .flat_map(|rule| rule.owners.iter())
+            .filter(|owner| owner.contains('/'))
+            .cloned()
+            .collect();
+        teams.sort();
+        teams.dedup();
+        teams
+    }
+}
+
+/// Convert a gitignore-style CODEOWNERS pattern into an anchored regex
+fn pattern_to_regex(pattern: &str) -> Option<Regex> {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+
+    let mut re = String::new();
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` also matches zero directories
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    let prefix = if anchored { "^" } else { "^(.*/)?" };
+
+    // `docs/*` owns direct children only; plain names also own their subtree
+    let last_segment = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    let suffix = if dir_only {
+        "/.*$"
+    } else if last_segment.contains('*') {
+        "$"
+    } else {
+        "(/.*)?$"
+    };
+
+    Regex::new(&format!("{}{}{}", prefix, re, suffix)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# Default owners
+*                   @org/maintainers
+
+/docs/              @alice      # documentation
+*.rs                @org/rust-team
+/crates/gc-validator/ @bob @carol
+scripts/*.sh        ops@example.com
+"#;
+
+    fn files(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_last_match_wins() {
+        let owners = CodeOwners::parse(SAMPLE);
+
+        assert_eq!(owners.rule_for("README.md").unwrap().owners, vec!["@org/maintainers"]);
+        assert_eq!(owners.rule_for("docs/guide.md").unwrap().owners, vec!["@alice"]);
+        assert_eq!(owners.rule_for("crates/gc-cli/src/main.rs").unwrap().owners, vec!["@org/rust-team"]);
+        assert_eq!(
+            owners.rule_for("crates/gc-validator/src/lib.rs").unwrap().owners,
+            vec!["@bob", "@carol"]
+        );
+    }
+
+    #[test]
+    fn test_email_only_rule_requires_nothing() {
+        let owners = CodeOwners::parse(SAMPLE);
+        let gaps = owners.missing_approvals(&files(&["scripts/deploy.sh"]), &[]);
+        assert!(gaps.is_empty());
+    }
+
+    #[test]
+    fn test_missing_approvals_grouped_by_pattern() {
+        let owners = CodeOwners::parse(SAMPLE);
+        let changed = files(&[
+            "crates/gc-validator/src/lib.rs",
+            "crates/gc-validator/Cargo.toml",
+            "docs/guide.md",
+        ]);
+
+        let gaps = owners.missing_approvals(&changed, &["@carol".to_string()]);
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].pattern, "/docs/");
+        assert_eq!(gaps[0].owners, vec!["@alice"]);
+
+        let gaps = owners.missing_approvals(&changed, &[]);
+        assert_eq!(gaps.len(), 2);
+        assert_eq!(gaps[0].files.len(), 2);
+    }
+
+    #[test]
+    fn test_single_star_does_not_recurse() {
+        let owners = CodeOwners::parse("docs/*  @alice\n");
+        assert!(owners.rule_for("docs/index.md").is_some());
+        assert!(owners.rule_for("docs/how-to/setup.md").is_none());
+    }
+
+    #[test]
+    fn test_double_star_patterns() {
+        let owners = CodeOwners::parse("**/tests/**  @qa\n");
+        assert!(owners.rule_for("tests/it.rs").is_some());
+        assert!(owners.rule_for("crates/a/tests/it.rs").is_some());
+        assert!(owners.rule_for("src/lib.rs").is_none());
+    }
+
+    #[test]
+    fn test_teams_for() {
+        let owners = CodeOwners::parse(SAMPLE);
+        let teams = owners.teams_for(&files(&["README.md", "src/main.rs", "docs/a.md"]));
+        assert_eq!(teams, vec!["@org/maintainers", "@org/rust-team"]);
+    }
+}
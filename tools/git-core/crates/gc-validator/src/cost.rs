@@ -0,0 +1,502 @@
+//! # CI Minutes
+//!
+//! Billable Actions minutes per workflow, job and runner OS, projected over a
+//! month and compared to a budget (`cost`).
+//!
+//! ## Billing
+//!
+//! ```text
+//! billable = ceil(job duration in minutes) × OS multiplier
+//! ```
+//!
+//! Every job is rounded up to a whole minute. Linux counts 1×, Windows 2× and
+//! macOS 10×; self-hosted runners are free. Larger runners are billed per
+//! minute at their own rates and counted as their OS here.
+//!
+//! Jobs of every attempt are counted, since re-runs are billed too. Standard
+//! runners are free for public repositories, so their minutes are reported
+//! but none are billable.
+//!
+//! ## Projection
+//!
+//! Minutes of the analyzed window are scaled to 30 days. When the projection
+//! exceeds the budget, scheduled workflows are the first to throttle: they are
+//! suggested, most expensive first, until the overage is covered.
+
+use crate::github::{GitHubClient, Job, RunFilter, WorkflowRun};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use futures::future::join_all;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// Included minutes of private repositories on the Free plan (`moderate` in `gc ci-detect`)
+pub const DEFAULT_BUDGET_MINUTES: u64 = 2000;
+
+/// Exit code of a projection over budget (1 is left to runtime errors, as for `gc guardian`)
+pub const EXIT_OVER_BUDGET: i32 = 2;
+
+/// Share of the budget above which a warning is raised
+const BUDGET_WARNING_RATIO: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RunnerOs {
+    Linux,
+    Windows,
+    Macos,
+    SelfHosted,
+}
+
+impl RunnerOs {
+    /// Runner OS from a job's `runs-on` labels
+    pub fn from_labels(labels: &[String]) -> Self {
+        let has = |needle: &str| labels.iter().any(|l| l.to_lowercase().contains(needle));
+        if has("self-hosted") {
+            RunnerOs::SelfHosted
+        } else if has("windows") {
+            RunnerOs::Windows
+        } else if has("macos") {
+            RunnerOs::Macos
+        } else {
+            RunnerOs::Linux
+        }
+    }
+
+    pub fn multiplier(self) -> u64 {
+        match self {
+            RunnerOs::Linux => 1,
+            RunnerOs::Windows => 2,
+            RunnerOs::Macos => 10,
+            RunnerOs::SelfHosted => 0,
+        }
+    }
+}
+
+/// Minutes a job ran, rounded up; 0 for jobs that did not run
+pub fn job_minutes(job: &Job) -> u64 {
+    let parse = |t: &Option<String>| t.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+    match (parse(&job.started_at), parse(&job.completed_at)) {
+        (Some(start), Some(end)) => {
+            let seconds = (end - start).num_seconds().max(0) as u64;
+            seconds.div_ceil(60)
+        }
+        _ => 0,
+    }
+}
+
+/// Minutes of one workflow, job or OS
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Usage {
+    pub runs: usize,
+    pub minutes: u64,
+    pub billable_minutes: u64,
+}
+
+impl Usage {
+    fn add(&mut self, minutes: u64, billable: u64) {
+        self.minutes += minutes;
+        self.billable_minutes += billable;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowUsage {
+    pub workflow: String,
+    /// Runs in the window triggered by `schedule`
+    pub scheduled_runs: usize,
+    /// Billable minutes of the scheduled runs
+    pub scheduled_minutes: u64,
+    #[serde(flatten)]
+    pub usage: Usage,
+}
+
+/// Suggested frequency reduction for a scheduled workflow
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Throttle {
+    pub workflow: String,
+    /// Projected monthly billable minutes of its scheduled runs
+    pub monthly_minutes: u64,
+    /// Keep one scheduled run out of this many; `None` to drop the schedule
+    pub keep_one_in: Option<u64>,
+    pub saves_minutes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CostReport {
+    pub hours: u64,
+    /// Public repository: standard runners are not billed
+    pub public: bool,
+    pub workflows: Vec<WorkflowUsage>,
+    /// `workflow / job` → usage
+    pub jobs: BTreeMap<String, Usage>,
+    pub runner_os: BTreeMap<RunnerOs, Usage>,
+    pub billable_minutes: u64,
+    pub projected_monthly_minutes: u64,
+    pub budget_minutes: u64,
+    pub throttle: Vec<Throttle>,
+    pub recommendations: Vec<String>,
+}
+
+impl CostReport {
+    /// Account the jobs of `runs` over a window of `hours`
+    pub fn build<'a, I>(runs: I, hours: u64, budget_minutes: u64, public: bool) -> Self
+    where
+        I: IntoIterator<Item = (&'a WorkflowRun, &'a [Job])>,
+    {
+        let mut workflows: BTreeMap<String, WorkflowUsage> = BTreeMap::new();
+        let mut jobs: BTreeMap<String, Usage> = BTreeMap::new();
+        let mut runner_os: BTreeMap<RunnerOs, Usage> = BTreeMap::new();
+
+        for (run, run_jobs) in runs {
+            let workflow = workflows.entry(run.name.clone()).or_insert_with(|| WorkflowUsage {
+                workflow: run.name.clone(),
+                scheduled_runs: 0,
+                scheduled_minutes: 0,
+                usage: Usage::default(),
+            });
+            workflow.usage.runs += 1;
+            let scheduled = run.event == "schedule";
+            if scheduled {
+                workflow.scheduled_runs += 1;
+            }
+
+            for job in run_jobs {
+                let os = RunnerOs::from_labels(&job.labels);
+                let minutes = job_minutes(job);
+                let billable = if public { 0 } else { minutes * os.multiplier() };
+
+                workflow.usage.add(minutes, billable);
+                if scheduled {
+                    workflow.scheduled_minutes += billable;
+                }
+                let job_usage = jobs.entry(format!("{} / {}", run.name, job.name)).or_default();
+                job_usage.runs += 1;
+                job_usage.add(minutes, billable);
+                let os_usage = runner_os.entry(os).or_default();
+                os_usage.runs += 1;
+                os_usage.add(minutes, billable);
+            }
+        }
+
+        let mut workflows: Vec<WorkflowUsage> = workflows.into_values().collect();
+        workflows.sort_by_key(|w| std::cmp::Reverse(w.usage.billable_minutes));
+
+        let billable_minutes = workflows.iter().map(|w| w.usage.billable_minutes).sum();
+        let to_monthly = |minutes: u64| (minutes as f64 * 30.0 * 24.0 / hours.max(1) as f64).round() as u64;
+        let projected_monthly_minutes = to_monthly(billable_minutes);
+
+        let scheduled: Vec<(String, u64)> = workflows.iter()
+            .filter(|w| w.scheduled_minutes > 0)
+            .map(|w| (w.workflow.clone(), to_monthly(w.scheduled_minutes)))
+            .collect();
+        let overage = match budget_minutes {
+            0 => 0,
+            budget => projected_monthly_minutes.saturating_sub(budget),
+        };
+        let throttle = plan_throttle(&scheduled, overage);
+
+        let mut report = Self {
+            hours,
+            public,
+            workflows,
+            jobs,
+            runner_os,
+            billable_minutes,
+            projected_monthly_minutes,
+            budget_minutes,
+            throttle,
+            recommendations: Vec::new(),
+        };
+        report.recommendations = report.recommend();
+        report
+    }
+
+    pub fn over_budget(&self) -> bool {
+        self.budget_minutes > 0 && self.projected_monthly_minutes > self.budget_minutes
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::from("# 💰 CI Minutes\n\n");
+        md.push_str(&format!(
+            "**{} billable min** in the last {}h → **{} min/month** projected (budget {} min)\n\n",
+            self.billable_minutes,
+            self.hours,
+            self.projected_monthly_minutes,
+            if self.budget_minutes == 0 { "unlimited".to_string() } else { self.budget_minutes.to_string() }
+        ));
+        if self.public {
+            md.push_str("Public repository: standard runners are free.\n\n");
+        }
+
+        md.push_str("| Workflow | Runs | Minutes | Billable | Scheduled runs |\n");
+        md.push_str("|----------|------|---------|----------|----------------|\n");
+        for w in &self.workflows {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                w.workflow, w.usage.runs, w.usage.minutes, w.usage.billable_minutes, w.scheduled_runs
+            ));
+        }
+
+        md.push_str("\n| Runner OS | Jobs | Minutes | Billable |\n|-----------|------|---------|----------|\n");
+        for (os, usage) in &self.runner_os {
+            md.push_str(&format!("| {:?} | {} | {} | {} |\n", os, usage.runs, usage.minutes, usage.billable_minutes));
+        }
+
+        if !self.recommendations.is_empty() {
+            md.push_str("\n## 💡 Recommendations\n\n");
+            for rec in &self.recommendations {
+                md.push_str(&format!("- {}\n", rec));
+            }
+        }
+        md
+    }
+
+    fn recommend(&self) -> Vec<String> {
+        let mut recs = Vec::new();
+        if self.budget_minutes == 0 {
+            return recs;
+        }
+
+        let usage = self.projected_monthly_minutes as f64 / self.budget_minutes as f64;
+        if self.over_budget() {
+            recs.push(format!(
+                "Projected {} min/month exceeds the {} min budget by {:.0}%",
+                self.projected_monthly_minutes, self.budget_minutes, (usage - 1.0) * 100.0
+            ));
+        } else if usage >= BUDGET_WARNING_RATIO {
+            recs.push(format!(
+                "Projected {} min/month uses {:.0}% of the {} min budget",
+                self.projected_monthly_minutes, usage * 100.0, self.budget_minutes
+            ));
+        }
+
+        for t in &self.throttle {
+            recs.push(match t.keep_one_in {
+                Some(n) => format!(
+                    "Throttle scheduled `{}`: keep 1 run in {} (saves ~{} min/month)",
+                    t.workflow, n, t.saves_minutes
+                ),
+                None => format!(
+                    "Drop the schedule of `{}` and trigger it manually (saves ~{} min/month)",
+                    t.workflow, t.saves_minutes
+                ),
+            });
+        }
+        if self.over_budget() && self.throttle.iter().map(|t| t.saves_minutes).sum::<u64>()
+            < self.projected_monthly_minutes - self.budget_minutes
+        {
+            recs.push("Throttling schedules is not enough; reduce push/PR triggers (paths filters, concurrency groups)".to_string());
+        }
+
+        if let Some(macos) = self.runner_os.get(&RunnerOs::Macos).filter(|u| u.billable_minutes > 0) {
+            let share = macos.billable_minutes as f64 / self.billable_minutes.max(1) as f64;
+            if share >= 0.5 {
+                recs.push(format!(
+                    "macOS jobs account for {:.0}% of billable minutes (10× multiplier); run only what needs macOS there",
+                    share * 100.0
+                ));
+            }
+        }
+        recs
+    }
+}
+
+/// Throttle scheduled workflows, most expensive first, until `overage` monthly minutes are saved
+///
+/// `scheduled` holds the projected monthly minutes of each workflow's scheduled runs.
+pub fn plan_throttle(scheduled: &[(String, u64)], overage: u64) -> Vec<Throttle> {
+    let mut candidates: Vec<&(String, u64)> = scheduled.iter().filter(|(_, m)| *m > 0).collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut remaining = overage;
+    let mut plan = Vec::new();
+    for (workflow, monthly) in candidates {
+        if remaining == 0 {
+            break;
+        }
+        let monthly = *monthly;
+        // Keeping 1 in n saves monthly × (1 - 1/n)
+        let (keep_one_in, saves) = if remaining >= monthly {
+            (None, monthly)
+        } else {
+            let n = monthly.div_ceil(monthly - remaining);
+            (Some(n), monthly - monthly / n)
+        };
+        plan.push(Throttle { workflow: workflow.clone(), monthly_minutes: monthly, keep_one_in, saves_minutes: saves });
+        remaining = remaining.saturating_sub(saves);
+    }
+    plan
+}
+
+/// Fetch the runs of the last `hours`, account their minutes and print the report
+pub async fn run_cost_report(
+    client: &GitHubClient,
+    filter: &RunFilter,
+    hours: u64,
+    budget_minutes: u64,
+    output_format: &str,
+) -> Result<CostReport> {
+    info!("💰 Accounting CI minutes over the last {} hours...", hours);
+
+    let public = match client.get_repo_visibility().await {
+        Ok(visibility) => visibility == "public",
+        Err(e) => {
+            warn!("⚠️  Could not detect repository visibility, assuming private: {}", e);
+            false
+        }
+    };
+
+    let runs = client.collect_runs(&filter.clone().created_since(Utc::now() - Duration::hours(hours as i64))).await?;
+    let jobs = join_all(runs.iter().map(|run| client.get_jobs_all_attempts(run.id))).await;
+    let runs_with_jobs: Vec<(WorkflowRun, Vec<Job>)> = runs.into_iter()
+        .zip(jobs)
+        .filter_map(|(run, jobs)| match jobs {
+            Ok(jobs) => Some((run, jobs)),
+            Err(e) => {
+                warn!("⚠️  Could not fetch jobs of run {}: {}", run.id, e);
+                None
+            }
+        })
+        .collect();
+
+    let report = CostReport::build(runs_with_jobs.iter().map(|(r, j)| (r, j.as_slice())), hours, budget_minutes, public);
+
+    match output_format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        "markdown" => println!("{}", report.to_markdown()),
+        _ => print_terminal_report(&report),
+    }
+
+    Ok(report)
+}
+
+fn print_terminal_report(report: &CostReport) {
+    println!("\n💰 CI Minutes (last {}h)\n", report.hours);
+    if report.public {
+        println!("Public repository: standard runners are free\n");
+    }
+    println!("{:<40} {:>6} {:>9} {:>9} {:>10}", "Workflow", "Runs", "Minutes", "Billable", "Scheduled");
+    println!("{}", "-".repeat(78));
+    for w in &report.workflows {
+        let name: String = w.workflow.chars().take(40).collect();
+        println!(
+            "{:<40} {:>6} {:>9} {:>9} {:>10}",
+            name, w.usage.runs, w.usage.minutes, w.usage.billable_minutes, w.scheduled_runs
+        );
+    }
+
+    println!();
+    for (os, usage) in &report.runner_os {
+        println!("{:<12} {:>5} jobs {:>7} min × {:<2} = {:>7} billable", format!("{:?}", os), usage.runs, usage.minutes, os.multiplier(), usage.billable_minutes);
+    }
+
+    let icon = if report.over_budget() { "🔴" } else { "🟢" };
+    println!("\n{} Projected: {} min/month (budget {} min)", icon, report.projected_monthly_minutes, report.budget_minutes);
+    for rec in &report.recommendations {
+        println!("   💡 {}", rec);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: u64, name: &str, event: &str) -> WorkflowRun {
+        WorkflowRun {
+            id,
+            name: name.to_string(),
+            status: "completed".to_string(),
+            conclusion: Some("success".to_string()),
+            html_url: String::new(),
+            created_at: "2025-03-03T10:00:00Z".to_string(),
+            updated_at: "2025-03-03T10:00:00Z".to_string(),
+            run_attempt: 1,
+            workflow_id: 1,
+            head_branch: "main".to_string(),
+            head_sha: "abc".to_string(),
+            path: String::new(),
+            event: event.to_string(),
+        }
+    }
+
+    fn job(name: &str, label: &str, seconds: i64) -> Job {
+        let start = DateTime::parse_from_rfc3339("2025-03-03T10:00:00Z").unwrap();
+        Job {
+            id: 1,
+            name: name.to_string(),
+            status: "completed".to_string(),
+            conclusion: Some("success".to_string()),
+            started_at: Some(start.to_rfc3339()),
+            completed_at: Some((start + Duration::seconds(seconds)).to_rfc3339()),
+            steps: None,
+            labels: vec![label.to_string()],
+        }
+    }
+
+    #[test]
+    fn test_minutes_round_up_per_job_with_os_multipliers() {
+        let ci = run(1, "CI", "push");
+        let jobs = vec![
+            job("linux", "ubuntu-latest", 61),
+            job("windows", "windows-2022", 30),
+            job("mac", "macos-14", 120),
+            job("local", "self-hosted", 600),
+        ];
+
+        let nightly = run(2, "Nightly", "schedule");
+        let nightly_jobs = vec![job("bench", "ubuntu-latest", 600)];
+        let report = CostReport::build([(&ci, jobs.as_slice()), (&nightly, nightly_jobs.as_slice())], 24, 0, false);
+
+        assert_eq!(report.workflows[0].workflow, "CI");
+        assert_eq!(report.workflows[0].usage.minutes, 2 + 1 + 2 + 10);
+        assert_eq!(report.workflows[0].usage.billable_minutes, 2 + 2 + 20);
+        assert_eq!(report.runner_os[&RunnerOs::Macos].billable_minutes, 20);
+        assert_eq!(report.projected_monthly_minutes, (24 + 10) * 30);
+        // Unlimited budget: nothing to throttle
+        assert!(report.throttle.is_empty());
+    }
+
+    #[test]
+    fn test_public_repository_bills_nothing() {
+        let ci = run(1, "CI", "push");
+        let jobs = vec![job("linux", "ubuntu-latest", 61), job("mac", "macos-14", 120)];
+        let report = CostReport::build([(&ci, jobs.as_slice())], 24, 2000, true);
+
+        assert_eq!(report.workflows[0].usage.minutes, 2 + 2);
+        assert_eq!(report.billable_minutes, 0);
+        assert!(!report.over_budget());
+        assert!(report.recommendations.is_empty());
+    }
+
+    #[test]
+    fn test_over_budget_throttles_scheduled_workflows_first() {
+        let nightly = run(1, "Nightly", "schedule");
+        let ci = run(2, "CI", "push");
+        let nightly_jobs = vec![job("bench", "ubuntu-latest", 50 * 60)];
+        let ci_jobs = vec![job("test", "ubuntu-latest", 20 * 60)];
+
+        // One week: 70 billable minutes → 300/month, 214 of them scheduled
+        let report = CostReport::build(
+            [(&nightly, nightly_jobs.as_slice()), (&ci, ci_jobs.as_slice())],
+            24 * 7,
+            200,
+            false,
+        );
+
+        assert!(report.over_budget());
+        assert_eq!(report.projected_monthly_minutes, 300);
+        assert_eq!(report.throttle, vec![Throttle {
+            workflow: "Nightly".to_string(),
+            monthly_minutes: 214,
+            keep_one_in: Some(2),
+            saves_minutes: 107,
+        }]);
+        assert!(report.recommendations.iter().any(|r| r.contains("keep 1 run in 2")));
+
+        // An overage larger than the schedule drops it entirely
+        let plan = plan_throttle(&[("Nightly".to_string(), 214)], 250);
+        assert_eq!(plan[0].keep_one_in, None);
+    }
+}
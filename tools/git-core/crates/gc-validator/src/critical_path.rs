@@ -0,0 +1,532 @@
+//! # Critical Path
+//!
+//! Job-level scheduling analysis of a workflow run. Combines the start/end
+//! timestamps of the run's jobs with the `needs:` edges declared in the
+//! workflow file to find:
+//!
+//! - the critical path (longest dependency chain of job durations)
+//! - idle queue time (time a job waited for a runner after its dependencies finished)
+//! - the achievable speedup with unlimited runners and unnecessary `needs:` dropped
+//! - concrete suggestions (jobs that could run in parallel, steps worth caching)
+//!
+//! Without the workflow file, jobs are treated as independent.
+
+use crate::github::{Job, WorkflowAnalysis, WorkflowRun};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_yaml::Value;
+use std::path::Path;
+
+/// A job queued for longer than this after its dependencies finished is reported
+const QUEUE_THRESHOLD_SECONDS: i64 = 120;
+
+/// Steps on the critical path slower than this are candidates for caching
+const SLOW_STEP_SECONDS: i64 = 60;
+
+/// Steps whose names suggest dependency installation or compilation
+const CACHEABLE_STEP_KEYWORDS: [&str; 6] = ["install", "setup", "dependencies", "deps", "build", "restore"];
+
+/// Jobs whose ordering is semantic (gated on earlier jobs passing), never suggested for parallelization
+const GATED_JOB_KEYWORDS: [&str; 3] = ["deploy", "release", "publish"];
+
+/// A job as declared in the workflow file
+#[derive(Debug, Clone)]
+struct JobSpec {
+    id: String,
+    name: Option<String>,
+    needs: Vec<String>,
+    /// Full job definition, used to look for `needs.<id>.` references
+    source: String,
+    downloads_artifacts: bool,
+    caches: bool,
+    gated: bool,
+}
+
+impl JobSpec {
+    /// Whether this job reads outputs, results or artifacts of `dep`
+    fn uses(&self, dep: &str) -> bool {
+        self.downloads_artifacts || self.source.contains(&format!("needs.{}.", dep))
+    }
+
+    /// Whether an API job name belongs to this job (matrix and reusable workflow names included)
+    fn matches(&self, job_name: &str) -> bool {
+        let display = self.name.as_deref().unwrap_or(&self.id);
+        let prefix = display.split("${{").next().unwrap_or(display).trim_end();
+        if prefix.len() < display.len() {
+            return !prefix.is_empty() && job_name.starts_with(prefix);
+        }
+        job_name == display
+            || job_name.starts_with(&format!("{} (", display))
+            || job_name.starts_with(&format!("{} / ", display))
+    }
+}
+
+/// The `needs:` graph of a workflow file
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowGraph {
+    jobs: Vec<JobSpec>,
+}
+
+impl WorkflowGraph {
+    /// Parse the jobs of a workflow file
+    pub fn parse(content: &str) -> Result<Self> {
+        let doc: Value = serde_yaml::from_str(content).context("Invalid workflow YAML")?;
+        let Some(jobs) = doc.get("jobs").and_then(Value::as_mapping) else {
+            return Ok(Self::default());
+        };
+
+        let jobs = jobs.iter()
+            .filter_map(|(id, job)| {
+                let id = id.as_str()?.to_string();
+                let name = job.get("name").and_then(Value::as_str).map(String::from);
+                let needs = match job.get("needs") {
+                    Some(Value::String(s)) => vec![s.clone()],
+                    Some(Value::Sequence(seq)) => seq.iter().filter_map(Value::as_str).map(String::from).collect(),
+                    _ => Vec::new(),
+                };
+                let steps = job.get("steps").and_then(Value::as_sequence).cloned().unwrap_or_default();
+                let step_uses = |needle: &str| steps.iter()
+                    .filter_map(|s| s.get("uses").and_then(Value::as_str))
+                    .any(|u| u.starts_with(needle));
+                let downloads_artifacts = step_uses("actions/download-artifact");
+                let caches = step_uses("actions/cache")
+                    || steps.iter().any(|s| s.get("with").and_then(|w| w.get("cache")).is_some());
+                let label = format!("{} {}", id, name.as_deref().unwrap_or("")).to_lowercase();
+                let gated = job.get("environment").is_some()
+                    || GATED_JOB_KEYWORDS.iter().any(|k| label.contains(k));
+
+                Some(JobSpec {
+                    source: serde_yaml::to_string(job).unwrap_or_default(),
+                    id,
+                    name,
+                    needs,
+                    downloads_artifacts,
+                    caches,
+                    gated,
+                })
+            })
+            .collect();
+
+        Ok(Self { jobs })
+    }
+
+    /// Load a workflow file, `None` if it does not exist locally
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&content).map(Some)
+    }
+
+    fn spec_for(&self, job_name: &str) -> Option<&JobSpec> {
+        self.jobs.iter().find(|s| s.matches(job_name))
+    }
+}
+
+/// Timing of a single job within the run
+#[derive(Debug, Clone, Serialize)]
+pub struct JobTiming {
+    pub name: String,
+    /// Seconds from run start until the job started
+    pub start_offset_seconds: i64,
+    pub duration_seconds: i64,
+    /// Seconds spent waiting for a runner after dependencies finished
+    pub queue_seconds: i64,
+    pub needs: Vec<String>,
+    pub on_critical_path: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionKind {
+    Parallelize,
+    Cache,
+    Queue,
+}
+
+impl SuggestionKind {
+    pub fn title(self) -> &'static str {
+        match self {
+            SuggestionKind::Parallelize => "Run independent jobs in parallel",
+            SuggestionKind::Cache => "Cache slow steps on the critical path",
+            SuggestionKind::Queue => "Reduce runner queue time",
+        }
+    }
+
+    pub fn action(self) -> &'static str {
+        match self {
+            SuggestionKind::Parallelize => "Drop the unused `needs:` dependency",
+            SuggestionKind::Cache => "Add actions/cache or the `cache:` input of the setup action",
+            SuggestionKind::Queue => "Add runner capacity or reduce concurrent jobs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    pub job: String,
+    pub message: String,
+}
+
+/// Critical-path analysis of one run
+#[derive(Debug, Clone, Serialize)]
+pub struct CriticalPathReport {
+    /// Whether `needs:` edges from the workflow file were available
+    pub has_dependency_graph: bool,
+    /// Run start to last job completion
+    pub wall_seconds: i64,
+    /// Jobs on the longest dependency chain, in execution order
+    pub critical_path: Vec<String>,
+    pub critical_path_seconds: i64,
+    /// Sum of all job durations
+    pub total_job_seconds: i64,
+    pub idle_queue_seconds: i64,
+    /// Jobs that overlapped in time with at least one other job
+    pub parallel_jobs: usize,
+    /// Critical path length once unused `needs:` edges are dropped
+    pub achievable_seconds: i64,
+    /// `wall_seconds / achievable_seconds`
+    pub achievable_speedup: f64,
+    pub jobs: Vec<JobTiming>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl CriticalPathReport {
+    /// Share of the wall time that is inherent to the dependency chain (1.0 = no waste)
+    pub fn efficiency(&self) -> f64 {
+        if self.wall_seconds > 0 {
+            (self.critical_path_seconds as f64 / self.wall_seconds as f64).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Analyze a run, reading its workflow file from the local checkout when present
+pub fn analyze_run(analysis: &WorkflowAnalysis) -> Option<CriticalPathReport> {
+    let graph = if analysis.run.path.is_empty() {
+        None
+    } else {
+        WorkflowGraph::load(&analysis.run.path).ok().flatten()
+    };
+    analyze(&analysis.run, &analysis.jobs, graph.as_ref())
+}
+
+/// Analyze the jobs of a run against an optional dependency graph
+///
+/// Returns `None` when no job has both start and completion timestamps.
+pub fn analyze(run: &WorkflowRun, jobs: &[Job], graph: Option<&WorkflowGraph>) -> Option<CriticalPathReport> {
+    let timed: Vec<(&Job, i64, i64)> = jobs.iter()
+        .filter_map(|j| {
+            let start = timestamp(j.started_at.as_deref()?)?;
+            let end = timestamp(j.completed_at.as_deref()?)?;
+            Some((j, start, end.max(start)))
+        })
+        .collect();
+    if timed.is_empty() {
+        return None;
+    }
+
+    // Re-run attempts keep the original created_at, so start from the first job instead
+    let first_start = timed.iter().map(|(_, s, _)| *s).min().unwrap_or_default();
+    let base = match timestamp(&run.created_at) {
+        Some(created) if run.run_attempt <= 1 => created.min(first_start),
+        _ => first_start,
+    };
+    let last_end = timed.iter().map(|(_, _, e)| *e).max().unwrap_or_default();
+
+    let specs: Vec<Option<&JobSpec>> = timed.iter()
+        .map(|(j, _, _)| graph.and_then(|g| g.spec_for(&j.name)))
+        .collect();
+
+    // Dependency edges between API jobs; the flag marks edges whose outputs are used
+    let deps: Vec<Vec<(usize, bool)>> = specs.iter()
+        .map(|spec| {
+            let Some(spec) = spec else { return Vec::new() };
+            spec.needs.iter()
+                .flat_map(|need| {
+                    let used = spec.gated || spec.uses(need);
+                    specs.iter().enumerate()
+                        .filter(move |(_, s)| s.map(|s| &s.id) == Some(need))
+                        .map(move |(i, _)| (i, used))
+                })
+                .collect()
+        })
+        .collect();
+
+    let durations: Vec<i64> = timed.iter().map(|(_, s, e)| e - s).collect();
+    let (finish, previous) = longest_paths(&durations, &deps, false);
+    let (achievable, _) = longest_paths(&durations, &deps, true);
+
+    let end = (0..timed.len()).max_by_key(|&i| finish[i]).unwrap_or_default();
+    let mut path = vec![end];
+    while let Some(p) = previous[*path.last().unwrap_or(&end)] {
+        if path.contains(&p) {
+            break;
+        }
+        path.push(p);
+    }
+    path.reverse();
+
+    let queue: Vec<i64> = (0..timed.len())
+        .map(|i| {
+            let ready = deps[i].iter().map(|&(d, _)| timed[d].2).max().unwrap_or(base);
+            (timed[i].1 - ready).max(0)
+        })
+        .collect();
+
+    let parallel_jobs = (0..timed.len())
+        .filter(|&i| (0..timed.len()).any(|k| k != i && timed[i].1 < timed[k].2 && timed[k].1 < timed[i].2))
+        .count();
+
+    let job_timings: Vec<JobTiming> = timed.iter().enumerate()
+        .map(|(i, (job, start, _))| JobTiming {
+            name: job.name.clone(),
+            start_offset_seconds: start - base,
+            duration_seconds: durations[i],
+            queue_seconds: queue[i],
+            needs: specs[i].map(|s| s.needs.clone()).unwrap_or_default(),
+            on_critical_path: path.contains(&i),
+        })
+        .collect();
+
+    let mut suggestions = Vec::new();
+
+    // needs: edges whose outputs and artifacts are never used
+    let mut reported = Vec::new();
+    for spec in specs.iter().flatten() {
+        for need in &spec.needs {
+            if spec.gated || spec.uses(need) || reported.contains(&(spec.id.as_str(), need.as_str())) {
+                continue;
+            }
+            reported.push((spec.id.as_str(), need.as_str()));
+            suggestions.push(Suggestion {
+                kind: SuggestionKind::Parallelize,
+                job: spec.id.clone(),
+                message: format!(
+                    "`{}` waits for `{}` but uses none of its outputs or artifacts; if the ordering is not required, they can run in parallel",
+                    spec.id, need),
+            });
+        }
+    }
+
+    // Slow install/build steps on the critical path of jobs without caching
+    for &i in &path {
+        if specs[i].is_some_and(|s| s.caches) {
+            continue;
+        }
+        let slow = timed[i].0.steps.iter().flatten()
+            .filter(|s| {
+                let name = s.name.to_lowercase();
+                CACHEABLE_STEP_KEYWORDS.iter().any(|k| name.contains(k))
+            })
+            .filter_map(|s| {
+                let seconds = timestamp(s.completed_at.as_deref()?)? - timestamp(s.started_at.as_deref()?)?;
+                (seconds >= SLOW_STEP_SECONDS).then_some((s.name.as_str(), seconds))
+            });
+        for (step, seconds) in slow {
+            suggestions.push(Suggestion {
+                kind: SuggestionKind::Cache,
+                job: timed[i].0.name.clone(),
+                message: format!("Step `{}` in `{}` takes {}s on the critical path and the job has no cache", step, timed[i].0.name, seconds),
+            });
+        }
+    }
+
+    for (i, (job, _, _)) in timed.iter().enumerate() {
+        if queue[i] > QUEUE_THRESHOLD_SECONDS {
+            suggestions.push(Suggestion {
+                kind: SuggestionKind::Queue,
+                job: job.name.clone(),
+                message: format!("`{}` waited {}s for a runner after its dependencies finished", job.name, queue[i]),
+            });
+        }
+    }
+
+    let wall_seconds = last_end - base;
+    let achievable_seconds = achievable.iter().copied().max().unwrap_or_default();
+
+    Some(CriticalPathReport {
+        has_dependency_graph: graph.is_some(),
+        wall_seconds,
+        critical_path: path.iter().map(|&i| timed[i].0.name.clone()).collect(),
+        critical_path_seconds: finish[end],
+        total_job_seconds: durations.iter().sum(),
+        idle_queue_seconds: queue.iter().sum(),
+        parallel_jobs,
+        achievable_seconds,
+        achievable_speedup: if achievable_seconds > 0 {
+            wall_seconds as f64 / achievable_seconds as f64
+        } else {
+            1.0
+        },
+        jobs: job_timings,
+        suggestions,
+    })
+}
+
+/// Longest finish time of every job along its dependency chain, with the predecessor on that chain
+///
+/// With `used_only`, edges whose outputs are never used are ignored. Cycles are broken.
+fn longest_paths(durations: &[i64], deps: &[Vec<(usize, bool)>], used_only: bool) -> (Vec<i64>, Vec<Option<usize>>) {
+    fn visit(
+        i: usize,
+        durations: &[i64],
+        deps: &[Vec<(usize, bool)>],
+        used_only: bool,
+        state: &mut [u8],
+        finish: &mut [i64],
+        previous: &mut [Option<usize>],
+    ) {
+        if state[i] != 0 {
+            return;
+        }
+        state[i] = 1;
+        let mut best: Option<(usize, i64)> = None;
+        for &(d, used) in &deps[i] {
+            if used_only && !used {
+                continue;
+            }
+            visit(d, durations, deps, used_only, state, finish, previous);
+            if state[d] == 2 && best.is_none_or(|(_, f)| finish[d] > f) {
+                best = Some((d, finish[d]));
+            }
+        }
+        finish[i] = durations[i] + best.map(|(_, f)| f).unwrap_or(0);
+        previous[i] = best.map(|(d, _)| d);
+        state[i] = 2;
+    }
+
+    let mut state = vec![0u8; durations.len()];
+    let mut finish = vec![0; durations.len()];
+    let mut previous = vec![None; durations.len()];
+    for i in 0..durations.len() {
+        visit(i, durations, deps, used_only, &mut state, &mut finish, &mut previous);
+    }
+    (finish, previous)
+}
+
+fn timestamp(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value).ok().map(|t| t.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::Step;
+
+    const WORKFLOW: &str = r#"
+on: push
+jobs:
+  lint:
+    runs-on: ubuntu-latest
+    steps:
+      - run: cargo clippy
+  build:
+    name: Build
+    runs-on: ubuntu-latest
+    outputs:
+      version: ${{ steps.v.outputs.version }}
+    steps:
+      - name: Install dependencies
+        run: cargo fetch
+  test:
+    needs: [lint, build]
+    runs-on: ubuntu-latest
+    steps:
+      - run: echo ${{ needs.build.outputs.version }}
+  deploy:
+    needs: test
+    environment: production
+    runs-on: ubuntu-latest
+    steps:
+      - run: ./deploy.sh
+"#;
+
+    fn run() -> WorkflowRun {
+        WorkflowRun {
+            id: 1,
+            name: "CI".to_string(),
+            status: "completed".to_string(),
+            conclusion: Some("success".to_string()),
+            html_url: String::new(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: String::new(),
+            run_attempt: 1,
+            workflow_id: 1,
+            head_branch: "main".to_string(),
+            head_sha: "abc".to_string(),
+            path: ".github/workflows/ci.yml".to_string(),
+            event: "push".to_string(),
+        }
+    }
+
+    fn job(name: &str, start: &str, end: &str, steps: Option<Vec<Step>>) -> Job {
+        Job {
+            id: 1,
+            name: name.to_string(),
+            status: "completed".to_string(),
+            conclusion: Some("success".to_string()),
+            started_at: Some(format!("2025-01-01T00:{}Z", start)),
+            completed_at: Some(format!("2025-01-01T00:{}Z", end)),
+            steps,
+            labels: Vec::new(),
+        }
+    }
+
+    fn jobs() -> Vec<Job> {
+        let install = Step {
+            name: "Install dependencies".to_string(),
+            status: "completed".to_string(),
+            conclusion: Some("success".to_string()),
+            number: 1,
+            started_at: Some("2025-01-01T00:00:10Z".to_string()),
+            completed_at: Some("2025-01-01T00:03:10Z".to_string()),
+        };
+        vec![
+            job("lint", "00:10", "01:10", None),
+            job("Build (ubuntu-latest)", "00:10", "05:10", Some(vec![install])),
+            job("test", "08:10", "10:10", None),
+            job("deploy", "10:20", "11:20", None),
+        ]
+    }
+
+    #[test]
+    fn test_critical_path_follows_needs() {
+        let graph = WorkflowGraph::parse(WORKFLOW).unwrap();
+        let report = analyze(&run(), &jobs(), Some(&graph)).unwrap();
+
+        assert_eq!(report.critical_path, vec!["Build (ubuntu-latest)", "test", "deploy"]);
+        assert_eq!(report.critical_path_seconds, 300 + 120 + 60);
+        assert_eq!(report.wall_seconds, 680);
+        // test waited 3 minutes after build finished, everything else ~10s
+        assert_eq!(report.idle_queue_seconds, 10 + 10 + 180 + 10);
+        assert_eq!(report.parallel_jobs, 2);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let graph = WorkflowGraph::parse(WORKFLOW).unwrap();
+        let report = analyze(&run(), &jobs(), Some(&graph)).unwrap();
+        let kinds: Vec<_> = report.suggestions.iter().map(|s| (s.kind, s.job.as_str())).collect();
+
+        // test uses build outputs but not lint; deploy is gated by its environment
+        assert!(kinds.contains(&(SuggestionKind::Parallelize, "test")));
+        assert_eq!(kinds.iter().filter(|(k, _)| *k == SuggestionKind::Parallelize).count(), 1);
+        assert!(kinds.contains(&(SuggestionKind::Cache, "Build (ubuntu-latest)")));
+        assert!(kinds.contains(&(SuggestionKind::Queue, "test")));
+    }
+
+    #[test]
+    fn test_without_graph_jobs_are_independent() {
+        let report = analyze(&run(), &jobs(), None).unwrap();
+
+        assert_eq!(report.critical_path, vec!["Build (ubuntu-latest)"]);
+        assert!(!report.has_dependency_graph);
+        assert!(report.achievable_speedup > 2.0);
+    }
+}
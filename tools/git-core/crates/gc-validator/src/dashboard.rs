@@ -0,0 +1,434 @@
+//! # Dashboard
+//!
+//! Self-contained HTML report of workflow health: a single file with inline
+//! CSS and SVG charts, no scripts or external assets, so it can be uploaded as
+//! a CI artifact or published to GitHub Pages as is.
+//!
+//! Sections:
+//! - per-workflow health (success rate, average duration, duration sparkline)
+//! - runs per day by outcome
+//! - top error fingerprints (`analyzer::group_errors`)
+//! - flaky jobs and tests (`flaky::detect_flaky`)
+
+use crate::analyzer::{self, ErrorReport};
+use crate::flaky::{self, FlakyEntry, FlakyKind};
+use crate::github::WorkflowAnalysis;
+use gc_report::escape;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Rows shown in the error and flaky tables
+const TOP_N: usize = 10;
+
+/// Success rate at or above which a workflow counts as healthy
+const HEALTHY_RATE: f64 = 0.9;
+
+/// Success rate below which a workflow counts as failing
+const FAILING_RATE: f64 = 0.7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    Healthy,
+    Degraded,
+    Failing,
+}
+
+impl Health {
+    fn from_rate(rate: f64) -> Self {
+        if rate >= HEALTHY_RATE {
+            Health::Healthy
+        } else if rate >= FAILING_RATE {
+            Health::Degraded
+        } else {
+            Health::Failing
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Health::Healthy => "healthy",
+            Health::Degraded => "degraded",
+            Health::Failing => "failing",
+        }
+    }
+}
+
+/// Aggregates for one workflow
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowHealth {
+    pub name: String,
+    pub runs: usize,
+    pub successful: usize,
+    pub failed: usize,
+    /// Share of completed runs that succeeded
+    pub success_rate: f64,
+    pub health: Health,
+    pub avg_duration_seconds: Option<i64>,
+    /// Conclusion and URL of the most recent run
+    pub last_conclusion: Option<String>,
+    pub last_url: String,
+    /// Durations of completed runs, oldest first
+    pub durations: Vec<i64>,
+}
+
+/// Runs started on one day (UTC)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DayStats {
+    pub day: String,
+    pub successful: usize,
+    pub failed: usize,
+    pub other: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Dashboard {
+    pub generated_at: DateTime<Utc>,
+    pub hours: u64,
+    pub workflows: Vec<WorkflowHealth>,
+    pub days: Vec<DayStats>,
+    pub top_errors: Vec<ErrorReport>,
+    pub flaky: Vec<FlakyEntry>,
+}
+
+impl Dashboard {
+    /// Aggregate analyzed runs from the last `hours`
+    pub fn build(analyses: &[WorkflowAnalysis], hours: u64, now: DateTime<Utc>) -> Self {
+        let mut sorted: Vec<&WorkflowAnalysis> = analyses.iter().collect();
+        sorted.sort_by(|a, b| a.run.created_at.cmp(&b.run.created_at));
+
+        let mut by_workflow: BTreeMap<&str, Vec<&WorkflowAnalysis>> = BTreeMap::new();
+        let mut days: BTreeMap<String, DayStats> = BTreeMap::new();
+        for analysis in &sorted {
+            by_workflow.entry(analysis.run.name.as_str()).or_default().push(analysis);
+
+            let day = analysis.run.created_at.get(..10).unwrap_or("unknown").to_string();
+            let stats = days.entry(day.clone()).or_insert_with(|| DayStats { day, ..Default::default() });
+            match analysis.run.conclusion.as_deref() {
+                Some("success") => stats.successful += 1,
+                Some("failure") => stats.failed += 1,
+                _ => stats.other += 1,
+            }
+        }
+
+        let mut workflows: Vec<WorkflowHealth> = by_workflow.into_iter()
+            .map(|(name, runs)| {
+                let successful = runs.iter().filter(|a| a.run.conclusion.as_deref() == Some("success")).count();
+                let failed = runs.iter().filter(|a| a.run.conclusion.as_deref() == Some("failure")).count();
+                let completed = successful + failed;
+                let success_rate = if completed > 0 { successful as f64 / completed as f64 } else { 1.0 };
+                let durations: Vec<i64> = runs.iter().filter_map(|a| a.duration_seconds).collect();
+                let last = runs.last().map(|a| &a.run);
+
+                WorkflowHealth {
+                    name: name.to_string(),
+                    runs: runs.len(),
+                    successful,
+                    failed,
+                    success_rate,
+                    health: Health::from_rate(success_rate),
+                    avg_duration_seconds: (!durations.is_empty())
+                        .then(|| durations.iter().sum::<i64>() / durations.len() as i64),
+                    last_conclusion: last.and_then(|r| r.conclusion.clone()),
+                    last_url: last.map(|r| r.html_url.clone()).unwrap_or_default(),
+                    durations,
+                }
+            })
+            .collect();
+        // Worst first, then by volume
+        workflows.sort_by(|a, b| a.success_rate.total_cmp(&b.success_rate).then(b.runs.cmp(&a.runs)));
+
+        let mut top_errors = analyzer::group_errors(analyses);
+        top_errors.truncate(TOP_N);
+        let mut flaky = flaky::detect_flaky(&flaky::observations_from_analyses(analyses));
+        flaky.truncate(TOP_N);
+
+        Self {
+            generated_at: now,
+            hours,
+            workflows,
+            days: days.into_values().collect(),
+            top_errors,
+            flaky,
+        }
+    }
+
+    /// Render the dashboard as a single HTML document
+    pub fn to_html(&self) -> String {
+        let total: usize = self.workflows.iter().map(|w| w.runs).sum();
+        let successful: usize = self.workflows.iter().map(|w| w.successful).sum();
+        let failed: usize = self.workflows.iter().map(|w| w.failed).sum();
+        let rate = if successful + failed > 0 { successful as f64 / (successful + failed) as f64 } else { 1.0 };
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+        html.push_str("<title>Workflow Health Dashboard</title>\n");
+        html.push_str(STYLE);
+        html.push_str("</head>\n<body>\n");
+        html.push_str(&format!(
+            "<h1>📊 Workflow Health</h1>\n<p class=\"muted\">Last {} hours · generated {}</p>\n",
+            self.hours,
+            self.generated_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+
+        html.push_str("<div class=\"cards\">\n");
+        for (label, value, class) in [
+            ("Runs", total.to_string(), ""),
+            ("Successful", successful.to_string(), "healthy"),
+            ("Failed", failed.to_string(), if failed > 0 { "failing" } else { "" }),
+            ("Success rate", format!("{:.1}%", rate * 100.0), Health::from_rate(rate).label()),
+        ] {
+            html.push_str(&format!(
+                "<div class=\"card\"><div class=\"muted\">{}</div><div class=\"value {}\">{}</div></div>\n",
+                label, class, value
+            ));
+        }
+        html.push_str("</div>\n");
+
+        html.push_str("<h2>Runs per day</h2>\n");
+        html.push_str(&day_chart(&self.days));
+
+        html.push_str("<h2>Workflows</h2>\n");
+        if self.workflows.is_empty() {
+            html.push_str("<p class=\"muted\">No runs in this window.</p>\n");
+        } else {
+            html.push_str("<table>\n<tr><th>Workflow</th><th>Health</th><th>Runs</th><th>Success rate</th><th>Avg duration</th><th>Duration trend</th><th>Last run</th></tr>\n");
+            for w in &self.workflows {
+                let last = match &w.last_conclusion {
+                    Some(c) if !w.last_url.is_empty() => format!("<a href=\"{}\">{}</a>", escape(&w.last_url), escape(c)),
+                    Some(c) => escape(c),
+                    None => "in progress".to_string(),
+                };
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td><span class=\"badge {}\">{}</span></td><td>{}</td><td>{:.1}%</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape(&w.name),
+                    w.health.label(), w.health.label(),
+                    w.runs,
+                    w.success_rate * 100.0,
+                    w.avg_duration_seconds.map(format_duration).unwrap_or_else(|| "-".to_string()),
+                    sparkline(&w.durations),
+                    last,
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("<h2>Top error fingerprints</h2>\n");
+        if self.top_errors.is_empty() {
+            html.push_str("<p class=\"muted\">No failures parsed from job logs.</p>\n");
+        } else {
+            html.push_str("<table>\n<tr><th>Runs</th><th>Workflow / job</th><th>Error</th><th>Location</th><th>Fingerprint</th></tr>\n");
+            for e in &self.top_errors {
+                let location = match (&e.file, e.line) {
+                    (Some(file), Some(line)) => format!("{}:{}", escape(file), line),
+                    (Some(file), None) => escape(file),
+                    _ => "-".to_string(),
+                };
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{} / {}</td><td><code>{}</code></td><td>{}</td><td><code>{}</code></td></tr>\n",
+                    e.frequency,
+                    escape(&e.workflow_name), escape(&e.job_name),
+                    escape(&e.error_message),
+                    location,
+                    escape(&e.fingerprint),
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("<h2>Flaky jobs and tests</h2>\n");
+        if self.flaky.is_empty() {
+            html.push_str("<p class=\"muted\">Nothing passed and failed on the same commit.</p>\n");
+        } else {
+            html.push_str("<table>\n<tr><th>Kind</th><th>Name</th><th>Workflow / job</th><th>Flake rate</th><th>Flaky commits</th></tr>\n");
+            for f in &self.flaky {
+                let kind = match f.kind {
+                    FlakyKind::Job => "job",
+                    FlakyKind::Test => "test",
+                };
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{} / {}</td><td>{}</td><td>{} of {}</td></tr>\n",
+                    kind,
+                    escape(&f.name),
+                    escape(&f.workflow), escape(&f.job),
+                    bar(f.flake_rate),
+                    f.flaky_shas.len(), f.total_shas,
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+const STYLE: &str = r#"<style>
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem auto; max-width: 1100px; padding: 0 1rem; color: #1f2328; }
+h1 { margin-bottom: 0; }
+h2 { margin-top: 2rem; border-bottom: 1px solid #d1d9e0; padding-bottom: .3rem; }
+table { border-collapse: collapse; width: 100%; font-size: .9rem; }
+th, td { text-align: left; padding: .4rem .6rem; border-bottom: 1px solid #d1d9e0; vertical-align: middle; }
+code { font-size: .8rem; word-break: break-all; }
+.muted { color: #59636e; }
+.cards { display: flex; gap: 1rem; margin-top: 1rem; }
+.card { flex: 1; border: 1px solid #d1d9e0; border-radius: 6px; padding: .8rem; }
+.value { font-size: 1.6rem; font-weight: 600; }
+.badge { border-radius: 1rem; padding: .1rem .6rem; font-size: .8rem; color: #fff; }
+.badge.healthy { background: #1a7f37; } .badge.degraded { background: #9a6700; } .badge.failing { background: #cf222e; }
+.value.healthy { color: #1a7f37; } .value.degraded { color: #9a6700; } .value.failing { color: #cf222e; }
+</style>
+"#;
+
+/// Stacked bars of successful / failed / other runs per day
+fn day_chart(days: &[DayStats]) -> String {
+    if days.is_empty() {
+        return "<p class=\"muted\">No runs in this window.</p>\n".to_string();
+    }
+    let (width, height, label) = (36.0, 120.0, 16.0);
+    let max = days.iter().map(|d| d.successful + d.failed + d.other).max().unwrap_or(1).max(1) as f64;
+
+    let mut svg = format!(
+        "<svg width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"Runs per day\">\n",
+        days.len() as f64 * width, height + label
+    );
+    for (i, day) in days.iter().enumerate() {
+        let x = i as f64 * width + 4.0;
+        let mut y = height;
+        for (count, color) in [(day.successful, "#1a7f37"), (day.failed, "#cf222e"), (day.other, "#8c959f")] {
+            if count == 0 {
+                continue;
+            }
+            let h = count as f64 / max * height;
+            y -= h;
+            svg.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{}: {}</title></rect>\n",
+                x, y, width - 8.0, h, color, escape(&day.day), count
+            ));
+        }
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"9\" fill=\"#59636e\">{}</text>\n",
+            x, height + 12.0, escape(day.day.get(5..).unwrap_or(&day.day))
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Inline line chart of run durations, oldest first
+fn sparkline(durations: &[i64]) -> String {
+    if durations.len() < 2 {
+        return "-".to_string();
+    }
+    let (width, height) = (160.0, 32.0);
+    let max = *durations.iter().max().unwrap_or(&1) as f64;
+    let min = *durations.iter().min().unwrap_or(&0) as f64;
+    let range = (max - min).max(1.0);
+    let step = width / (durations.len() - 1) as f64;
+
+    let points: Vec<String> = durations.iter().enumerate()
+        .map(|(i, d)| format!("{:.1},{:.1}", i as f64 * step, height - 2.0 - (*d as f64 - min) / range * (height - 4.0)))
+        .collect();
+    format!(
+        "<svg width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"Duration trend\"><title>{} → {}</title><polyline fill=\"none\" stroke=\"#0969da\" stroke-width=\"1.5\" points=\"{}\"/></svg>",
+        width, height,
+        format_duration(durations[0]), format_duration(durations[durations.len() - 1]),
+        points.join(" ")
+    )
+}
+
+/// Horizontal percentage bar
+fn bar(rate: f64) -> String {
+    format!(
+        "<svg width=\"100\" height=\"10\"><rect width=\"100\" height=\"10\" fill=\"#eaeef2\"/><rect width=\"{:.0}\" height=\"10\" fill=\"#9a6700\"/></svg> {:.0}%",
+        rate * 100.0, rate * 100.0
+    )
+}
+
+fn format_duration(seconds: i64) -> String {
+    if seconds >= 60 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::{Job, WorkflowRun};
+    use crate::log_parser::parse_log;
+
+    fn analysis(id: u64, name: &str, conclusion: &str, created_at: &str, duration: i64, log: &str) -> WorkflowAnalysis {
+        WorkflowAnalysis {
+            run: WorkflowRun {
+                id,
+                name: name.to_string(),
+                status: "completed".to_string(),
+                conclusion: Some(conclusion.to_string()),
+                html_url: format!("https://github.com/acme/widgets/actions/runs/{}", id),
+                created_at: created_at.to_string(),
+                updated_at: created_at.to_string(),
+                run_attempt: 1,
+                workflow_id: 7,
+                head_branch: "main".to_string(),
+                head_sha: "abc".to_string(),
+                path: String::new(),
+                event: "push".to_string(),
+            },
+            jobs: vec![Job {
+                id: id * 10,
+                name: "test".to_string(),
+                status: "completed".to_string(),
+                conclusion: Some(conclusion.to_string()),
+                started_at: None,
+                completed_at: None,
+                steps: None,
+                labels: Vec::new(),
+            }],
+            job_logs: Default::default(),
+            errors: Vec::new(),
+            failures: parse_log(log).into_iter().map(|f| f.in_job("test")).collect(),
+            warnings: Vec::new(),
+            duration_seconds: Some(duration),
+        }
+    }
+
+    fn sample() -> Vec<WorkflowAnalysis> {
+        vec![
+            analysis(1, "CI", "success", "2026-10-01T10:00:00Z", 300, ""),
+            analysis(2, "CI", "failure", "2026-10-02T10:00:00Z", 420, "test api::<login> ... FAILED\n"),
+            analysis(3, "Docs", "success", "2026-10-02T11:00:00Z", 60, ""),
+        ]
+    }
+
+    #[test]
+    fn test_build_aggregates_workflows_days_and_flaky() {
+        let dashboard = Dashboard::build(&sample(), 168, Utc::now());
+
+        assert_eq!(dashboard.workflows[0].name, "CI");
+        assert_eq!(dashboard.workflows[0].health, Health::Failing);
+        assert_eq!(dashboard.workflows[0].durations, vec![300, 420]);
+        assert_eq!(dashboard.workflows[0].avg_duration_seconds, Some(360));
+        assert_eq!(dashboard.workflows[1].health, Health::Healthy);
+        let days: Vec<_> = dashboard.days.iter().map(|d| (d.day.as_str(), d.successful, d.failed)).collect();
+        assert_eq!(days, vec![("2026-10-01", 1, 0), ("2026-10-02", 1, 1)]);
+        // Job `test` passed and failed on the same SHA
+        assert!(dashboard.flaky.iter().any(|f| f.kind == FlakyKind::Test && f.name == "api::<login>"));
+        assert_eq!(dashboard.top_errors.len(), 1);
+    }
+
+    #[test]
+    fn test_html_is_self_contained_and_escaped() {
+        let html = Dashboard::build(&sample(), 168, Utc::now()).to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<polyline"));
+        assert!(html.contains("api::&lt;login&gt;"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("src="));
+    }
+}
@@ -32,7 +32,7 @@
 //! ## Example
 //!
 //! ```rust,no_run
-//! use workflow_orchestrator::dispatcher_core::{DispatcherCore, Strategy};
+//! use gc_validator::dispatcher_core::{DispatcherCore, Strategy};
 //! use octocrab::Octocrab;
 //!
 //! #[tokio::main]
@@ -0,0 +1,329 @@
+//! # Fix Rules
+//!
+//! Knowledge base of fix suggestions for failed jobs. The shipped rules
+//! (`rules/ci-fixes.yml`) are extended by `.gitcore/ci-fixes.yml`; a repository
+//! rule with the same `id` replaces the shipped one.
+//!
+//! ```yaml
+//! rules:
+//!   - id: flaky-db-port
+//!     title: Test database not ready
+//!     match:
+//!       step: "(?i)integration"              # regex on the failed step name
+//!       kind: test                           # compile, test, npm, exit
+//!       fingerprint: 3f1c9a0b2d4e5f67        # exact failure fingerprint
+//!       log: "connection refused.*5432"      # regex on the failed job log
+//!     suggestion: Wait for the postgres service health check before running tests.
+//!     link: https://docs.github.com/en/actions/using-containerized-services
+//!     confidence: 0.8
+//! ```
+//!
+//! Every matcher present must match. Whether a suggestion actually fixed a
+//! failure is recorded in the history database (`fixes record`) and blended
+//! into the rule's confidence.
+
+use crate::history::{HistoryStore, DEFAULT_HISTORY_PATH};
+use crate::log_parser::LogFailure;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Repository rules file
+pub const DEFAULT_RULES_PATH: &str = ".gitcore/ci-fixes.yml";
+
+/// Rules shipped with the validator
+const SHIPPED_RULES: &str = include_str!("../rules/ci-fixes.yml");
+
+/// Weight of the configured confidence against recorded outcomes
+const PRIOR_WEIGHT: f64 = 2.0;
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RuleSpec {
+    id: String,
+    title: String,
+    #[serde(rename = "match", default)]
+    matcher: MatchSpec,
+    suggestion: String,
+    link: Option<String>,
+    #[serde(default = "default_confidence")]
+    confidence: f64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct MatchSpec {
+    step: Option<String>,
+    kind: Option<String>,
+    fingerprint: Option<String>,
+    log: Option<String>,
+}
+
+fn default_confidence() -> f64 {
+    0.5
+}
+
+/// A compiled fix rule
+#[derive(Debug, Clone)]
+pub struct FixRule {
+    pub id: String,
+    pub title: String,
+    pub suggestion: String,
+    pub link: Option<String>,
+    pub confidence: f64,
+    step: Option<Regex>,
+    kind: Option<String>,
+    fingerprint: Option<String>,
+    log: Option<Regex>,
+    /// Recorded outcomes: (fixed, total)
+    outcomes: (u32, u32),
+}
+
+impl FixRule {
+    fn compile(spec: RuleSpec) -> Result<Self> {
+        let m = &spec.matcher;
+        if m.step.is_none() && m.kind.is_none() && m.fingerprint.is_none() && m.log.is_none() {
+            anyhow::bail!("Fix rule '{}' has no matcher", spec.id);
+        }
+        let regex = |pattern: &Option<String>| {
+            pattern.as_deref()
+                .map(Regex::new)
+                .transpose()
+                .with_context(|| format!("Invalid regex in fix rule '{}'", spec.id))
+        };
+
+        Ok(Self {
+            step: regex(&m.step)?,
+            kind: m.kind.clone(),
+            fingerprint: m.fingerprint.clone(),
+            log: regex(&m.log)?,
+            id: spec.id,
+            title: spec.title,
+            suggestion: spec.suggestion,
+            link: spec.link,
+            confidence: spec.confidence.clamp(0.0, 1.0),
+            outcomes: (0, 0),
+        })
+    }
+
+    /// The failure this rule matches in `ctx`, `Some(None)` for a match not tied to a parsed failure
+    fn matches<'a>(&self, ctx: &FixContext<'a>) -> Option<Option<&'a LogFailure>> {
+        if let Some(step) = &self.step {
+            if !ctx.step.is_some_and(|s| step.is_match(s)) {
+                return None;
+            }
+        }
+        if let Some(log) = &self.log {
+            let in_log = ctx.log.is_some_and(|l| log.is_match(l));
+            if !in_log && !ctx.failures.iter().any(|f| log.is_match(&f.summary())) {
+                return None;
+            }
+        }
+        if self.kind.is_none() && self.fingerprint.is_none() {
+            return Some(None);
+        }
+        ctx.failures.iter()
+            .find(|f| {
+                self.kind.as_ref().is_none_or(|k| f.kind.to_string() == *k)
+                    && self.fingerprint.as_ref().is_none_or(|fp| f.fingerprint == *fp)
+            })
+            .map(Some)
+    }
+
+    /// Configured confidence adjusted by recorded outcomes
+    pub fn effective_confidence(&self) -> f64 {
+        let (fixed, total) = self.outcomes;
+        (self.confidence * PRIOR_WEIGHT + fixed as f64) / (PRIOR_WEIGHT + total as f64)
+    }
+}
+
+/// What is known about a failed step
+#[derive(Debug, Clone, Default)]
+pub struct FixContext<'a> {
+    pub job: &'a str,
+    pub step: Option<&'a str>,
+    pub failures: &'a [LogFailure],
+    pub log: Option<&'a str>,
+}
+
+/// A rule that matched a failure
+#[derive(Debug, Clone, Serialize)]
+pub struct FixSuggestion {
+    pub rule_id: String,
+    pub title: String,
+    pub suggestion: String,
+    pub link: Option<String>,
+    pub confidence: f64,
+    /// Fingerprint of the matched failure, for `fixes record`
+    pub fingerprint: Option<String>,
+    pub job: String,
+    pub step: Option<String>,
+    /// Times this rule was recorded as fixing a failure
+    pub times_fixed: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KnowledgeBase {
+    rules: Vec<FixRule>,
+}
+
+impl KnowledgeBase {
+    /// Shipped rules only
+    pub fn shipped() -> Result<Self> {
+        Self::default().with_rules_str(SHIPPED_RULES)
+    }
+
+    /// Shipped rules, `.gitcore/ci-fixes.yml` and outcomes from the default history database
+    pub fn for_repo() -> Result<Self> {
+        let kb = Self::shipped()?.with_rules_file(DEFAULT_RULES_PATH)?;
+        if !Path::new(DEFAULT_HISTORY_PATH).exists() {
+            return Ok(kb);
+        }
+        kb.with_outcomes(&HistoryStore::open(DEFAULT_HISTORY_PATH)?)
+    }
+
+    /// Add rules from a YAML file, skipping a missing file
+    pub fn with_rules_file<P: AsRef<Path>>(self, path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(self);
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.with_rules_str(&content)
+            .with_context(|| format!("Invalid fix rules in {}", path.display()))
+    }
+
+    /// Add rules from YAML; a rule replaces an existing one with the same id
+    pub fn with_rules_str(mut self, content: &str) -> Result<Self> {
+        let file: RulesFile = serde_yaml::from_str(content)?;
+        for spec in file.rules {
+            let rule = FixRule::compile(spec)?;
+            match self.rules.iter_mut().find(|r| r.id == rule.id) {
+                Some(existing) => *existing = rule,
+                None => self.rules.push(rule),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Attach recorded outcomes from the history database
+    pub fn with_outcomes(mut self, store: &HistoryStore) -> Result<Self> {
+        for (rule_id, fixed, total) in store.fix_outcomes()? {
+            if let Some(rule) = self.rules.iter_mut().find(|r| r.id == rule_id) {
+                rule.outcomes = (fixed, total);
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn rules(&self) -> &[FixRule] {
+        &self.rules
+    }
+
+    pub fn contains(&self, rule_id: &str) -> bool {
+        self.rules.iter().any(|r| r.id == rule_id)
+    }
+
+    /// Matching rules for a failed step, most confident first
+    pub fn suggest(&self, ctx: &FixContext) -> Vec<FixSuggestion> {
+        let mut suggestions: Vec<FixSuggestion> = self.rules.iter()
+            .filter_map(|rule| {
+                let failure = rule.matches(ctx)?;
+                Some(FixSuggestion {
+                    rule_id: rule.id.clone(),
+                    title: rule.title.clone(),
+                    suggestion: rule.suggestion.clone(),
+                    link: rule.link.clone(),
+                    confidence: rule.effective_confidence(),
+                    fingerprint: failure.map(|f| f.fingerprint.clone()),
+                    job: ctx.job.to_string(),
+                    step: ctx.step.map(String::from),
+                    times_fixed: rule.outcomes.0,
+                })
+            })
+            .collect();
+        suggestions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::log_parser::parse_log;
+
+    #[test]
+    fn test_shipped_rules_compile() {
+        let kb = KnowledgeBase::shipped().unwrap();
+        assert!(kb.contains("step-checkout"));
+        assert!(kb.contains("rust-unresolved-import"));
+    }
+
+    #[test]
+    fn test_specific_rule_ranks_above_step_fallback() {
+        let log = "error[E0432]: unresolved import `serde_json`\n --> src/main.rs:3:5\n";
+        let failures = parse_log(log);
+        let kb = KnowledgeBase::shipped().unwrap();
+        let suggestions = kb.suggest(&FixContext {
+            job: "build",
+            step: Some("Build workspace"),
+            failures: &failures,
+            log: Some(log),
+        });
+
+        let ids: Vec<_> = suggestions.iter().map(|s| s.rule_id.as_str()).collect();
+        assert_eq!(ids, vec!["rust-unresolved-import", "step-build"]);
+        assert_eq!(suggestions[0].fingerprint.as_deref(), Some(failures[0].fingerprint.as_str()));
+    }
+
+    #[test]
+    fn test_repo_rules_override_and_fingerprint_match() {
+        let log = "error[E0432]: unresolved import `serde_json`\n --> src/main.rs:3:5\n";
+        let failures = parse_log(log);
+        let repo_rules = format!(r#"
+rules:
+  - id: step-build
+    title: Build failed
+    match:
+      step: "(?i)compile"
+    suggestion: Run make prepare first.
+  - id: known-serde-break
+    title: serde_json removed from workspace deps
+    match:
+      fingerprint: "{}"
+    suggestion: Re-add serde_json to the crate manifest.
+    confidence: 0.95
+"#, failures[0].fingerprint);
+        let kb = KnowledgeBase::shipped().unwrap().with_rules_str(&repo_rules).unwrap();
+
+        let suggestions = kb.suggest(&FixContext {
+            job: "build",
+            step: Some("Build workspace"),
+            failures: &failures,
+            log: None,
+        });
+        let ids: Vec<_> = suggestions.iter().map(|s| s.rule_id.as_str()).collect();
+        assert_eq!(ids, vec!["known-serde-break", "rust-unresolved-import"]);
+    }
+
+    #[test]
+    fn test_recorded_outcomes_adjust_confidence() {
+        let store = HistoryStore::open_in_memory().unwrap();
+        store.record_fix_outcome("step-test", None, true).unwrap();
+        store.record_fix_outcome("step-test", None, true).unwrap();
+        store.record_fix_outcome("step-checkout", None, false).unwrap();
+
+        let kb = KnowledgeBase::shipped().unwrap().with_outcomes(&store).unwrap();
+        let confidence = |id: &str| kb.rules().iter().find(|r| r.id == id).unwrap().effective_confidence();
+
+        // 0.3 prior with weight 2: (0.6 + 2) / 4 and (0.6 + 0) / 3
+        assert!((confidence("step-test") - 0.65).abs() < 1e-9);
+        assert!((confidence("step-checkout") - 0.2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,442 @@
+//! # Flaky Detection
+//!
+//! Tells flaky jobs and tests apart from broken ones. A job is flaky when it
+//! both passed and failed for the same head SHA, either across runs or across
+//! re-run attempts of one run. A test is flaky when it failed on a SHA where
+//! the same job also passed.
+//!
+//! ## Flake Rate
+//!
+//! ```text
+//! flake_rate = SHAs with both outcomes / SHAs the job ran on
+//! ```
+//!
+//! Entries are ranked by flake rate, then by failure count. With
+//! `--create-issues` every flaky test gets a tracking issue labeled
+//! `flaky-test`, updated in place on later runs.
+
+use crate::github::{GitHubClient, RunFilter, WorkflowAnalysis, WorkflowRun};
+use crate::log_parser::parse_log;
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{info, warn};
+
+/// Label of flaky test tracking issues
+pub const FLAKY_LABEL: &str = "flaky-test";
+
+/// Title prefix of flaky test tracking issues, followed by the test name
+pub const FLAKY_TITLE_PREFIX: &str = "Flaky test: ";
+
+/// One execution of a job (any attempt) and the tests it failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobObservation {
+    pub workflow: String,
+    pub job: String,
+    pub head_sha: String,
+    pub run_id: u64,
+    pub attempt: u32,
+    pub passed: bool,
+    pub failed_tests: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlakyKind {
+    Job,
+    Test,
+}
+
+/// A flaky job or test, ranked by flake rate
+#[derive(Debug, Clone, Serialize)]
+pub struct FlakyEntry {
+    pub kind: FlakyKind,
+    /// Job name, or test name for tests
+    pub name: String,
+    pub workflow: String,
+    pub job: String,
+    /// SHAs the job/test both passed and failed on
+    pub flaky_shas: Vec<String>,
+    /// SHAs the job ran on in the window
+    pub total_shas: usize,
+    pub failures: usize,
+    pub flake_rate: f64,
+}
+
+/// Turn analyzed runs into job observations (latest attempt only)
+pub fn observations_from_analyses(analyses: &[WorkflowAnalysis]) -> Vec<JobObservation> {
+    analyses
+        .iter()
+        .flat_map(|analysis| {
+            analysis.jobs.iter().filter_map(move |job| {
+                let passed = match job.conclusion.as_deref() {
+                    Some("success") => true,
+                    Some("failure") => false,
+                    _ => return None,
+                };
+                let failed_tests = analysis
+                    .failures
+                    .iter()
+                    .filter(|f| f.job.as_deref() == Some(job.name.as_str()))
+                    .filter_map(|f| f.test.clone())
+                    .collect();
+
+                Some(JobObservation {
+                    workflow: analysis.run.name.clone(),
+                    job: job.name.clone(),
+                    head_sha: analysis.run.head_sha.clone(),
+                    run_id: analysis.run.id,
+                    attempt: analysis.run.run_attempt,
+                    passed,
+                    failed_tests,
+                })
+            })
+        })
+        .collect()
+}
+
+/// Detect flaky jobs and tests, highest flake rate first
+pub fn detect_flaky(observations: &[JobObservation]) -> Vec<FlakyEntry> {
+    // (workflow, job) -> sha -> observations
+    let mut groups: BTreeMap<(&str, &str), BTreeMap<&str, Vec<&JobObservation>>> = BTreeMap::new();
+    for obs in observations {
+        groups
+            .entry((obs.workflow.as_str(), obs.job.as_str()))
+            .or_default()
+            .entry(obs.head_sha.as_str())
+            .or_default()
+            .push(obs);
+    }
+
+    let mut entries = Vec::new();
+
+    for ((workflow, job), by_sha) in &groups {
+        let total_shas = by_sha.len();
+        let mut flaky_shas = Vec::new();
+        let mut failures = 0;
+        // test -> (SHAs where it failed while the job also passed, failure count)
+        let mut tests: BTreeMap<&str, (BTreeSet<&str>, usize)> = BTreeMap::new();
+
+        for (sha, runs) in by_sha {
+            let passed = runs.iter().any(|o| o.passed);
+            let failed: Vec<&&JobObservation> = runs.iter().filter(|o| !o.passed).collect();
+            failures += failed.len();
+
+            if !passed || failed.is_empty() {
+                continue;
+            }
+            flaky_shas.push(sha.to_string());
+
+            for obs in failed {
+                for test in &obs.failed_tests {
+                    let entry = tests.entry(test.as_str()).or_default();
+                    entry.0.insert(sha);
+                    entry.1 += 1;
+                }
+            }
+        }
+
+        if flaky_shas.is_empty() {
+            continue;
+        }
+
+        for (test, (shas, count)) in tests {
+            entries.push(FlakyEntry {
+                kind: FlakyKind::Test,
+                name: test.to_string(),
+                workflow: workflow.to_string(),
+                job: job.to_string(),
+                flake_rate: shas.len() as f64 / total_shas as f64,
+                flaky_shas: shas.into_iter().map(String::from).collect(),
+                total_shas,
+                failures: count,
+            });
+        }
+
+        entries.push(FlakyEntry {
+            kind: FlakyKind::Job,
+            name: job.to_string(),
+            workflow: workflow.to_string(),
+            job: job.to_string(),
+            flake_rate: flaky_shas.len() as f64 / total_shas as f64,
+            flaky_shas,
+            total_shas,
+            failures,
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        b.flake_rate
+            .total_cmp(&a.flake_rate)
+            .then(b.failures.cmp(&a.failures))
+            .then(a.name.cmp(&b.name))
+    });
+    entries
+}
+
+/// Job observations of the attempts before `run.run_attempt`
+///
+/// Logs of failed jobs are parsed so a test that failed on attempt 1 and
+/// passed on the re-run is reported as flaky, not only its job.
+pub async fn earlier_attempt_observations(client: &GitHubClient, run: &WorkflowRun) -> Vec<JobObservation> {
+    let mut observations = Vec::new();
+
+    for attempt in 1..run.run_attempt {
+        let jobs = match client.get_jobs_for_attempt(run.id, attempt).await {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                warn!("⚠️  Could not fetch attempt {} of run {}: {}", attempt, run.id, e);
+                continue;
+            }
+        };
+
+        for job in jobs {
+            let passed = match job.conclusion.as_deref() {
+                Some("success") => true,
+                Some("failure") => false,
+                _ => continue,
+            };
+            let failed_tests = if passed {
+                Vec::new()
+            } else {
+                match client.get_job_logs(job.id).await {
+                    Ok(log) => parse_log(&log).into_iter().filter_map(|f| f.test).collect(),
+                    Err(e) => {
+                        warn!("⚠️  Could not download the log of job '{}' (run #{} attempt {}): {}", job.name, run.id, attempt, e);
+                        Vec::new()
+                    }
+                }
+            };
+
+            observations.push(JobObservation {
+                workflow: run.name.clone(),
+                job: job.name,
+                head_sha: run.head_sha.clone(),
+                run_id: run.id,
+                attempt,
+                passed,
+                failed_tests,
+            });
+        }
+    }
+
+    observations
+}
+
+/// Detect flaky jobs/tests over the last `hours` and report them
+pub async fn run_flaky_analysis(
+    client: &GitHubClient,
+    filter: &RunFilter,
+    hours: u64,
+    create_issues: bool,
+    output_format: &str,
+) -> Result<Vec<FlakyEntry>> {
+    info!("🎲 Detecting flaky jobs and tests over the last {} hours...", hours);
+
+    let cutoff = Utc::now() - Duration::hours(hours as i64);
+    let runs = client.collect_runs(&filter.clone().created_since(cutoff)).await?;
+
+    let analyses = client.analyze_runs_parallel(runs).await?;
+    let mut observations = observations_from_analyses(&analyses);
+
+    // Earlier attempts of re-run workflows
+    for analysis in analyses.iter().filter(|a| a.run.run_attempt > 1) {
+        observations.extend(earlier_attempt_observations(client, &analysis.run).await);
+    }
+
+    let entries = detect_flaky(&observations);
+    info!("🎲 Found {} flaky job(s)/test(s)", entries.len());
+
+    match output_format {
+        "json" => println!("{}", serde_json::to_string_pretty(&entries)?),
+        "markdown" => println!("{}", flaky_markdown(&entries)),
+        _ => print_terminal_report(&entries),
+    }
+
+    if create_issues {
+        upsert_tracking_issues(client, &entries).await?;
+    }
+
+    Ok(entries)
+}
+
+/// Markdown table of flaky entries
+pub fn flaky_markdown(entries: &[FlakyEntry]) -> String {
+    let mut md = String::from("# 🎲 Flaky Report\n\n");
+    if entries.is_empty() {
+        md.push_str("No flaky jobs or tests detected.\n");
+        return md;
+    }
+
+    md.push_str("| Kind | Name | Workflow / Job | Flake Rate | Failures | SHAs |\n");
+    md.push_str("|------|------|----------------|------------|----------|------|\n");
+    for entry in entries {
+        md.push_str(&format!(
+            "| {:?} | `{}` | {} / {} | {:.0}% | {} | {}/{} |\n",
+            entry.kind,
+            entry.name,
+            entry.workflow,
+            entry.job,
+            entry.flake_rate * 100.0,
+            entry.failures,
+            entry.flaky_shas.len(),
+            entry.total_shas
+        ));
+    }
+    md
+}
+
+fn print_terminal_report(entries: &[FlakyEntry]) {
+    println!("\n🎲 Flaky Report\n");
+    if entries.is_empty() {
+        println!("No flaky jobs or tests detected.");
+        return;
+    }
+
+    println!("{:<6} {:<50} {:>8} {:>9}", "Kind", "Name", "Rate", "Failures");
+    println!("{}", "-".repeat(76));
+    for entry in entries {
+        let name: String = entry.name.chars().take(50).collect();
+        println!(
+            "{:<6} {:<50} {:>7.0}% {:>9}",
+            format!("{:?}", entry.kind),
+            name,
+            entry.flake_rate * 100.0,
+            entry.failures
+        );
+    }
+}
+
+/// Open or update one tracking issue per flaky test
+async fn upsert_tracking_issues(client: &GitHubClient, entries: &[FlakyEntry]) -> Result<()> {
+    let existing = client.list_issues_with_label(FLAKY_LABEL, "open").await?;
+
+    for entry in entries.iter().filter(|e| e.kind == FlakyKind::Test) {
+        let title = format!("{}{}", FLAKY_TITLE_PREFIX, entry.name);
+        let body = format!(
+            "## 🎲 Flaky Test\n\n\
+             | | |\n|---|---|\n\
+             | Test | `{}` |\n\
+             | Workflow / Job | {} / {} |\n\
+             | Flake rate | {:.0}% ({} of {} SHAs) |\n\
+             | Failures | {} |\n\n\
+             **SHAs with both outcomes:** {}\n\n\
+             ---\n*Updated by Git-Core Protocol flaky detection*\n",
+            entry.name,
+            entry.workflow,
+            entry.job,
+            entry.flake_rate * 100.0,
+            entry.flaky_shas.len(),
+            entry.total_shas,
+            entry.failures,
+            entry.flaky_shas.iter().map(|s| format!("`{}`", &s[..s.len().min(7)])).collect::<Vec<_>>().join(", "),
+        );
+
+        match existing.iter().find(|i| i.title == title) {
+            Some(issue) => {
+                client.update_issue_body(issue.number, &body).await?;
+                info!("📝 Updated flaky test issue #{}", issue.number);
+            }
+            None => {
+                let issue = client.create_issue(&title, &body, &[FLAKY_LABEL]).await?;
+                info!("📝 Opened flaky test issue #{}", issue.number);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cassette::{Cassette, CassetteServer};
+    use serde_json::json;
+
+    fn obs(job: &str, sha: &str, attempt: u32, passed: bool, tests: &[&str]) -> JobObservation {
+        JobObservation {
+            workflow: "CI".to_string(),
+            job: job.to_string(),
+            head_sha: sha.to_string(),
+            run_id: 1,
+            attempt,
+            passed,
+            failed_tests: tests.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_rerun_pass_marks_job_and_test_flaky() {
+        let observations = vec![
+            obs("test", "aaa", 1, false, &["api::login"]),
+            obs("test", "aaa", 2, true, &[]),
+            obs("test", "bbb", 1, true, &[]),
+        ];
+
+        let entries = detect_flaky(&observations);
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.flake_rate == 0.5));
+
+        let test = entries.iter().find(|e| e.kind == FlakyKind::Test).unwrap();
+        assert_eq!(test.name, "api::login");
+        assert_eq!(test.flaky_shas, vec!["aaa"]);
+    }
+
+    #[test]
+    fn test_consistently_failing_job_is_broken_not_flaky() {
+        let observations = vec![
+            obs("build", "aaa", 1, false, &[]),
+            obs("build", "aaa", 2, false, &[]),
+            obs("build", "bbb", 1, false, &[]),
+        ];
+        assert!(detect_flaky(&observations).is_empty());
+    }
+
+    #[test]
+    fn test_ranked_by_flake_rate() {
+        let observations = vec![
+            obs("lint", "aaa", 1, false, &[]),
+            obs("lint", "aaa", 2, true, &[]),
+            obs("lint", "bbb", 1, true, &[]),
+            obs("lint", "ccc", 1, true, &[]),
+            obs("e2e", "aaa", 1, false, &[]),
+            obs("e2e", "aaa", 2, true, &[]),
+        ];
+
+        let entries = detect_flaky(&observations);
+        assert_eq!(entries[0].name, "e2e");
+        assert_eq!(entries[0].flake_rate, 1.0);
+        assert_eq!(entries[1].name, "lint");
+    }
+
+    #[tokio::test]
+    async fn test_earlier_attempt_logs_reveal_flaky_test() {
+        let job = |id: u64, conclusion: &str| json!({
+            "id": id, "name": "test", "status": "completed", "conclusion": conclusion,
+            "started_at": null, "completed_at": null, "steps": []
+        });
+        let cassette: Cassette = serde_json::from_value(json!({ "interactions": [
+            { "request": { "method": "GET", "path": "/repos/acme/widgets/actions/runs/5/attempts/1/jobs?per_page=100&page=1" },
+              "response": { "status": 200, "body": { "total_count": 1, "jobs": [job(51, "failure")] } } },
+            { "request": { "method": "GET", "path": "/repos/acme/widgets/actions/jobs/51/logs" },
+              "response": { "status": 200, "content_type": "text/plain",
+                            "body": "running 2 tests\ntest api::login ... FAILED\ntest api::logout ... ok\n" } },
+        ] })).unwrap();
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("token", "acme/widgets", 2).with_base_url(server.url());
+
+        let run: WorkflowRun = serde_json::from_value(json!({
+            "id": 5, "name": "CI", "status": "completed", "conclusion": "success",
+            "html_url": "", "created_at": "", "updated_at": "", "run_attempt": 2,
+            "workflow_id": 1, "head_branch": "main", "head_sha": "aaa", "path": "", "event": "push"
+        })).unwrap();
+        let mut observations = earlier_attempt_observations(&client, &run).await;
+        observations.push(obs("test", "aaa", 2, true, &[]));
+
+        assert_eq!(observations[0].failed_tests, vec!["api::login"]);
+        let entries = detect_flaky(&observations);
+        assert!(entries.iter().any(|e| e.kind == FlakyKind::Test && e.name == "api::login"));
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+    }
+}
@@ -1,22 +1,41 @@
 //! GitHub API client with parallel execution support
 
+use crate::log_parser::{parse_log, LogFailure};
 use anyhow::{Result, Context};
 use reqwest::{Client, header};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use crate::parallel::RateLimiter;
+use chrono::{DateTime, SecondsFormat, Utc};
 use std::sync::Arc;
-use tokio::sync::Semaphore;
 use futures::future::join_all;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use tracing::{info, debug, warn};
 
+/// Public GitHub API, used unless `GITHUB_API_URL` or `with_base_url` says otherwise
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// Largest page size the REST API accepts
+const PAGE_SIZE: usize = 100;
+
+/// API base URL from `GITHUB_API_URL` (set on Actions runners, GHES included)
+pub fn api_base_url() -> String {
+    std::env::var("GITHUB_API_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_API_URL.to_string())
+}
+
 /// GitHub API client with rate limiting and parallel execution
 pub struct GitHubClient {
     client: Client,
+    base_url: String,
     repo: String,
     owner: String,
-    semaphore: Arc<Semaphore>,
+    limiter: Arc<RateLimiter>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkflowRun {
     pub id: u64,
     pub name: String,
@@ -29,6 +48,12 @@ pub struct WorkflowRun {
     pub workflow_id: u64,
     pub head_branch: String,
     pub head_sha: String,
+    /// Workflow file, e.g. `.github/workflows/ci.yml`
+    #[serde(default)]
+    pub path: String,
+    /// Triggering event (`push`, `pull_request`, `schedule`, ...)
+    #[serde(default)]
+    pub event: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -37,7 +62,117 @@ pub struct WorkflowRunsResponse {
     pub workflow_runs: Vec<WorkflowRun>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Server-side filters for listing workflow runs
+///
+/// Unset fields are not sent; `limit` caps the number of runs a stream yields
+/// (all matching runs when unset).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunFilter {
+    /// Workflow file name (`ci.yml`) or ID
+    pub workflow: Option<String>,
+    pub branch: Option<String>,
+    pub event: Option<String>,
+    pub actor: Option<String>,
+    /// Run status or conclusion (`completed`, `in_progress`, `failure`, ...)
+    pub status: Option<String>,
+    /// Creation date range in search syntax (`>=2024-05-01`, `2024-05-01..2024-05-07`)
+    pub created: Option<String>,
+    pub head_sha: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl RunFilter {
+    pub fn with_head_sha(mut self, head_sha: &str) -> Self {
+        self.head_sha = Some(head_sha.to_string());
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Runs created at or after `since`, narrowing a `created` range that is already set
+    pub fn created_since(mut self, since: DateTime<Utc>) -> Self {
+        let (lower, upper) = match self.created.as_deref() {
+            None => (None, None),
+            Some(created) => match parse_created(created) {
+                Some(bounds) => bounds,
+                None => {
+                    warn!("Cannot combine created range '{}' with a time window; using it as given", created);
+                    return self;
+                }
+            },
+        };
+
+        let lower = lower.map_or(since, |lower| lower.max(since));
+        let format = |dt: DateTime<Utc>| dt.to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.created = Some(match upper {
+            Some(upper) => format!("{}..{}", format(lower), format(upper)),
+            None => format!(">={}", format(lower)),
+        });
+        self
+    }
+
+    /// Query parameters of the filter, excluding paging
+    fn query(&self) -> Vec<(&'static str, String)> {
+        [
+            ("branch", &self.branch),
+            ("event", &self.event),
+            ("actor", &self.actor),
+            ("status", &self.status),
+            ("created", &self.created),
+            ("head_sha", &self.head_sha),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.clone().map(|v| (key, v)))
+        .collect()
+    }
+
+    /// Page size: the API maximum, or less when the limit fits in one page
+    fn per_page(&self) -> u32 {
+        self.limit.map_or(100, |limit| limit.clamp(1, 100) as u32)
+    }
+}
+
+/// Inclusive lower and upper bound of a `created` range; `None` is unbounded
+type CreatedBounds = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Bounds of a `created` search range (`>=X`, `>X`, `<=Y`, `<Y`, `X..Y`, `X`)
+///
+/// Dates cover the whole day. `None` when the range does not parse.
+fn parse_created(created: &str) -> Option<CreatedBounds> {
+    let second = chrono::Duration::seconds(1);
+    let bound = |value: &str| -> Option<Option<(DateTime<Utc>, DateTime<Utc>)>> {
+        if value == "*" {
+            return Some(None);
+        }
+        if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+            let dt = dt.with_timezone(&Utc);
+            return Some(Some((dt, dt)));
+        }
+        let day = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?.and_utc();
+        Some(Some((day, day + chrono::Duration::days(1) - second)))
+    };
+
+    let created = created.trim();
+    if let Some(value) = created.strip_prefix(">=") {
+        Some((bound(value)?.map(|(start, _)| start), None))
+    } else if let Some(value) = created.strip_prefix('>') {
+        Some((bound(value)?.map(|(_, end)| end + second), None))
+    } else if let Some(value) = created.strip_prefix("<=") {
+        Some((None, bound(value)?.map(|(_, end)| end)))
+    } else if let Some(value) = created.strip_prefix('<') {
+        Some((None, bound(value)?.map(|(start, _)| start - second)))
+    } else if let Some((from, to)) = created.split_once("..") {
+        Some((bound(from)?.map(|(start, _)| start), bound(to)?.map(|(_, end)| end)))
+    } else {
+        let (start, end) = bound(created)??;
+        Some((Some(start), Some(end)))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Job {
     pub id: u64,
     pub name: String,
@@ -46,9 +181,12 @@ pub struct Job {
     pub started_at: Option<String>,
     pub completed_at: Option<String>,
     pub steps: Option<Vec<Step>>,
+    /// `runs-on` labels, e.g. `ubuntu-latest`
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Step {
     pub name: String,
     pub status: String,
@@ -98,12 +236,74 @@ pub struct CreateIssueCommentRequest {
     pub body: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct User {
+    pub login: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PullRequestInfo {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub user: User,
+    pub merged_at: Option<String>,
+    pub merge_commit_sha: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    pub head: Option<BranchRef>,
+    #[serde(default)]
+    pub base: Option<BranchRef>,
+    /// Number of commits (only returned for a single pull request)
+    #[serde(default)]
+    pub commits: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PullRequestCommit {
+    pub sha: String,
+    pub commit: CommitDetail,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CommitDetail {
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BranchRef {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// Repository the branch lives in (`None` once a fork is deleted)
+    #[serde(default)]
+    pub repo: Option<RepoRef>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct RepoRef {
+    pub full_name: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct IssueSummary {
+    pub number: u64,
+    pub title: String,
+    /// Present when the issue is a pull request
+    pub pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowAnalysis {
     pub run: WorkflowRun,
     pub jobs: Vec<Job>,
-    pub logs: Option<String>,
+    /// Logs of the failed jobs that could be downloaded, by job id
+    ///
+    /// Not serialized: stored analyses and JSON output keep the parsed `failures`.
+    #[serde(skip)]
+    pub job_logs: HashMap<u64, String>,
     pub errors: Vec<String>,
+    /// Structured failures parsed from the logs of failed jobs
+    pub failures: Vec<LogFailure>,
     pub warnings: Vec<String>,
     pub duration_seconds: Option<i64>,
 }
@@ -143,51 +343,229 @@ impl GitHubClient {
 
         Self {
             client,
+            base_url: api_base_url(),
             repo: repo_name,
             owner,
-            semaphore: Arc::new(Semaphore::new(max_parallel)),
+            limiter: Arc::new(RateLimiter::new(max_parallel, 0)),
         }
     }
 
+    /// Send requests to another API root (GHES, a stub server or a cassette replay)
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     fn api_url(&self, path: &str) -> String {
-        format!("https://api.github.com/repos/{}/{}{}", self.owner, self.repo, path)
+        format!("{}/repos/{}/{}{}", self.base_url, self.owner, self.repo, path)
+    }
+
+    /// Every page of a list endpoint that returns a JSON array
+    async fn get_all_pages<T: serde::de::DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<T>> {
+        let url = self.api_url(path);
+        let mut items = Vec::new();
+        for page in 1.. {
+            let batch: Vec<T> = {
+                let _permit = self.limiter.acquire().await;
+                self.client
+                    .get(&url)
+                    .query(query)
+                    .query(&[("per_page", PAGE_SIZE), ("page", page)])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?
+            };
+            let last = batch.len() < PAGE_SIZE;
+            items.extend(batch);
+            if last {
+                break;
+            }
+        }
+        Ok(items)
     }
 
-    /// Get all workflow runs with parallel job fetching
-    pub async fn get_workflow_runs(&self, per_page: u32) -> Result<Vec<WorkflowRun>> {
-        let url = self.api_url(&format!("/actions/runs?per_page={}", per_page));
+    /// Stream workflow runs matching `filter`, newest first, fetching pages as they are consumed
+    ///
+    /// Filters are applied by the API. Pages are requested through the client's
+    /// rate limiter, so the stream shares the concurrency bound with job and log
+    /// requests. The API stops paginating after 1000 results; narrow `created`
+    /// for longer histories.
+    pub fn workflow_runs(&self, filter: &RunFilter) -> impl Stream<Item = Result<WorkflowRun>> + '_ {
+        let path = match &filter.workflow {
+            Some(workflow) => format!("/actions/workflows/{}/runs", workflow),
+            None => "/actions/runs".to_string(),
+        };
+        let url = self.api_url(&path);
+        let query = filter.query();
+        let per_page = filter.per_page();
+        let limit = filter.limit.unwrap_or(usize::MAX);
 
-        let response: WorkflowRunsResponse = self.client
+        // State: next page to fetch and runs seen so far; `None` once the last page was read
+        stream::try_unfold(Some((1u32, 0usize)), move |state| {
+            let url = url.clone();
+            let query = query.clone();
+            async move {
+                let Some((page, seen)) = state else {
+                    return Ok(None);
+                };
+                let response: WorkflowRunsResponse = {
+                    let _permit = self.limiter.acquire().await;
+                    self.client
+                        .get(&url)
+                        .query(&query)
+                        .query(&[("per_page", per_page), ("page", page)])
+                        .send()
+                        .await?
+                        .error_for_status()?
+                        .json()
+                        .await?
+                };
+                debug!("Fetched page {} of workflow runs ({} of {})", page, response.workflow_runs.len(), response.total_count);
+
+                let seen = seen + response.workflow_runs.len();
+                let more = response.workflow_runs.len() == per_page as usize
+                    && seen < response.total_count as usize
+                    && seen < limit;
+                let next = more.then_some((page + 1, seen));
+                Ok::<_, anyhow::Error>(Some((stream::iter(response.workflow_runs.into_iter().map(Ok)), next)))
+            }
+        })
+        .try_flatten()
+        .take(limit)
+    }
+
+    /// Collect all workflow runs matching `filter`
+    pub async fn collect_runs(&self, filter: &RunFilter) -> Result<Vec<WorkflowRun>> {
+        let runs: Vec<WorkflowRun> = self.workflow_runs(filter).try_collect().await?;
+        info!("📊 Found {} workflow runs", runs.len());
+        Ok(runs)
+    }
+
+    /// Get a single workflow run
+    pub async fn get_workflow_run(&self, run_id: u64) -> Result<WorkflowRun> {
+        let url = self.api_url(&format!("/actions/runs/{}", run_id));
+
+        let _permit = self.limiter.acquire().await;
+        let run = self.client
             .get(&url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
 
-        info!("📊 Found {} workflow runs", response.total_count);
-        Ok(response.workflow_runs)
+        Ok(run)
     }
 
-    /// Get jobs for a workflow run
-    pub async fn get_jobs(&self, run_id: u64) -> Result<Vec<Job>> {
-        let _permit = self.semaphore.acquire().await?;
+    /// Trigger a `workflow_dispatch` event; `workflow` is the file name or ID
+    pub async fn dispatch_workflow(&self, workflow: &str, git_ref: &str, inputs: &BTreeMap<String, String>) -> Result<()> {
+        let url = self.api_url(&format!("/actions/workflows/{}/dispatches", workflow));
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .post(&url)
+            .json(&serde_json::json!({ "ref": git_ref, "inputs": inputs }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Login of the user the token belongs to (fails for GitHub App installation tokens)
+    pub async fn get_authenticated_user(&self) -> Result<String> {
+        let url = format!("{}/user", self.base_url);
+
+        #[derive(Deserialize)]
+        struct UserResponse {
+            login: String,
+        }
+
+        let _permit = self.limiter.acquire().await;
+        let response: UserResponse = self.client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
 
-        let url = self.api_url(&format!("/actions/runs/{}/jobs", run_id));
+        Ok(response.login)
+    }
+
+    /// Resolve a branch, tag or SHA to the commit SHA it points at
+    pub async fn get_commit_sha(&self, git_ref: &str) -> Result<String> {
+        let url = self.api_url(&format!("/commits/{}", git_ref));
+
+        #[derive(Deserialize)]
+        struct CommitResponse {
+            sha: String,
+        }
 
-        let response: JobsResponse = self.client
+        let _permit = self.limiter.acquire().await;
+        let response: CommitResponse = self.client
             .get(&url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
 
-        Ok(response.jobs)
+        Ok(response.sha)
+    }
+
+    /// Get jobs for a workflow run
+    pub async fn get_jobs(&self, run_id: u64) -> Result<Vec<Job>> {
+        self.get_job_pages(&format!("/actions/runs/{}/jobs", run_id), &[]).await
+    }
+
+    /// Get jobs of every attempt of a workflow run (re-runs included)
+    pub async fn get_jobs_all_attempts(&self, run_id: u64) -> Result<Vec<Job>> {
+        self.get_job_pages(&format!("/actions/runs/{}/jobs", run_id), &[("filter", "all")]).await
+    }
+
+    /// Get jobs of a specific attempt of a workflow run (earlier attempts of re-runs)
+    pub async fn get_jobs_for_attempt(&self, run_id: u64, attempt: u32) -> Result<Vec<Job>> {
+        self.get_job_pages(&format!("/actions/runs/{}/attempts/{}/jobs", run_id, attempt), &[]).await
+    }
+
+    /// Follow the pages of a jobs listing, which wraps its items in `{total_count, jobs}`
+    async fn get_job_pages(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<Job>> {
+        let url = self.api_url(path);
+        let mut jobs = Vec::new();
+        for page in 1.. {
+            let response: JobsResponse = {
+                let _permit = self.limiter.acquire().await;
+                self.client
+                    .get(&url)
+                    .query(query)
+                    .query(&[("per_page", PAGE_SIZE), ("page", page)])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?
+            };
+            let last = response.jobs.len() < PAGE_SIZE;
+            jobs.extend(response.jobs);
+            if last || jobs.len() >= response.total_count as usize {
+                break;
+            }
+        }
+        Ok(jobs)
     }
 
     /// Get all workflows
     pub async fn get_workflows(&self) -> Result<Vec<Workflow>> {
         let url = self.api_url("/actions/workflows");
 
+        let _permit = self.limiter.acquire().await;
         let response: WorkflowsResponse = self.client
             .get(&url)
             .send()
@@ -198,22 +576,35 @@ impl GitHubClient {
         Ok(response.workflows)
     }
 
-    /// Get logs for a job (returns URL, actual download is separate)
+    /// Download the plain-text log of a job (fails once logs expired or are not ready)
     pub async fn get_job_logs(&self, job_id: u64) -> Result<String> {
-        let _permit = self.semaphore.acquire().await?;
+        let _permit = self.limiter.acquire().await;
 
         let url = self.api_url(&format!("/actions/jobs/{}/logs", job_id));
 
-        let response = self.client
+        let log = self.client
             .get(&url)
             .send()
+            .await?
+            .error_for_status()?
+            .text()
             .await?;
 
-        if response.status().is_success() {
-            Ok(response.text().await?)
-        } else {
-            Ok(String::new())
-        }
+        Ok(log)
+    }
+
+    /// Re-run the failed jobs of a workflow run (and the jobs that depend on them)
+    pub async fn rerun_failed_jobs(&self, run_id: u64) -> Result<()> {
+        let url = self.api_url(&format!("/actions/runs/{}/rerun-failed-jobs", run_id));
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .post(&url)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
     }
 
     /// Analyze multiple workflow runs in parallel
@@ -246,12 +637,27 @@ impl GitHubClient {
 
         let mut errors = Vec::new();
         let mut warnings = Vec::new();
+        let mut failures = Vec::new();
+        let mut job_logs = HashMap::new();
 
         // Collect errors and warnings from jobs
         for job in &jobs {
             if job.conclusion.as_deref() == Some("failure") {
                 errors.push(format!("Job '{}' failed", job.name));
 
+                // Download and parse the log of every failed job; without a log
+                // the job is reported by its fallback `Job 'x' failed` signature
+                match self.get_job_logs(job.id).await {
+                    Ok(log) => {
+                        failures.extend(parse_log(&log).into_iter().map(|f| f.in_job(&job.name)));
+                        job_logs.insert(job.id, log);
+                    }
+                    Err(e) => {
+                        warn!("⚠️  Could not download the log of job '{}' (run #{}): {}", job.name, run.id, e);
+                        warnings.push(format!("Log of job '{}' unavailable, failures not parsed", job.name));
+                    }
+                }
+
                 if let Some(steps) = &job.steps {
                     for step in steps {
                         if step.conclusion.as_deref() == Some("failure") {
@@ -281,74 +687,249 @@ impl GitHubClient {
         Ok(WorkflowAnalysis {
             run,
             jobs,
-            logs: None, // Fetch on demand to save API calls
+            job_logs,
             errors,
+            failures,
             warnings,
             duration_seconds,
         })
     }
 
-    /// Create a PR with validation results
+    /// Create a PR with validation results against the default branch
     pub async fn create_pr(&self, title: &str, body: &str, branch: &str) -> Result<PRResponse> {
+        let base = self.get_default_branch().await?;
+        self.create_pr_with_base(title, body, branch, &base).await
+    }
+
+    /// Add comment to PR
+    pub async fn add_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        let url = self.api_url(&format!("/issues/{}/comments", pr_number));
+
+        let request = CreateIssueCommentRequest {
+            body: body.to_string(),
+        };
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Add labels to an issue or PR
+    pub async fn add_labels(&self, issue_number: u64, labels: &[&str]) -> Result<()> {
+        let url = self.api_url(&format!("/issues/{}/labels", issue_number));
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .post(&url)
+            .json(&serde_json::json!({ "labels": labels }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Remove a label from an issue or PR (missing labels are ignored)
+    pub async fn remove_label(&self, issue_number: u64, label: &str) -> Result<()> {
+        let mut url = reqwest::Url::parse(&self.api_url(&format!("/issues/{}/labels", issue_number)))?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid API URL {}", self.base_url))?
+            .push(label);
+
+        let _permit = self.limiter.acquire().await;
+        let response = self.client.delete(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        response.error_for_status()?;
+        Ok(())
+    }
+
+    /// List issues and PRs carrying a label
+    pub async fn list_issues_with_label(&self, label: &str, state: &str) -> Result<Vec<IssueSummary>> {
+        self.get_all_pages("/issues", &[("labels", label), ("state", state)]).await
+    }
+
+    /// Create an issue
+    pub async fn create_issue(&self, title: &str, body: &str, labels: &[&str]) -> Result<IssueSummary> {
+        let url = self.api_url("/issues");
+
+        let _permit = self.limiter.acquire().await;
+        let issue = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "title": title, "body": body, "labels": labels }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(issue)
+    }
+
+    /// Replace the body of an issue
+    pub async fn update_issue_body(&self, issue_number: u64, body: &str) -> Result<()> {
+        let url = self.api_url(&format!("/issues/{}", issue_number));
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .patch(&url)
+            .json(&serde_json::json!({ "body": body }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Replace the title and body of an issue or PR
+    pub async fn update_issue(&self, issue_number: u64, title: &str, body: &str) -> Result<()> {
+        let url = self.api_url(&format!("/issues/{}", issue_number));
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .patch(&url)
+            .json(&serde_json::json!({ "title": title, "body": body }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Get a pull request
+    pub async fn get_pull_request(&self, pr_number: u64) -> Result<PullRequestInfo> {
+        let url = self.api_url(&format!("/pulls/{}", pr_number));
+
+        let _permit = self.limiter.acquire().await;
+        let pr = self.client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(pr)
+    }
+
+    /// Create a PR against an explicit base branch
+    pub async fn create_pr_with_base(&self, title: &str, body: &str, branch: &str, base: &str) -> Result<PRResponse> {
         let url = self.api_url("/pulls");
 
         let request = CreatePRRequest {
             title: title.to_string(),
             body: body.to_string(),
             head: branch.to_string(),
-            base: "main".to_string(),
+            base: base.to_string(),
             draft: false,
         };
 
+        let _permit = self.limiter.acquire().await;
         let response = self.client
             .post(&url)
             .json(&request)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
 
         Ok(response)
     }
 
-    /// Add comment to PR
-    pub async fn add_pr_comment(&self, pr_number: u64, body: &str) -> Result<()> {
-        let url = self.api_url(&format!("/issues/{}/comments", pr_number));
+    /// Get the repository's default branch name
+    pub async fn get_default_branch(&self) -> Result<String> {
+        let url = self.api_url("");
 
-        let request = CreateIssueCommentRequest {
-            body: body.to_string(),
-        };
+        #[derive(Deserialize)]
+        struct RepoResponse {
+            default_branch: String,
+        }
 
-        self.client
-            .post(&url)
-            .json(&request)
+        let _permit = self.limiter.acquire().await;
+        let response: RepoResponse = self.client
+            .get(&url)
             .send()
+            .await?
+            .error_for_status()?
+            .json()
             .await?;
 
-        Ok(())
+        Ok(response.default_branch)
+    }
+
+    /// Repository visibility: `public`, `private` or `internal`
+    pub async fn get_repo_visibility(&self) -> Result<String> {
+        let url = self.api_url("");
+
+        #[derive(Deserialize)]
+        struct RepoResponse {
+            visibility: String,
+        }
+
+        let _permit = self.limiter.acquire().await;
+        let response: RepoResponse = self.client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(response.visibility)
     }
 
     /// Create a branch
     pub async fn create_branch(&self, branch_name: &str, from_sha: &str) -> Result<()> {
-        let url = format!("https://api.github.com/repos/{}/{}/git/refs", self.owner, self.repo);
+        let url = self.api_url("/git/refs");
 
         let body = serde_json::json!({
             "ref": format!("refs/heads/{}", branch_name),
             "sha": from_sha
         });
 
+        let _permit = self.limiter.acquire().await;
         self.client
             .post(&url)
             .json(&body)
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Point an existing branch at `sha`, discarding its commits
+    pub async fn reset_branch(&self, branch: &str, sha: &str) -> Result<()> {
+        let url = self.api_url(&format!("/git/refs/heads/{}", branch));
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .patch(&url)
+            .json(&serde_json::json!({ "sha": sha, "force": true }))
+            .send()
+            .await?
+            .error_for_status()?;
 
         Ok(())
     }
 
     /// Get default branch SHA
     pub async fn get_default_branch_sha(&self) -> Result<String> {
-        let url = self.api_url("/git/refs/heads/main");
+        let branch = self.get_default_branch().await?;
+        self.get_branch_sha(&branch).await
+    }
+
+    /// Get the head commit SHA of a branch
+    pub async fn get_branch_sha(&self, branch: &str) -> Result<String> {
+        let url = self.api_url(&format!("/git/refs/heads/{}", branch));
 
         #[derive(Deserialize)]
         struct RefResponse {
@@ -360,22 +941,212 @@ impl GitHubClient {
             sha: String,
         }
 
+        let _permit = self.limiter.acquire().await;
         let response: RefResponse = self.client
             .get(&url)
             .send()
             .await?
+            .error_for_status()?
             .json()
             .await?;
 
         Ok(response.object.sha)
     }
 
+    /// Whether a branch exists
+    pub async fn branch_exists(&self, branch: &str) -> Result<bool> {
+        let url = self.api_url(&format!("/git/refs/heads/{}", branch));
+
+        let _permit = self.limiter.acquire().await;
+        let response = self.client.get(&url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+        Ok(true)
+    }
+
+    /// Delete a branch
+    pub async fn delete_branch(&self, branch: &str) -> Result<()> {
+        let url = self.api_url(&format!("/git/refs/heads/{}", branch));
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .delete(&url)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// List open pull requests (all pages)
+    pub async fn list_open_pull_requests(&self) -> Result<Vec<PullRequestInfo>> {
+        self.get_all_pages("/pulls", &[("state", "open")]).await
+    }
+
+    /// Commits of a pull request, oldest first
+    pub async fn get_pull_request_commits(&self, pr_number: u64) -> Result<Vec<PullRequestCommit>> {
+        self.get_all_pages(&format!("/pulls/{}/commits", pr_number), &[]).await
+    }
+
+    /// Close a pull request without merging
+    pub async fn close_pull_request(&self, pr_number: u64) -> Result<()> {
+        let url = self.api_url(&format!("/pulls/{}", pr_number));
+
+        let _permit = self.limiter.acquire().await;
+        self.client
+            .patch(&url)
+            .json(&serde_json::json!({ "state": "closed" }))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Create or update a file on a branch with a single commit
+    pub async fn put_file(&self, path: &str, content: &str, message: &str, branch: &str) -> Result<()> {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let url = self.api_url(&format!("/contents/{}", path));
+
+        #[derive(Deserialize)]
+        struct ContentResponse {
+            sha: String,
+        }
+
+        let _permit = self.limiter.acquire().await;
+
+        // Updating an existing file requires its blob SHA
+        let existing = self.client
+            .get(&url)
+            .query(&[("ref", branch)])
+            .send()
+            .await?;
+        let sha = if existing.status().is_success() {
+            Some(existing.json::<ContentResponse>().await?.sha)
+        } else {
+            None
+        };
+
+        let mut body = serde_json::json!({
+            "message": message,
+            "content": general_purpose::STANDARD.encode(content),
+            "branch": branch,
+        });
+        if let Some(sha) = sha {
+            body["sha"] = serde_json::Value::String(sha);
+        }
+
+        self.client
+            .put(&url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
     fn clone_minimal(&self) -> Self {
         Self {
             client: self.client.clone(),
+            base_url: self.base_url.clone(),
             repo: self.repo.clone(),
             owner: self.owner.clone(),
-            semaphore: self.semaphore.clone(),
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cassette::{Cassette, CassetteServer};
+    use serde_json::json;
+
+    fn runs(ids: std::ops::Range<u64>, total: u32) -> serde_json::Value {
+        let runs: Vec<_> = ids.map(|id| json!({
+            "id": id, "name": "CI", "status": "completed", "conclusion": "failure",
+            "html_url": format!("https://github.com/acme/widgets/actions/runs/{}", id),
+            "created_at": "2025-03-03T10:00:00Z", "updated_at": "2025-03-03T10:00:00Z",
+            "run_attempt": 1, "workflow_id": 11, "head_branch": "main", "head_sha": "abc123"
+        })).collect();
+        json!({ "total_count": total, "workflow_runs": runs })
+    }
+
+    #[tokio::test]
+    async fn test_run_stream_pages_with_server_side_filters() {
+        let query = "branch=main&status=failure&created=%3E%3D2025-03-01T00%3A00%3A00Z";
+        let cassette = Cassette::default()
+            .with("GET", &format!("/repos/acme/widgets/actions/workflows/ci.yml/runs?{}&per_page=100&page=1", query), 200, runs(0..100, 130))
+            .with("GET", &format!("/repos/acme/widgets/actions/workflows/ci.yml/runs?{}&per_page=100&page=2", query), 200, runs(100..130, 130))
+            .with("GET", &format!("/repos/acme/widgets/actions/workflows/ci.yml/runs?{}&per_page=100&page=1", query), 200, runs(0..100, 130));
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("test-token", "acme/widgets", 2).with_base_url(server.url());
+
+        let since = DateTime::parse_from_rfc3339("2025-03-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let filter = RunFilter {
+            workflow: Some("ci.yml".to_string()),
+            branch: Some("main".to_string()),
+            status: Some("failure".to_string()),
+            ..Default::default()
         }
+        .created_since(since);
+
+        let all = client.collect_runs(&filter).await.unwrap();
+        assert_eq!(all.len(), 130);
+        assert_eq!(all.last().unwrap().id, 129);
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+        assert!(server.requests()[0].ends_with(&format!("?{}&per_page=100&page=1", query)));
+
+        // A limit within the first page stops after it
+        let first: Vec<WorkflowRun> = client.workflow_runs(&filter.clone().with_limit(100)).try_collect().await.unwrap();
+        assert_eq!(first.len(), 100);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_created_since_narrows_an_explicit_range() {
+        let since = DateTime::parse_from_rfc3339("2025-03-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let created = |range: &str| RunFilter { created: Some(range.to_string()), ..Default::default() }.created_since(since).created.unwrap();
+
+        assert_eq!(RunFilter::default().created_since(since).created.unwrap(), ">=2025-03-01T00:00:00Z");
+        // An earlier lower bound is raised, a later one is kept
+        assert_eq!(created(">=2025-02-01"), ">=2025-03-01T00:00:00Z");
+        assert_eq!(created(">2025-03-10"), ">=2025-03-11T00:00:00Z");
+        // Upper bounds survive as a range
+        assert_eq!(created("<=2025-03-07"), "2025-03-01T00:00:00Z..2025-03-07T23:59:59Z");
+        assert_eq!(created("<2025-03-07"), "2025-03-01T00:00:00Z..2025-03-06T23:59:59Z");
+        assert_eq!(created("2025-02-01..2025-03-05"), "2025-03-01T00:00:00Z..2025-03-05T23:59:59Z");
+        assert_eq!(created("2025-03-05..*"), ">=2025-03-05T00:00:00Z");
+        assert_eq!(created("2025-03-02"), "2025-03-02T00:00:00Z..2025-03-02T23:59:59Z");
+        // A range entirely before the window stays empty instead of widening
+        assert_eq!(created("2025-01-01..2025-01-31"), "2025-03-01T00:00:00Z..2025-01-31T23:59:59Z");
+        // Unparseable ranges are passed through unchanged
+        assert_eq!(created("last-week"), "last-week");
+    }
+
+    #[tokio::test]
+    async fn test_jobs_of_all_attempts_follow_pages() {
+        let jobs = |ids: std::ops::Range<u64>| {
+            let jobs: Vec<_> = ids.map(|id| json!({
+                "id": id, "name": format!("job {}", id), "status": "completed", "conclusion": "success",
+                "started_at": null, "completed_at": null, "steps": null
+            })).collect();
+            json!({ "total_count": 120, "jobs": jobs })
+        };
+        let path = "/repos/acme/widgets/actions/runs/7/jobs?filter=all&per_page=100";
+        let cassette = Cassette::default()
+            .with("GET", &format!("{}&page=1", path), 200, jobs(0..100))
+            .with("GET", &format!("{}&page=2", path), 200, jobs(100..120));
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("test-token", "acme/widgets", 2).with_base_url(server.url());
+
+        let all = client.get_jobs_all_attempts(7).await.unwrap();
+        assert_eq!(all.len(), 120);
+        assert_eq!(server.requests().len(), 2);
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
     }
 }
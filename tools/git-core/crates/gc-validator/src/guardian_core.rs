@@ -17,6 +17,9 @@
 //!   - CI passes: +40
 //!   - Approved reviews: +40
 //!
+//! Required:
+//!   - Approval from a code owner of every owned path (if the base branch has CODEOWNERS)
+//!
 //! Bonuses:
 //!   - Has tests: +10
 //!   - Single scope: +10
@@ -35,7 +38,7 @@
 //! ## Example
 //!
 //! ```rust,no_run
-//! use workflow_orchestrator::guardian_core::GuardianCore;
+//! use gc_validator::guardian_core::GuardianCore;
 //! use octocrab::Octocrab;
 //!
 //! #[tokio::main]
@@ -50,16 +53,35 @@
 //! }
 //! ```
 
-use anyhow::Result;
-use octocrab::{Octocrab, models::pulls::ReviewState, params::repos::Commitish};
+use crate::codeowners::{CodeOwners, CODEOWNERS_PATHS};
+use crate::guardian_policy::{CheckState, CheckSummary, GuardianPolicy, MergeStrategy};
+use crate::guardian_report::{DecisionReport, Signal};
+use crate::guardian_watch::AUTO_MERGED_LABEL;
+use anyhow::{Context, Result};
+use octocrab::{Octocrab, Page, models::{CombinedStatus, pulls::{Review, ReviewState}, repos::DiffEntry}, params::repos::Commitish};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 const DEFAULT_THRESHOLD: u8 = 70;
 
+/// Upper bound of the confidence score, which reads as a percentage. The
+/// bonuses can add up to 105 (CI 40, reviews 40, tests 15, scope 10), so a
+/// small, fully green PR is capped here rather than reported above 100%.
+pub const MAX_CONFIDENCE: u8 = 100;
+
+/// Items per request on paginated endpoints (the API maximum)
+const PAGE_SIZE: u8 = 100;
+
+/// Process exit codes of a decision, shared by `gc guardian` and
+/// `workflow-orchestrator guardian --ci-mode` (1 is left to runtime errors)
+pub const EXIT_AUTO_MERGE: i32 = 0;
+pub const EXIT_ESCALATE: i32 = 2;
+pub const EXIT_BLOCKED: i32 = 3;
+
 /// Decision outcome from PR evaluation
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Decision {
@@ -83,6 +105,15 @@ impl Decision {
             }
         }
     }
+
+    /// Exit code for CI: 0 auto-merge, 2 escalate, 3 blocked
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Decision::AutoMerge { .. } => EXIT_AUTO_MERGE,
+            Decision::Escalate { .. } => EXIT_ESCALATE,
+            Decision::Blocked { .. } => EXIT_BLOCKED,
+        }
+    }
 }
 
 /// PR data aggregated from GitHub API
@@ -90,13 +121,22 @@ impl Decision {
 pub struct PrData {
     pub number: u64,
     pub labels: Vec<String>,
+    /// Latest verdict of each reviewer (comments excluded)
     pub reviews: Vec<ReviewState>,
+    /// Owner handles that approved: `@login` plus `@org/team` for their teams
+    pub approvers: Vec<String>,
     pub additions: u32,
     pub deletions: u32,
     pub changed_files: u32,
     pub files: Vec<String>,
-    pub checks_passed: bool,
+    pub checks: CheckSummary,
+    pub draft: bool,
     pub head_ref: String,
+    /// Head commit the evaluation is pinned to
+    pub head_sha: String,
+    pub base_ref: String,
+    /// CODEOWNERS in force on the base branch, when read from the API
+    pub codeowners: Option<CodeOwners>,
 }
 
 /// Risk map configuration loaded from .gitcore/risk-map.json
@@ -121,10 +161,16 @@ impl RiskMap {
 
 /// Guardian Core engine
 pub struct GuardianCore {
-    github: Octocrab,
+    /// `None` for local evaluation, which never calls the API
+    github: Option<Octocrab>,
     owner: String,
     repo: String,
     risk_map: Option<RiskMap>,
+    /// Read CODEOWNERS from each PR's base branch
+    read_codeowners: bool,
+    policy: GuardianPolicy,
+    /// Poll pending checks until they finish: (timeout, interval)
+    wait: Option<(Duration, Duration)>,
     threshold: u8,
 }
 
@@ -132,14 +178,42 @@ impl GuardianCore {
     /// Create new Guardian instance
     pub fn new(github: Octocrab, owner: String, repo: String) -> Self {
         Self {
-            github,
+            github: Some(github),
             owner,
             repo,
+            ..Self::local()
+        }
+    }
+
+    /// Guardian without a GitHub client, for scoring local [`PrData`] with [`Self::evaluate`]
+    pub fn local() -> Self {
+        Self {
+            github: None,
+            owner: String::new(),
+            repo: String::new(),
             risk_map: None,
+            read_codeowners: false,
+            policy: GuardianPolicy::default(),
+            wait: None,
             threshold: DEFAULT_THRESHOLD,
         }
     }
 
+    /// Create a Guardian instance with its own GitHub client (anonymous without a token)
+    pub fn from_token(token: Option<&str>, owner: &str, repo: &str) -> Result<Self> {
+        Self::from_token_at(token, &crate::github::api_base_url(), owner, repo)
+    }
+
+    /// Like `from_token`, against another API root (GHES, a stub server or a cassette replay)
+    pub fn from_token_at(token: Option<&str>, base_url: &str, owner: &str, repo: &str) -> Result<Self> {
+        let builder = Octocrab::builder().base_uri(base_url)?;
+        let github = match token {
+            Some(token) => builder.personal_token(token.to_string()).build()?,
+            None => builder.build()?,
+        };
+        Ok(Self::new(github, owner.to_string(), repo.to_string()))
+    }
+
     /// Set confidence threshold (default: 70)
     pub fn with_threshold(mut self, threshold: u8) -> Self {
         self.threshold = threshold;
@@ -153,21 +227,112 @@ impl GuardianCore {
         Ok(self)
     }
 
+    /// Set merge method, required checks and neutral handling
+    pub fn with_policy(mut self, policy: GuardianPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Override the merge method from the policy and repository settings
+    pub fn with_merge_method(mut self, strategy: MergeStrategy) -> Self {
+        self.policy.merge_method = Some(strategy);
+        self
+    }
+
+    /// Load the repository's Guardian configuration, skipping missing files:
+    /// risk map and Guardian policy from the working tree. CODEOWNERS is read
+    /// from each PR's base branch, so a PR cannot approve its own ownership change.
+    pub fn with_repo_config(mut self, risk_map: &str, policy: &str) -> Result<Self> {
+        if Path::new(risk_map).exists() {
+            self = self.with_risk_map(risk_map)?;
+        } else {
+            info!("⚠️  Risk map not found: {}, skipping risk analysis", risk_map);
+        }
+
+        if Path::new(policy).exists() {
+            self = self.with_policy(GuardianPolicy::from_file(policy)?);
+        }
+
+        self.read_codeowners = true;
+        Ok(self)
+    }
+
+    /// The GitHub client, an error for a local Guardian
+    fn github(&self) -> Result<&Octocrab> {
+        self.github.as_ref().context("Guardian has no GitHub client (local evaluation only)")
+    }
+
+    /// Policy in force (merge method, required checks, neutral handling)
+    pub fn policy(&self) -> &GuardianPolicy {
+        &self.policy
+    }
+
+    /// Wait for pending checks, polling every `interval` up to `timeout`
+    pub fn with_wait(mut self, timeout: Duration, interval: Duration) -> Self {
+        self.wait = Some((timeout, interval));
+        self
+    }
+
     /// Main evaluation entry point
     pub async fn evaluate_pr(&self, pr_number: u64, dry_run: bool) -> Result<Decision> {
+        Ok(self.evaluate_pr_report(pr_number, dry_run).await?.decision)
+    }
+
+    /// Evaluate a PR and return the full decision report
+    pub async fn evaluate_pr_report(&self, pr_number: u64, dry_run: bool) -> Result<DecisionReport> {
         info!("🛡️ Evaluating PR #{}", pr_number);
 
-        // Fetch all PR data in parallel
-        let pr_data = self.fetch_pr_data(pr_number).await?;
+        let started = Instant::now();
+        let mut pr_data = self.fetch_pr_data(pr_number).await?;
 
-        // Check blocking labels
-        if let Some(blocker) = self.check_blockers(&pr_data.labels) {
-            warn!("⛔ PR blocked: {}", blocker);
-            return Ok(Decision::Blocked { reason: blocker });
+        // Optionally wait for running checks instead of escalating right away
+        if let Some((timeout, interval)) = self.wait {
+            while pr_data.checks.is_pending() && started.elapsed() < timeout {
+                info!(
+                    "⏳ Waiting for {} pending check(s): {}",
+                    pr_data.checks.pending.len(),
+                    pr_data.checks.pending.join(", ")
+                );
+                tokio::time::sleep(interval).await;
+                pr_data = self.fetch_pr_data(pr_number).await?;
+            }
+        }
+
+        let report = self.evaluate(&pr_data);
+
+        // Execute decision
+        if !dry_run {
+            self.execute_decision(&pr_data, &report).await?;
+        }
+
+        Ok(report)
+    }
+
+    /// Score already-fetched PR data without touching the GitHub API
+    pub fn evaluate(&self, pr_data: &PrData) -> DecisionReport {
+        let mut signals = Vec::new();
+
+        // Check blocking labels and draft state
+        let mut blocking_reasons = self.check_blockers(&pr_data.labels);
+        if pr_data.draft {
+            blocking_reasons.push("PR is a draft".to_string());
+        }
+        if !blocking_reasons.is_empty() {
+            let reason = blocking_reasons.join("; ");
+            warn!("⛔ PR blocked: {}", reason);
+            return DecisionReport {
+                pr_number: pr_data.number,
+                decision: Decision::Blocked { reason },
+                confidence: 0,
+                threshold: self.threshold,
+                signals,
+                blocking_reasons,
+                missing_owner_approvals: Vec::new(),
+            };
         }
 
         // Calculate confidence score
-        let ci_ok = pr_data.checks_passed;
+        let checks = &pr_data.checks;
         let reviews_ok = self.check_reviews(&pr_data.reviews);
         let risk_score = self.calculate_risk(&pr_data.files);
         let size_penalty = self.calculate_size_penalty(pr_data.additions, pr_data.deletions);
@@ -175,118 +340,334 @@ impl GuardianCore {
         let mut confidence: u8 = 0;
 
         // CI checks (required)
-        if ci_ok {
-            confidence += 40;
-            debug!("✅ CI passed: +40 confidence");
-        } else {
+        if !checks.failed.is_empty() {
             warn!("❌ CI failed: 0 confidence");
-            return Ok(Decision::Escalate {
-                reason: "CI checks failed".to_string(),
+            signals.push(Signal::new("ci", format!("{} failed", checks.failed.len()), 0));
+            return self.report(pr_data, signals, Decision::Escalate {
+                reason: format!("CI checks failed: {}", checks.failed.join(", ")),
+                confidence: 0,
+            });
+        }
+        if !checks.is_green() {
+            warn!("⏳ CI pending: 0 confidence");
+            signals.push(Signal::new("ci", format!("{} pending", checks.pending.len()), 0));
+            return self.report(pr_data, signals, Decision::Escalate {
+                reason: format!("CI checks pending: {}", checks.pending.join(", ")),
                 confidence: 0,
             });
         }
 
+        confidence += 40;
+        let ci_value = if checks.neutral.is_empty() {
+            "passed".to_string()
+        } else {
+            format!("passed ({} neutral)", checks.neutral.len())
+        };
+        signals.push(Signal::new("ci", ci_value, 40));
+        debug!("✅ CI passed: +40 confidence");
+
         // Reviews (required)
+        let approvals = pr_data.reviews.iter().filter(|r| **r == ReviewState::Approved).count();
         if reviews_ok {
             confidence += 40;
+            signals.push(Signal::new("reviews", format!("{} approval(s)", approvals), 40));
             debug!("✅ Reviews approved: +40 confidence");
         } else {
             warn!("❌ No approvals or changes requested");
-            return Ok(Decision::Escalate {
+            signals.push(Signal::new("reviews", "not approved", 0));
+            return self.report(pr_data, signals, Decision::Escalate {
                 reason: "No approved reviews".to_string(),
                 confidence,
             });
         }
 
+        // Code owners (required when CODEOWNERS is present)
+        if let Some(codeowners) = &pr_data.codeowners {
+            let gaps = codeowners.missing_approvals(&pr_data.files, &pr_data.approvers);
+            if !gaps.is_empty() {
+                let missing: Vec<String> = gaps
+                    .iter()
+                    .map(|g| format!("{} ({})", g.pattern, g.owners.join(", ")))
+                    .collect();
+                warn!("❌ Missing code owner approval: {}", missing.join("; "));
+                signals.push(Signal::new("codeowners", format!("{} path(s) unapproved", gaps.len()), 0));
+                let mut report = self.report(pr_data, signals, Decision::Escalate {
+                    reason: format!("Missing code owner approval: {}", missing.join("; ")),
+                    confidence,
+                });
+                report.missing_owner_approvals = gaps;
+                return report;
+            }
+            signals.push(Signal::new("codeowners", "approved", 0));
+            debug!("✅ Code owners approved");
+        }
+
         // Risk analysis (penalty)
         let risk_penalty = (risk_score / 10).min(10); // Max -10
         confidence = confidence.saturating_sub(risk_penalty);
+        signals.push(Signal::new("risk", format!("score {}", risk_score), -(risk_penalty as i16)));
         debug!("📊 Risk score: {} (penalty: -{})", risk_score, risk_penalty);
 
         // Size penalty
         confidence = confidence.saturating_sub(size_penalty);
+        signals.push(Signal::new(
+            "size",
+            format!("{} lines", pr_data.additions + pr_data.deletions),
+            -(size_penalty as i16),
+        ));
         debug!("📏 Size penalty: -{}", size_penalty);
 
         // Tests bonus
         if self.has_tests(&pr_data.files) {
-            confidence = confidence.saturating_add(15).min(100);
+            confidence = confidence.saturating_add(15).min(MAX_CONFIDENCE);
+            signals.push(Signal::new("tests", "included", 15));
             debug!("🧪 Tests included: +15 confidence");
+        } else {
+            signals.push(Signal::new("tests", "none", 0));
         }
 
         // Single scope bonus
         if self.is_single_scope(&pr_data.files) {
-            confidence = confidence.saturating_add(10).min(100);
+            confidence = confidence.saturating_add(10).min(MAX_CONFIDENCE);
+            signals.push(Signal::new("scope", "single", 10));
             debug!("🎯 Single scope: +10 confidence");
+        } else {
+            signals.push(Signal::new("scope", "multiple", 0));
         }
 
         info!("📊 Final confidence: {}/{}", confidence, self.threshold);
 
         let decision = Decision::from_confidence(confidence, self.threshold, None);
+        self.report(pr_data, signals, decision)
+    }
 
-        // Execute decision
-        if !dry_run {
-            self.execute_decision(pr_number, &decision).await?;
-        }
+    fn report(&self, pr_data: &PrData, signals: Vec<Signal>, decision: Decision) -> DecisionReport {
+        let confidence = match &decision {
+            Decision::AutoMerge { confidence } | Decision::Escalate { confidence, .. } => *confidence,
+            Decision::Blocked { .. } => 0,
+        };
 
-        Ok(decision)
+        DecisionReport {
+            pr_number: pr_data.number,
+            decision,
+            confidence,
+            threshold: self.threshold,
+            signals,
+            blocking_reasons: Vec::new(),
+            missing_owner_approvals: Vec::new(),
+        }
     }
 
     /// Fetch PR data from GitHub API
     async fn fetch_pr_data(&self, pr_number: u64) -> Result<PrData> {
-        let pulls = self.github.pulls(&self.owner, &self.repo);
+        let pulls = self.github()?.pulls(&self.owner, &self.repo);
         let pr = pulls.get(pr_number).await?;
 
-        // Fetch reviews
-        let reviews = pulls.list_reviews(pr_number).send().await?;
-        let review_states: Vec<ReviewState> = reviews
-            .items
-            .into_iter()
-            .filter_map(|r| r.state)
+        // Fetch reviews and files (every page: large PRs exceed the default 30)
+        let reviews: Vec<Review> = self.all_pages(&format!("pulls/{}/reviews", pr_number)).await?;
+        let verdicts = latest_verdicts(
+            reviews.iter().filter_map(|r| Some((r.user.as_ref()?.login.as_str(), r.state?))),
+        );
+        let review_states: Vec<ReviewState> = verdicts.values().copied().collect();
+        let approving_logins: Vec<String> = verdicts
+            .iter()
+            .filter(|(_, state)| **state == ReviewState::Approved)
+            .map(|(login, _)| login.clone())
             .collect();
 
-        // Fetch files
-        let files_page = pulls.list_files(pr_number).await?;
-        let files: Vec<String> = files_page
-            .items
+        let files: Vec<String> = self
+            .all_pages::<DiffEntry>(&format!("pulls/{}/files", pr_number))
+            .await?
             .into_iter()
             .map(|f| f.filename)
             .collect();
 
-        // Fetch checks
-        let head_ref = pr.head.ref_field.clone();
-        let checks = self
-            .github
-            .checks(&self.owner, &self.repo)
-            .list_check_runs_for_git_ref(Commitish(head_ref))
-            .send()
-            .await?;
-
-        let checks_passed = checks.check_runs.iter().all(|check| {
-            check.conclusion.as_ref().map_or(false, |c| c.as_str() == "success" || c.as_str() == "skipped" || c.as_str() == "neutral")
-        });
+        // Checks are pinned to the head SHA, not the branch name
+        let head_sha = pr.head.sha.clone();
+        let base_ref = pr.base.ref_field.clone();
+
+        let codeowners = match self.read_codeowners {
+            true => self.codeowners_at(&base_ref).await?,
+            false => None,
+        };
+        let approvers = self.resolve_approvers(codeowners.as_ref(), &approving_logins, &files).await;
+
+        let checks = self.fetch_checks(&head_sha, &base_ref).await?;
 
         Ok(PrData {
             number: pr_number,
             labels: pr.labels.unwrap_or_default().into_iter().map(|l| l.name).collect(),
             reviews: review_states,
+            approvers,
             additions: pr.additions.unwrap_or(0) as u32,
             deletions: pr.deletions.unwrap_or(0) as u32,
             changed_files: pr.changed_files.unwrap_or(0) as u32,
             files,
-            checks_passed,
+            checks,
+            draft: pr.draft.unwrap_or(false),
             head_ref: pr.head.ref_field,
+            head_sha,
+            base_ref,
+            codeowners,
         })
     }
 
+    /// Every page of a PR sub-resource (`pulls/{n}/files`, `pulls/{n}/reviews`)
+    async fn all_pages<T: serde::de::DeserializeOwned>(&self, resource: &str) -> Result<Vec<T>> {
+        let route = format!("/repos/{}/{}/{}", self.owner, self.repo, resource);
+        let first_page: Page<T> = self.github()?.get(route, Some(&[("per_page", PAGE_SIZE)])).await?;
+        Ok(self.github()?.all_pages(first_page).await?)
+    }
+
+    /// CODEOWNERS as it exists on `base_ref`, `None` when the branch has none
+    async fn codeowners_at(&self, base_ref: &str) -> Result<Option<CodeOwners>> {
+        let repos = self.github()?.repos(&self.owner, &self.repo);
+        for candidate in CODEOWNERS_PATHS {
+            match repos.get_content().path(candidate).r#ref(base_ref).send().await {
+                Ok(content) => {
+                    let Some(text) = content.items.first().and_then(|item| item.decoded_content()) else {
+                        continue;
+                    };
+                    let codeowners = CodeOwners::parse(&text);
+                    info!("✅ CODEOWNERS loaded from {}:{} ({} rules)", base_ref, candidate, codeowners.rules.len());
+                    return Ok(Some(codeowners));
+                }
+                Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        debug!("No CODEOWNERS on {}", base_ref);
+        Ok(None)
+    }
+
+    /// Collect check runs and commit statuses for a commit
+    async fn fetch_checks(&self, head_sha: &str, base_ref: &str) -> Result<CheckSummary> {
+        let neutral_ok = self.policy.neutral_is_success;
+        let mut summary = CheckSummary::default();
+
+        // Both endpoints wrap their list in an object, so Page<T> does not apply
+        for page in 1u32.. {
+            let check_runs = self
+                .github()?
+                .checks(&self.owner, &self.repo)
+                .list_check_runs_for_git_ref(Commitish(head_sha.to_string()))
+                .per_page(PAGE_SIZE)
+                .page(page)
+                .send()
+                .await?;
+            for run in &check_runs.check_runs {
+                summary.record(&run.name, CheckState::from_conclusion(run.conclusion.as_deref()), neutral_ok);
+            }
+            if is_last_page(page, check_runs.check_runs.len(), check_runs.total_count as usize) {
+                break;
+            }
+        }
+
+        let route = format!("/repos/{}/{}/commits/{}/status", self.owner, self.repo, head_sha);
+        for page in 1u32.. {
+            let combined: CombinedStatus = self
+                .github()?
+                .get(&route, Some(&[("per_page", PAGE_SIZE as u32), ("page", page)]))
+                .await?;
+            for status in &combined.statuses {
+                let name = status.context.clone().unwrap_or_else(|| "status".to_string());
+                summary.record(&name, CheckState::from(status.state), neutral_ok);
+            }
+            if is_last_page(page, combined.statuses.len(), combined.total_count as usize) {
+                break;
+            }
+        }
+
+        let mut required = self.policy.required_checks.clone();
+        required.extend(self.required_status_checks(base_ref).await);
+        required.sort();
+        required.dedup();
+        summary.require(&required);
+
+        Ok(summary)
+    }
+
+    /// Required status checks from the base branch protection (best effort)
+    async fn required_status_checks(&self, base_ref: &str) -> Vec<String> {
+        let Some(github) = &self.github else {
+            return Vec::new();
+        };
+        let route = format!("/repos/{}/{}/branches/{}", self.owner, self.repo, base_ref);
+
+        match github.get::<serde_json::Value, _, ()>(route, None).await {
+            Ok(branch) => branch["protection"]["required_status_checks"]["contexts"]
+                .as_array()
+                .map(|contexts| {
+                    contexts
+                        .iter()
+                        .filter_map(|c| c.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Err(e) => {
+                debug!("Could not read branch protection for {}: {}", base_ref, e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Merge method from policy, falling back to repository settings
+    async fn merge_strategy(&self) -> Result<MergeStrategy> {
+        if let Some(method) = self.policy.merge_method {
+            return Ok(method);
+        }
+
+        let repo = self.github()?.repos(&self.owner, &self.repo).get().await?;
+        Ok(MergeStrategy::from_repo_settings(
+            repo.allow_squash_merge,
+            repo.allow_merge_commit,
+            repo.allow_rebase_merge,
+        ))
+    }
+
+    /// Expand approving logins with the CODEOWNERS teams they belong to
+    async fn resolve_approvers(&self, codeowners: Option<&CodeOwners>, logins: &[String], files: &[String]) -> Vec<String> {
+        let mut approvers: Vec<String> = logins.iter().map(|l| format!("@{}", l)).collect();
+
+        let (Some(codeowners), Some(github)) = (codeowners, &self.github) else {
+            return approvers;
+        };
+
+        for team in codeowners.teams_for(files) {
+            let Some((org, slug)) = team.trim_start_matches('@').split_once('/') else {
+                continue;
+            };
+
+            let members = match github.teams(org).members(slug).per_page(PAGE_SIZE).send().await {
+                Ok(first_page) => github.all_pages(first_page).await,
+                Err(e) => Err(e),
+            };
+
+            match members {
+                Ok(members) => {
+                    if members.iter().any(|m| logins.iter().any(|l| l.eq_ignore_ascii_case(&m.login))) {
+                        approvers.push(team.clone());
+                    }
+                }
+                Err(e) => warn!("⚠️  Could not resolve members of {}: {}", team, e),
+            }
+        }
+
+        approvers
+    }
+
     /// Check for blocking labels
-    fn check_blockers(&self, labels: &[String]) -> Option<String> {
+    fn check_blockers(&self, labels: &[String]) -> Vec<String> {
+        let mut blockers = Vec::new();
         if labels.iter().any(|l| l == "high-stakes") {
-            return Some("high-stakes label detected".to_string());
+            blockers.push("high-stakes label detected".to_string());
         }
         if labels.iter().any(|l| l == "needs-human") {
-            return Some("needs-human label detected".to_string());
+            blockers.push("needs-human label detected".to_string());
         }
-        None
+        blockers
     }
 
     /// Check review status
@@ -370,69 +751,83 @@ impl GuardianCore {
     }
 
     /// Execute the decision (merge or escalate)
-    async fn execute_decision(&self, pr_number: u64, decision: &Decision) -> Result<()> {
-        match decision {
+    async fn execute_decision(&self, pr_data: &PrData, report: &DecisionReport) -> Result<()> {
+        let pr_number = report.pr_number;
+
+        // Post (or refresh) the explanation before acting on it
+        self.upsert_report_comment(pr_number, &report.to_markdown()).await?;
+
+        match &report.decision {
             Decision::AutoMerge { confidence } => {
                 info!("✅ Auto-merging PR #{} (confidence: {})", pr_number, confidence);
 
-                // Add comment before merging
-                let comment = format!(
-                    "🤖 **Guardian Agent**: Auto-merge approved (confidence: {}%)\n\n\
-                     All checks passed, reviews approved, and confidence threshold met.",
-                    confidence
-                );
+                // Fail safe if new commits arrived while we were evaluating
+                let pulls = self.github()?.pulls(&self.owner, &self.repo);
+                let current = pulls.get(pr_number).await?;
+                if current.head.sha != pr_data.head_sha {
+                    anyhow::bail!(
+                        "PR #{} head changed during evaluation ({} -> {}), not merging",
+                        pr_number,
+                        pr_data.head_sha,
+                        current.head.sha
+                    );
+                }
 
-                self.github
-                    .issues(&self.owner, &self.repo)
-                    .create_comment(pr_number, comment)
-                    .await?;
+                let strategy = self.merge_strategy().await?;
+                info!("🔀 Merge method: {:?}", strategy);
 
-                // Merge the PR
-                self.github
-                    .pulls(&self.owner, &self.repo)
+                // Merge the PR, pinned to the evaluated head SHA
+                pulls
                     .merge(pr_number)
-                    .method(octocrab::params::pulls::MergeMethod::Squash)
+                    .method(strategy)
+                    .sha(pr_data.head_sha.clone())
                     .send()
                     .await?;
 
                 info!("✅ PR #{} merged successfully", pr_number);
-            }
-            Decision::Escalate { reason, confidence } => {
-                info!("⚠️ Escalating PR #{}: {} (confidence: {})", pr_number, reason, confidence);
-
-                let comment = format!(
-                    "🤖 **Guardian Agent**: Manual review required\n\n\
-                     **Reason:** {}\n\
-                     **Confidence:** {}%\n\n\
-                     A human reviewer must approve this PR for merge.",
-                    reason, confidence
-                );
 
-                self.github
+                // Hand the merge over to the post-merge watchdog
+                self.github()?
                     .issues(&self.owner, &self.repo)
-                    .create_comment(pr_number, comment)
+                    .add_labels(pr_number, &[String::from(AUTO_MERGED_LABEL)])
                     .await?;
+            }
+            Decision::Escalate { reason, confidence } => {
+                info!("⚠️ Escalating PR #{}: {} (confidence: {})", pr_number, reason, confidence);
 
                 // Add needs-human label
-                self.github
+                self.github()?
                     .issues(&self.owner, &self.repo)
                     .add_labels(pr_number, &[String::from("needs-human")])
                     .await?;
             }
             Decision::Blocked { reason } => {
                 warn!("⛔ PR #{} blocked: {}", pr_number, reason);
+            }
+        }
 
-                let comment = format!(
-                    "🤖 **Guardian Agent**: PR blocked\n\n\
-                     **Reason:** {}\n\n\
-                     This PR cannot be auto-merged. Please review the blocking conditions.",
-                    reason
-                );
+        Ok(())
+    }
 
-                self.github
-                    .issues(&self.owner, &self.repo)
-                    .create_comment(pr_number, comment)
-                    .await?;
+    /// Update the existing Guardian comment (found by marker) or create one
+    async fn upsert_report_comment(&self, pr_number: u64, body: &str) -> Result<()> {
+        let issues = self.github()?.issues(&self.owner, &self.repo);
+
+        let first_page = issues.list_comments(pr_number).per_page(100).send().await?;
+        let comments = self.github()?.all_pages(first_page).await?;
+
+        let existing = comments.into_iter().find(|c| {
+            c.body.as_deref().is_some_and(DecisionReport::is_report_comment)
+        });
+
+        match existing {
+            Some(comment) => {
+                debug!("📝 Updating Guardian comment {} on PR #{}", comment.id, pr_number);
+                issues.update_comment(comment.id, body).await?;
+            }
+            None => {
+                debug!("📝 Creating Guardian comment on PR #{}", pr_number);
+                issues.create_comment(pr_number, body).await?;
             }
         }
 
@@ -440,6 +835,28 @@ impl GuardianCore {
     }
 }
 
+/// Whether `page` (1-based) holding `len` items is the last of `total`
+fn is_last_page(page: u32, len: usize, total: usize) -> bool {
+    len < PAGE_SIZE as usize || page as usize * PAGE_SIZE as usize >= total
+}
+
+/// Most recent verdict of each reviewer, by login
+///
+/// Comments do not change a reviewer's verdict, so a reviewer who requested
+/// changes and later approved counts only as an approval.
+fn latest_verdicts<'a>(reviews: impl IntoIterator<Item = (&'a str, ReviewState)>) -> BTreeMap<String, ReviewState> {
+    let mut latest = BTreeMap::new();
+
+    for (login, state) in reviews {
+        if state == ReviewState::Commented {
+            continue;
+        }
+        latest.insert(login.to_string(), state);
+    }
+
+    latest
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -456,6 +873,13 @@ mod tests {
         assert!(matches!(decision, Decision::Blocked { .. }));
     }
 
+    #[test]
+    fn test_exit_codes_are_distinct_from_errors() {
+        assert_eq!(Decision::AutoMerge { confidence: 90 }.exit_code(), 0);
+        assert_eq!(Decision::Escalate { reason: "x".into(), confidence: 50 }.exit_code(), 2);
+        assert_eq!(Decision::Blocked { reason: "x".into() }.exit_code(), 3);
+    }
+
     #[tokio::test]
     async fn test_size_penalty() {
         let github = Octocrab::builder().build().unwrap();
@@ -490,4 +914,215 @@ mod tests {
         let files = vec!["src/main.rs".to_string(), "tests/test.rs".to_string()];
         assert!(!guardian.is_single_scope(&files));
     }
+
+    fn sample_pr(labels: Vec<&str>, reviews: Vec<ReviewState>, checks_passed: bool) -> PrData {
+        let mut checks = CheckSummary::default();
+        let state = if checks_passed { CheckState::Success } else { CheckState::Failed };
+        checks.record("build", state, true);
+
+        PrData {
+            number: 7,
+            labels: labels.into_iter().map(String::from).collect(),
+            reviews,
+            approvers: vec!["@alice".to_string()],
+            additions: 120,
+            deletions: 30,
+            changed_files: 2,
+            files: vec!["src/lib.rs".to_string(), "src/test_lib.rs".to_string()],
+            checks,
+            draft: false,
+            head_ref: "feat/x".to_string(),
+            head_sha: "abc123".to_string(),
+            base_ref: "main".to_string(),
+            codeowners: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_report_explains_score() {
+        let github = Octocrab::builder().build().unwrap();
+        let guardian = GuardianCore::new(github, "owner".to_string(), "repo".to_string());
+
+        let report = guardian.evaluate(&sample_pr(vec![], vec![ReviewState::Approved], true));
+
+        assert_eq!(report.decision, Decision::AutoMerge { confidence: 100 });
+        assert_eq!(report.threshold, 70);
+        assert!(report.blocking_reasons.is_empty());
+
+        let size = report.signals.iter().find(|s| s.name == "size").unwrap();
+        assert_eq!(size.contribution, -5);
+        let total: i16 = report.signals.iter().map(|s| s.contribution).sum();
+        assert_eq!(total, 100);
+    }
+
+    #[tokio::test]
+    async fn test_local_guardian_scores_without_client() {
+        let guardian = GuardianCore::local().with_threshold(80);
+
+        let report = guardian.evaluate(&sample_pr(vec![], vec![ReviewState::Approved], true));
+        assert_eq!(report.decision, Decision::AutoMerge { confidence: 100 });
+        assert_eq!(report.threshold, 80);
+
+        let err = guardian.evaluate_pr_report(7, true).await.unwrap_err();
+        assert!(err.to_string().contains("no GitHub client"));
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_collects_all_blockers() {
+        let github = Octocrab::builder().build().unwrap();
+        let guardian = GuardianCore::new(github, "owner".to_string(), "repo".to_string());
+
+        let pr = sample_pr(vec!["high-stakes", "needs-human"], vec![ReviewState::Approved], true);
+        let report = guardian.evaluate(&pr);
+
+        assert!(matches!(report.decision, Decision::Blocked { .. }));
+        assert_eq!(report.blocking_reasons.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_ci_failure_escalates() {
+        let github = Octocrab::builder().build().unwrap();
+        let guardian = GuardianCore::new(github, "owner".to_string(), "repo".to_string());
+
+        let report = guardian.evaluate(&sample_pr(vec![], vec![ReviewState::Approved], false));
+
+        assert_eq!(
+            report.decision,
+            Decision::Escalate { reason: "CI checks failed: build".to_string(), confidence: 0 }
+        );
+        assert_eq!(report.signals.last().unwrap().value, "1 failed");
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_pending_checks_escalate() {
+        let github = Octocrab::builder().build().unwrap();
+        let guardian = GuardianCore::new(github, "owner".to_string(), "repo".to_string());
+
+        let mut pr = sample_pr(vec![], vec![ReviewState::Approved], true);
+        pr.checks.require(&["build".to_string(), "test".to_string()]);
+        let report = guardian.evaluate(&pr);
+
+        assert_eq!(
+            report.decision,
+            Decision::Escalate { reason: "CI checks pending: test".to_string(), confidence: 0 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checks_reads_every_page() {
+        use crate::cassette::{Cassette, CassetteServer};
+        use serde_json::json;
+
+        let check_run = |id: u64, name: String, conclusion: &str| json!({
+            "id": id, "node_id": "CR", "details_url": null, "head_sha": "abc",
+            "url": "https://api.github.com/check-runs", "html_url": null, "conclusion": conclusion,
+            "output": { "title": null, "summary": null, "text": null, "annotations_count": 0, "annotations_url": "" },
+            "started_at": null, "completed_at": null, "name": name, "pull_requests": []
+        });
+        let first: Vec<_> = (0..100).map(|i| check_run(i, format!("check-{}", i), "success")).collect();
+        let runs = "/repos/acme/widgets/commits/abc/check-runs";
+        let status = "/repos/acme/widgets/commits/abc/status";
+        let cassette = Cassette::default()
+            .with("GET", &format!("{}?per_page=100&page=1", runs), 200, json!({ "total_count": 101, "check_runs": first }))
+            .with("GET", &format!("{}?per_page=100&page=2", runs), 200, json!({ "total_count": 101, "check_runs": [check_run(100, "deploy".into(), "failure")] }))
+            .with("GET", &format!("{}?per_page=100&page=1", status), 200, json!({
+                "state": "pending", "sha": "abc", "total_count": 0, "statuses": []
+            }));
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let guardian = GuardianCore::from_token_at(None, server.url(), "acme", "widgets").unwrap();
+
+        let checks = guardian.fetch_checks("abc", "main").await.unwrap();
+        assert_eq!(checks.failed, vec!["deploy"]);
+        assert!(!checks.is_green());
+        // Only the best-effort branch protection lookup is unrecorded
+        assert_eq!(server.unmatched(), vec!["GET /repos/acme/widgets/branches/main"]);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_approvers_reads_every_team_page() {
+        use crate::cassette::{Cassette, CassetteServer};
+        use serde_json::json;
+
+        let member = |login: String| json!({
+            "login": login, "id": 1, "node_id": "U", "avatar_url": "https://example.com/a",
+            "gravatar_id": "", "url": "https://example.com", "html_url": "https://example.com",
+            "followers_url": "https://example.com", "following_url": "https://example.com",
+            "gists_url": "https://example.com", "starred_url": "https://example.com",
+            "subscriptions_url": "https://example.com", "organizations_url": "https://example.com",
+            "repos_url": "https://example.com", "events_url": "https://example.com",
+            "received_events_url": "https://example.com", "type": "User", "site_admin": false
+        });
+        let first: Vec<_> = (0..100).map(|i| member(format!("dev-{}", i))).collect();
+        let members = "/orgs/acme/teams/core/members";
+        let mut cassette = Cassette::default()
+            .with("GET", &format!("{}?per_page=100", members), 200, json!(first))
+            .with("GET", &format!("{}?per_page=100&page=2", members), 200, json!([member("zed".into())]));
+        cassette.interactions[0].response.headers.insert(
+            "link".to_string(),
+            format!("<{}?per_page=100&page=2>; rel=\"next\"", members),
+        );
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let guardian = GuardianCore::from_token_at(None, server.url(), "acme", "widgets").unwrap();
+        let codeowners = CodeOwners::parse("/src/  @acme/core\n");
+
+        let approvers = guardian
+            .resolve_approvers(Some(&codeowners), &["zed".to_string()], &["src/lib.rs".to_string()])
+            .await;
+
+        assert_eq!(approvers, vec!["@zed", "@acme/core"]);
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_draft_is_blocked() {
+        let github = Octocrab::builder().build().unwrap();
+        let guardian = GuardianCore::new(github, "owner".to_string(), "repo".to_string());
+
+        let mut pr = sample_pr(vec![], vec![ReviewState::Approved], true);
+        pr.draft = true;
+        let report = guardian.evaluate(&pr);
+
+        assert_eq!(report.decision, Decision::Blocked { reason: "PR is a draft".to_string() });
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_reports_missing_code_owners() {
+        let github = Octocrab::builder().build().unwrap();
+        let guardian = GuardianCore::new(github, "owner".to_string(), "repo".to_string());
+        let mut pr = sample_pr(vec![], vec![ReviewState::Approved], true);
+        pr.codeowners = Some(CodeOwners::parse("*  @alice\n/src/  @bob @carol\n"));
+
+        let report = guardian.evaluate(&pr);
+
+        assert!(matches!(report.decision, Decision::Escalate { .. }));
+        assert_eq!(report.missing_owner_approvals.len(), 1);
+        assert_eq!(report.missing_owner_approvals[0].owners, vec!["@bob", "@carol"]);
+
+        pr.approvers.push("@carol".to_string());
+        let report = guardian.evaluate(&pr);
+        assert!(matches!(report.decision, Decision::AutoMerge { .. }));
+        assert!(report.missing_owner_approvals.is_empty());
+    }
+
+    #[test]
+    fn test_changes_requested_then_approved_counts_as_approval() {
+        let guardian = GuardianCore::local();
+        let verdicts = latest_verdicts([
+            ("alice", ReviewState::ChangesRequested),
+            ("bob", ReviewState::Commented),
+            ("alice", ReviewState::Approved),
+        ]);
+        assert_eq!(verdicts.len(), 1);
+        assert_eq!(verdicts.get("alice"), Some(&ReviewState::Approved));
+
+        let reviews: Vec<ReviewState> = verdicts.values().copied().collect();
+        assert!(guardian.check_reviews(&reviews));
+        let report = guardian.evaluate(&sample_pr(vec![], reviews, true));
+        assert!(matches!(report.decision, Decision::AutoMerge { .. }));
+
+        // The reverse order still blocks
+        let verdicts = latest_verdicts([("alice", ReviewState::Approved), ("alice", ReviewState::ChangesRequested)]);
+        let reviews: Vec<ReviewState> = verdicts.values().copied().collect();
+        assert!(!guardian.check_reviews(&reviews));
+    }
 }
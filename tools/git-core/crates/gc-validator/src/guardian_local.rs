@@ -0,0 +1,230 @@
+//! # Guardian Local Mode
+//!
+//! Builds [`PrData`] from the local git repository instead of the GitHub API,
+//! so a branch can be scored before a PR exists.
+//!
+//! - Files, additions and deletions come from `git diff --numstat base...head`
+//! - CI status comes from an optional JSON result file:
+//!
+//! ```json
+//! { "checks": [ { "name": "test", "conclusion": "success" } ] }
+//! ```
+//!
+//! Required checks of the Guardian policy missing from the result file count
+//! as pending. Without a result file CI is assumed to pass. Reviews cannot
+//! exist before a PR is opened, so an approval is assumed unless
+//! `require_review` is set. CODEOWNERS approvals are not checked locally.
+
+use crate::guardian_core::PrData;
+use crate::guardian_policy::{CheckState, CheckSummary};
+use anyhow::{Context, Result};
+use octocrab::models::pulls::ReviewState;
+use serde::Deserialize;
+use std::path::PathBuf;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// CI result file produced by a local run (`--ci-result`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocalCiResult {
+    #[serde(default)]
+    pub checks: Vec<LocalCheck>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LocalCheck {
+    pub name: String,
+    pub conclusion: String,
+}
+
+impl LocalCiResult {
+    pub fn from_file(path: &std::path::Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read CI result file {}", path.display()))?;
+        let result = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid CI result file {}", path.display()))?;
+        Ok(result)
+    }
+
+    /// Classify results with the same rules as remote check runs
+    pub fn summary(&self, neutral_is_success: bool) -> CheckSummary {
+        let mut summary = CheckSummary::default();
+        for check in &self.checks {
+            let state = CheckState::from_conclusion(Some(check.conclusion.as_str()));
+            summary.record(&check.name, state, neutral_is_success);
+        }
+        summary
+    }
+}
+
+/// Local source of PR data for a `base...head` range
+#[derive(Debug, Clone)]
+pub struct LocalPrSource {
+    pub base: String,
+    pub head: String,
+    pub ci_result: Option<PathBuf>,
+    pub require_review: bool,
+    pub neutral_is_success: bool,
+    pub required_checks: Vec<String>,
+}
+
+impl LocalPrSource {
+    pub fn new(base: &str, head: &str) -> Self {
+        Self {
+            base: base.to_string(),
+            head: head.to_string(),
+            ci_result: None,
+            require_review: false,
+            neutral_is_success: true,
+            required_checks: Vec::new(),
+        }
+    }
+
+    /// Read CI status from a result file
+    pub fn with_ci_result(mut self, path: Option<PathBuf>) -> Self {
+        self.ci_result = path;
+        self
+    }
+
+    /// Do not assume an approving review
+    pub fn with_require_review(mut self, require_review: bool) -> Self {
+        self.require_review = require_review;
+        self
+    }
+
+    /// Neutral/skipped handling from the Guardian policy
+    pub fn with_neutral_is_success(mut self, neutral_is_success: bool) -> Self {
+        self.neutral_is_success = neutral_is_success;
+        self
+    }
+
+    /// Checks the result file must report (`required_checks` of the Guardian policy)
+    pub fn with_required_checks(mut self, required_checks: &[String]) -> Self {
+        self.required_checks = required_checks.to_vec();
+        self
+    }
+
+    /// Collect PR data from git and the optional CI result file
+    pub async fn collect(&self) -> Result<PrData> {
+        let range = format!("{}...{}", self.base, self.head);
+        info!("🛡️ Evaluating local range {}", range);
+
+        let numstat = git(&["diff", "--numstat", "--no-renames", &range]).await?;
+        let (files, additions, deletions) = parse_numstat(&numstat);
+        let head_sha = git(&["rev-parse", &self.head]).await?.trim().to_string();
+
+        let checks = match &self.ci_result {
+            Some(path) => {
+                let mut checks = LocalCiResult::from_file(path)?.summary(self.neutral_is_success);
+                checks.require(&self.required_checks);
+                checks
+            }
+            None => {
+                warn!("⚠️  No CI result file given, assuming CI passes");
+                CheckSummary::default()
+            }
+        };
+
+        let reviews = if self.require_review {
+            Vec::new()
+        } else {
+            vec![ReviewState::Approved]
+        };
+
+        Ok(PrData {
+            number: 0,
+            labels: Vec::new(),
+            reviews,
+            approvers: Vec::new(),
+            additions,
+            deletions,
+            changed_files: files.len() as u32,
+            files,
+            checks,
+            draft: false,
+            head_ref: self.head.clone(),
+            head_sha,
+            base_ref: self.base.clone(),
+            codeowners: None,
+        })
+    }
+}
+
+/// Run a git command and return its stdout
+pub(crate) async fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .await
+        .context("Failed to execute git")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse `git diff --numstat` output into (files, additions, deletions)
+///
+/// Binary files (`-\t-\tpath`) count as changed files with no line changes.
+pub fn parse_numstat(output: &str) -> (Vec<String>, u32, u32) {
+    let mut files = Vec::new();
+    let mut additions = 0u32;
+    let mut deletions = 0u32;
+
+    for line in output.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+
+        additions += added.parse::<u32>().unwrap_or(0);
+        deletions += deleted.parse::<u32>().unwrap_or(0);
+        files.push(path.to_string());
+    }
+
+    (files, additions, deletions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numstat() {
+        let output = "10\t2\tsrc/lib.rs\n-\t-\tlogo.png\n3\t0\ttests/it.rs\n";
+        let (files, additions, deletions) = parse_numstat(output);
+
+        assert_eq!(files, vec!["src/lib.rs", "logo.png", "tests/it.rs"]);
+        assert_eq!(additions, 13);
+        assert_eq!(deletions, 2);
+    }
+
+    #[test]
+    fn test_parse_numstat_empty() {
+        let (files, additions, deletions) = parse_numstat("");
+        assert!(files.is_empty());
+        assert_eq!((additions, deletions), (0, 0));
+    }
+
+    #[test]
+    fn test_ci_result_passed() {
+        let result: LocalCiResult = serde_json::from_str(
+            r#"{"checks":[{"name":"build","conclusion":"success"},{"name":"lint","conclusion":"skipped"}]}"#,
+        )
+        .unwrap();
+        assert!(result.summary(true).is_green());
+        assert_eq!(result.summary(false).failed, vec!["lint"]);
+
+        let result: LocalCiResult = serde_json::from_str(
+            r#"{"checks":[{"name":"build","conclusion":"success"},{"name":"test","conclusion":"failure"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(result.summary(true).failed, vec!["test"]);
+    }
+}
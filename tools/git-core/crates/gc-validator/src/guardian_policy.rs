@@ -0,0 +1,252 @@
+//! # Guardian Policy
+//!
+//! Merge policy and CI check classification for Guardian, loaded from
+//! `.gitcore/guardian-policy.json`:
+//!
+//! ```json
+//! {
+//!   "merge_method": "squash",
+//!   "required_checks": ["build", "test"],
+//!   "neutral_is_success": true
+//! }
+//! ```
+//!
+//! All fields are optional. Without `merge_method` the repository settings
+//! decide (squash, then merge commit, then rebase — whichever is allowed).
+
+use anyhow::Result;
+use octocrab::models::StatusState;
+use octocrab::params::pulls::MergeMethod;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How Guardian merges an approved PR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    Squash,
+    Merge,
+    Rebase,
+}
+
+impl MergeStrategy {
+    /// Pick the preferred method the repository allows (squash > merge > rebase)
+    pub fn from_repo_settings(
+        allow_squash: Option<bool>,
+        allow_merge_commit: Option<bool>,
+        allow_rebase: Option<bool>,
+    ) -> Self {
+        // GitHub omits these fields for tokens without admin access; they
+        // default to enabled on new repositories
+        if allow_squash.unwrap_or(true) {
+            MergeStrategy::Squash
+        } else if allow_merge_commit.unwrap_or(true) {
+            MergeStrategy::Merge
+        } else if allow_rebase.unwrap_or(true) {
+            MergeStrategy::Rebase
+        } else {
+            MergeStrategy::Squash
+        }
+    }
+}
+
+impl std::str::FromStr for MergeStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "squash" => Ok(MergeStrategy::Squash),
+            "merge" => Ok(MergeStrategy::Merge),
+            "rebase" => Ok(MergeStrategy::Rebase),
+            _ => Err(anyhow::anyhow!("Invalid merge method: {}", s)),
+        }
+    }
+}
+
+impl From<MergeStrategy> for MergeMethod {
+    fn from(strategy: MergeStrategy) -> Self {
+        match strategy {
+            MergeStrategy::Squash => MergeMethod::Squash,
+            MergeStrategy::Merge => MergeMethod::Merge,
+            MergeStrategy::Rebase => MergeMethod::Rebase,
+        }
+    }
+}
+
+/// Policy configuration loaded from .gitcore/guardian-policy.json
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuardianPolicy {
+    /// Merge method; `None` defers to the repository settings
+    #[serde(default)]
+    pub merge_method: Option<MergeStrategy>,
+    /// Checks that must report success in addition to branch protection
+    #[serde(default)]
+    pub required_checks: Vec<String>,
+    /// Treat `neutral` and `skipped` conclusions as passing
+    #[serde(default = "default_true")]
+    pub neutral_is_success: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for GuardianPolicy {
+    fn default() -> Self {
+        Self {
+            merge_method: None,
+            required_checks: Vec::new(),
+            neutral_is_success: true,
+        }
+    }
+}
+
+impl GuardianPolicy {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let policy = serde_json::from_str(&content)?;
+        Ok(policy)
+    }
+}
+
+/// Outcome of a single check run or commit status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Success,
+    Neutral,
+    Pending,
+    Failed,
+}
+
+impl CheckState {
+    /// Classify a check run conclusion (`None` while the run is in progress)
+    pub fn from_conclusion(conclusion: Option<&str>) -> Self {
+        match conclusion {
+            None => CheckState::Pending,
+            Some("success") => CheckState::Success,
+            Some("neutral") | Some("skipped") => CheckState::Neutral,
+            Some(_) => CheckState::Failed,
+        }
+    }
+}
+
+impl From<StatusState> for CheckState {
+    /// Classify a commit status (`failure` and `error` both fail)
+    fn from(state: StatusState) -> Self {
+        match state {
+            StatusState::Success => CheckState::Success,
+            StatusState::Pending => CheckState::Pending,
+            _ => CheckState::Failed,
+        }
+    }
+}
+
+/// Check runs and commit statuses for a head SHA, grouped by state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CheckSummary {
+    pub passed: Vec<String>,
+    pub neutral: Vec<String>,
+    pub pending: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl CheckSummary {
+    /// Record one check; neutral results fail unless `neutral_is_success`
+    pub fn record(&mut self, name: &str, state: CheckState, neutral_is_success: bool) {
+        let bucket = match state {
+            CheckState::Success => &mut self.passed,
+            CheckState::Neutral if neutral_is_success => &mut self.neutral,
+            CheckState::Neutral | CheckState::Failed => &mut self.failed,
+            CheckState::Pending => &mut self.pending,
+        };
+        bucket.push(name.to_string());
+    }
+
+    /// Mark required checks that have not reported yet as pending
+    pub fn require(&mut self, required: &[String]) {
+        for name in required {
+            let reported = self
+                .passed
+                .iter()
+                .chain(&self.neutral)
+                .chain(&self.pending)
+                .chain(&self.failed)
+                .any(|n| n == name);
+            if !reported {
+                self.pending.push(name.clone());
+            }
+        }
+    }
+
+    /// No failed and no pending checks
+    pub fn is_green(&self) -> bool {
+        self.failed.is_empty() && self.pending.is_empty()
+    }
+
+    /// Nothing failed yet, but some checks are still running
+    pub fn is_pending(&self) -> bool {
+        self.failed.is_empty() && !self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_state_classification() {
+        assert_eq!(CheckState::from_conclusion(None), CheckState::Pending);
+        assert_eq!(CheckState::from_conclusion(Some("success")), CheckState::Success);
+        assert_eq!(CheckState::from_conclusion(Some("skipped")), CheckState::Neutral);
+        assert_eq!(CheckState::from_conclusion(Some("timed_out")), CheckState::Failed);
+        assert_eq!(CheckState::from(StatusState::Pending), CheckState::Pending);
+        assert_eq!(CheckState::from(StatusState::Error), CheckState::Failed);
+    }
+
+    #[test]
+    fn test_summary_neutral_policy() {
+        let mut summary = CheckSummary::default();
+        summary.record("lint", CheckState::Neutral, true);
+        assert!(summary.is_green());
+
+        let mut summary = CheckSummary::default();
+        summary.record("lint", CheckState::Neutral, false);
+        assert_eq!(summary.failed, vec!["lint"]);
+    }
+
+    #[test]
+    fn test_summary_required_checks() {
+        let mut summary = CheckSummary::default();
+        summary.record("build", CheckState::Success, true);
+        summary.require(&["build".to_string(), "test".to_string()]);
+
+        assert!(summary.is_pending());
+        assert_eq!(summary.pending, vec!["test"]);
+    }
+
+    #[test]
+    fn test_merge_strategy_from_repo_settings() {
+        assert_eq!(MergeStrategy::from_repo_settings(None, None, None), MergeStrategy::Squash);
+        assert_eq!(
+            MergeStrategy::from_repo_settings(Some(false), Some(true), Some(true)),
+            MergeStrategy::Merge
+        );
+        assert_eq!(
+            MergeStrategy::from_repo_settings(Some(false), Some(false), Some(true)),
+            MergeStrategy::Rebase
+        );
+        assert!("fast-forward".parse::<MergeStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_policy_defaults() {
+        let policy: GuardianPolicy = serde_json::from_str("{}").unwrap();
+        assert!(policy.neutral_is_success);
+        assert!(policy.merge_method.is_none());
+
+        let policy: GuardianPolicy =
+            serde_json::from_str(r#"{"merge_method":"rebase","required_checks":["ci"]}"#).unwrap();
+        assert_eq!(policy.merge_method, Some(MergeStrategy::Rebase));
+        assert_eq!(policy.required_checks, vec!["ci"]);
+    }
+}
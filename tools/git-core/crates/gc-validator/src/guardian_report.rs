@@ -0,0 +1,231 @@
+//! # Guardian Decision Report
+//!
+//! Structured, explainable view of a Guardian evaluation: every signal that
+//! went into the confidence score, its contribution, the threshold in force
+//! and any blocking reasons.
+//!
+//! The markdown rendering starts with [`REPORT_MARKER`], a hidden HTML comment
+//! Guardian uses to find its own PR comment and update it in place instead of
+//! posting a new one on every re-evaluation.
+//!
+//! ## JSON Schema
+//!
+//! `--ci-mode` used to print the bare decision (`{"AutoMerge": {"confidence": 95}}`).
+//! The report now carries [`REPORT_SCHEMA_VERSION`] and keeps that top-level
+//! decision key next to the new fields, so existing consumers keep working.
+
+use crate::codeowners::OwnerApprovalGap;
+use crate::guardian_core::{Decision, MAX_CONFIDENCE};
+use anyhow::Result;
+use serde::Serialize;
+
+/// Hidden marker identifying the Guardian decision comment on a PR
+pub const REPORT_MARKER: &str = "<!-- gc-guardian:decision -->";
+
+/// Version of the JSON report; the bare decision output was version 1
+pub const REPORT_SCHEMA_VERSION: u32 = 2;
+
+/// A single input to the confidence score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Signal {
+    /// Stable signal identifier (e.g. `ci`, `reviews`, `size`)
+    pub name: String,
+    /// Human readable observation (e.g. `passed`, `480 lines`)
+    pub value: String,
+    /// Points added to (positive) or removed from (negative) the confidence
+    pub contribution: i16,
+}
+
+impl Signal {
+    pub fn new(name: &str, value: impl Into<String>, contribution: i16) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.into(),
+            contribution,
+        }
+    }
+}
+
+/// Full explanation of a Guardian decision
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionReport {
+    pub pr_number: u64,
+    pub decision: Decision,
+    /// Final score, capped at [`MAX_CONFIDENCE`]; the signal contributions
+    /// may add up to more
+    pub confidence: u8,
+    pub threshold: u8,
+    pub signals: Vec<Signal>,
+    pub blocking_reasons: Vec<String>,
+    /// CODEOWNERS paths still waiting for an owner approval
+    pub missing_owner_approvals: Vec<OwnerApprovalGap>,
+}
+
+impl DecisionReport {
+    /// Returns true if `body` is a comment previously rendered by [`Self::to_markdown`]
+    pub fn is_report_comment(body: &str) -> bool {
+        body.contains(REPORT_MARKER)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct Versioned<'a> {
+            schema_version: u32,
+            /// Version 1 shape: `{"AutoMerge": {...}}` at the top level
+            #[serde(flatten)]
+            legacy: &'a Decision,
+            #[serde(flatten)]
+            report: &'a DecisionReport,
+        }
+
+        Ok(serde_json::to_string_pretty(&Versioned {
+            schema_version: REPORT_SCHEMA_VERSION,
+            legacy: &self.decision,
+            report: self,
+        })?)
+    }
+
+    /// Sum of the signal contributions when it exceeds the capped confidence
+    pub fn uncapped_confidence(&self) -> Option<i16> {
+        let total: i16 = self.signals.iter().map(|s| s.contribution).sum();
+        (self.confidence == MAX_CONFIDENCE && total > MAX_CONFIDENCE as i16).then_some(total)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+
+        md.push_str(REPORT_MARKER);
+        md.push('\n');
+
+        let (icon, headline) = match &self.decision {
+            Decision::AutoMerge { .. } => ("✅", "Auto-merge approved".to_string()),
+            Decision::Escalate { reason, .. } => ("⚠️", format!("Manual review required — {}", reason)),
+            Decision::Blocked { reason } => ("⛔", format!("PR blocked — {}", reason)),
+        };
+
+        md.push_str(&format!("## 🤖 Guardian Agent: {} {}\n\n", icon, headline));
+        let cap = match self.uncapped_confidence() {
+            Some(total) => format!(" (capped, signals add up to {})", total),
+            None => String::new(),
+        };
+        md.push_str(&format!(
+            "**Confidence:** {}%{} | **Threshold:** {}%\n\n",
+            self.confidence, cap, self.threshold
+        ));
+
+        if !self.blocking_reasons.is_empty() {
+            md.push_str("### ⛔ Blocking Reasons\n\n");
+            for reason in &self.blocking_reasons {
+                md.push_str(&format!("- {}\n", reason));
+            }
+            md.push('\n');
+        }
+
+        if !self.missing_owner_approvals.is_empty() {
+            md.push_str("### 👥 Missing Code Owner Approvals\n\n");
+            md.push_str("| Path | Owners | Files |\n");
+            md.push_str("|------|--------|-------|\n");
+            for gap in &self.missing_owner_approvals {
+                md.push_str(&format!(
+                    "| `{}` | {} | {} |\n",
+                    gap.pattern,
+                    gap.owners.join(", "),
+                    gap.files.len()
+                ));
+            }
+            md.push('\n');
+        }
+
+        if !self.signals.is_empty() {
+            md.push_str("### 📊 Signals\n\n");
+            md.push_str("| Signal | Value | Contribution |\n");
+            md.push_str("|--------|-------|--------------|\n");
+            for signal in &self.signals {
+                md.push_str(&format!(
+                    "| {} | {} | {:+} |\n",
+                    signal.name, signal.value, signal.contribution
+                ));
+            }
+            md.push('\n');
+        }
+
+        md.push_str("---\n*This comment is updated in place on every Guardian evaluation.*\n");
+
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> DecisionReport {
+        DecisionReport {
+            pr_number: 42,
+            decision: Decision::AutoMerge { confidence: 95 },
+            confidence: 95,
+            threshold: 70,
+            signals: vec![
+                Signal::new("ci", "passed", 40),
+                Signal::new("size", "250 lines", -5),
+            ],
+            blocking_reasons: vec![],
+            missing_owner_approvals: vec![],
+        }
+    }
+
+    #[test]
+    fn test_markdown_starts_with_marker() {
+        let md = sample_report().to_markdown();
+        assert!(md.starts_with(REPORT_MARKER));
+        assert!(DecisionReport::is_report_comment(&md));
+        assert!(md.contains("| ci | passed | +40 |"));
+        assert!(md.contains("| size | 250 lines | -5 |"));
+    }
+
+    #[test]
+    fn test_json_contains_signals() {
+        let json = sample_report().to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["pr_number"], 42);
+        assert_eq!(value["threshold"], 70);
+        assert_eq!(value["signals"][0]["name"], "ci");
+        assert_eq!(value["signals"][1]["contribution"], -5);
+        assert_eq!(value["schema_version"], REPORT_SCHEMA_VERSION);
+        // Version 1 consumers read the bare decision key
+        assert_eq!(value["AutoMerge"]["confidence"], 95);
+        assert_eq!(value["decision"]["AutoMerge"]["confidence"], 95);
+    }
+
+    #[test]
+    fn test_capped_confidence_is_explained() {
+        let mut report = sample_report();
+        assert!(report.uncapped_confidence().is_none());
+
+        report.confidence = MAX_CONFIDENCE;
+        report.signals = vec![
+            Signal::new("ci", "passed", 40),
+            Signal::new("reviews", "approved", 40),
+            Signal::new("tests", "included", 15),
+            Signal::new("scope", "single", 10),
+        ];
+        assert_eq!(report.uncapped_confidence(), Some(105));
+        assert!(report.to_markdown().contains("**Confidence:** 100% (capped, signals add up to 105)"));
+    }
+
+    #[test]
+    fn test_blocking_reasons_rendered() {
+        let mut report = sample_report();
+        report.decision = Decision::Blocked { reason: "needs-human label detected".to_string() };
+        report.blocking_reasons = vec!["needs-human label detected".to_string()];
+
+        let md = report.to_markdown();
+        assert!(md.contains("### ⛔ Blocking Reasons"));
+        assert!(md.contains("- needs-human label detected"));
+    }
+
+    #[test]
+    fn test_plain_comment_is_not_report() {
+        assert!(!DecisionReport::is_report_comment("LGTM 👍"));
+    }
+}
@@ -0,0 +1,398 @@
+//! # Guardian Watchdog - Post-Merge Verification
+//!
+//! Watches commits Guardian auto-merged and reverts them if they break the
+//! default branch.
+//!
+//! ## Lifecycle
+//!
+//! ```text
+//! guardian:auto-merged  → added by Guardian when it merges a PR
+//!        │
+//!        ├─ default-branch CI green  → guardian:verified
+//!        ├─ default-branch CI pending → checked again on the next run
+//!        └─ default-branch CI failed → revert PR opened, guardian:reverted,
+//!                                      author notified on the original PR
+//! ```
+//!
+//! Labels are the only state, so the watchdog can run statelessly on a
+//! schedule. Reverting needs a local clone with push access: the revert
+//! commit is created with `git revert` in a temporary worktree (the current
+//! checkout is left alone) and pushed to `guardian/revert-<pr>`. An open
+//! revert PR or an existing revert branch means an earlier run already
+//! reverted, so retries never push twice.
+//!
+//! A rebase merge lands every commit of the PR on the default branch, with
+//! `merge_commit_sha` pointing at the last one. When the last commits of the
+//! branch carry the PR's commit subjects, the whole range is reverted.
+//!
+//! Cancelled runs and runs that failed to start say nothing about the merge
+//! commit; they are skipped, and a commit with only such runs is `Unknown`.
+
+use crate::github::{GitHubClient, PullRequestInfo, RunFilter, WorkflowRun};
+use crate::guardian_local::git;
+use anyhow::Result;
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// Label Guardian adds to PRs it merged automatically
+pub const AUTO_MERGED_LABEL: &str = "guardian:auto-merged";
+/// Label for auto-merged PRs whose merge commit passed CI
+pub const VERIFIED_LABEL: &str = "guardian:verified";
+/// Label for auto-merged PRs that were reverted
+pub const REVERTED_LABEL: &str = "guardian:reverted";
+
+/// CI state of a commit on the default branch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum BranchHealth {
+    Green,
+    Pending,
+    Broken { failed_workflows: Vec<String> },
+    /// Only cancelled runs or runs that failed to start
+    Unknown,
+}
+
+/// What the watchdog did for one auto-merged PR
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchOutcome {
+    pub pr_number: u64,
+    pub merge_sha: String,
+    pub health: BranchHealth,
+    pub revert_pr: Option<u64>,
+}
+
+/// Classify the workflow runs of a merge commit on the default branch
+pub fn classify_runs(runs: &[WorkflowRun], default_branch: &str) -> BranchHealth {
+    let runs: Vec<&WorkflowRun> = runs.iter().filter(|r| r.head_branch == default_branch).collect();
+    let total = runs.len();
+    let runs: Vec<&WorkflowRun> = runs
+        .into_iter()
+        .filter(|r| !matches!(r.conclusion.as_deref(), Some("cancelled") | Some("startup_failure")))
+        .collect();
+    if total > 0 && runs.is_empty() {
+        return BranchHealth::Unknown;
+    }
+
+    let failed_workflows: Vec<String> = runs
+        .iter()
+        .filter(|r| matches!(r.conclusion.as_deref(), Some("failure") | Some("timed_out")))
+        .map(|r| r.name.clone())
+        .collect();
+
+    if !failed_workflows.is_empty() {
+        return BranchHealth::Broken { failed_workflows };
+    }
+
+    if runs.is_empty() || runs.iter().any(|r| r.status != "completed") {
+        return BranchHealth::Pending;
+    }
+
+    BranchHealth::Green
+}
+
+/// Check every auto-merged PR and revert the ones that broke the default branch
+pub async fn run_watch(client: &GitHubClient, dry_run: bool) -> Result<Vec<WatchOutcome>> {
+    info!("👀 Watching Guardian auto-merges...");
+
+    let default_branch = client.get_default_branch().await?;
+    let candidates = client.list_issues_with_label(AUTO_MERGED_LABEL, "closed").await?;
+
+    let mut outcomes = Vec::new();
+
+    for issue in candidates.iter().filter(|i| i.pull_request.is_some()) {
+        let pr = client.get_pull_request(issue.number).await?;
+
+        let Some(merge_sha) = pr.merge_commit_sha.clone().filter(|_| pr.merged_at.is_some()) else {
+            continue;
+        };
+
+        let runs = client.collect_runs(&RunFilter::default().with_head_sha(&merge_sha)).await?;
+        let health = classify_runs(&runs, &default_branch);
+
+        let mut revert_pr = None;
+        match &health {
+            BranchHealth::Green => {
+                info!("✅ PR #{} ({}) is green on {}", pr.number, short(&merge_sha), default_branch);
+                if !dry_run {
+                    client.remove_label(pr.number, AUTO_MERGED_LABEL).await?;
+                    client.add_labels(pr.number, &[VERIFIED_LABEL]).await?;
+                }
+            }
+            BranchHealth::Pending => {
+                info!("⏳ PR #{} ({}) still running on {}", pr.number, short(&merge_sha), default_branch);
+            }
+            BranchHealth::Unknown => {
+                warn!(
+                    "❔ PR #{} ({}) has only cancelled or unstarted runs on {}, skipping",
+                    pr.number,
+                    short(&merge_sha),
+                    default_branch
+                );
+            }
+            BranchHealth::Broken { failed_workflows } => {
+                warn!(
+                    "🔴 PR #{} ({}) broke {}: {}",
+                    pr.number,
+                    short(&merge_sha),
+                    default_branch,
+                    failed_workflows.join(", ")
+                );
+                if !dry_run {
+                    revert_pr = revert(client, &pr, &merge_sha, &default_branch, failed_workflows).await?;
+                }
+            }
+        }
+
+        outcomes.push(WatchOutcome {
+            pr_number: pr.number,
+            merge_sha,
+            health,
+            revert_pr,
+        });
+    }
+
+    info!("👀 Checked {} auto-merged PR(s)", outcomes.len());
+    Ok(outcomes)
+}
+
+/// Open a revert PR for `merge_sha`, label the original PR and notify its author
+///
+/// Returns the revert PR, `None` when a leftover revert branch without a PR
+/// blocks the revert.
+async fn revert(
+    client: &GitHubClient,
+    pr: &PullRequestInfo,
+    merge_sha: &str,
+    default_branch: &str,
+    failed_workflows: &[String],
+) -> Result<Option<u64>> {
+    let branch = format!("guardian/revert-{}", pr.number);
+
+    let open_revert = client
+        .list_open_pull_requests()
+        .await?
+        .into_iter()
+        .find(|p| p.head.as_ref().is_some_and(|h| h.ref_name == branch));
+    if let Some(existing) = open_revert {
+        info!("⏪ Revert PR #{} for #{} is already open", existing.number, pr.number);
+        client.remove_label(pr.number, AUTO_MERGED_LABEL).await?;
+        client.add_labels(pr.number, &[REVERTED_LABEL]).await?;
+        return Ok(Some(existing.number));
+    }
+    if client.branch_exists(&branch).await? {
+        warn!("⚠️  Branch {} already exists without an open PR; delete it to retry the revert of #{}", branch, pr.number);
+        return Ok(None);
+    }
+
+    git(&["fetch", "origin", default_branch]).await?;
+    let worktree = std::env::temp_dir().join(format!("guardian-revert-{}-{}", pr.number, std::process::id()));
+    let worktree = worktree.to_string_lossy().into_owned();
+    git(&["worktree", "add", "--detach", &worktree, &format!("origin/{}", default_branch)]).await?;
+
+    // Only multi-commit PRs can have been rebase-merged into several commits
+    let pr_subjects: Vec<String> = if pr.commits.unwrap_or(1) > 1 {
+        client
+            .get_pull_request_commits(pr.number)
+            .await?
+            .iter()
+            .map(|c| c.commit.message.lines().next().unwrap_or_default().to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let pushed = revert_in_worktree(&worktree, merge_sha, &pr_subjects, &branch).await;
+    if let Err(e) = git(&["worktree", "remove", "--force", &worktree]).await {
+        warn!("⚠️  Could not remove worktree {}: {}", worktree, e);
+    }
+    pushed?;
+
+    let body = format!(
+        "## ⏪ Guardian Watchdog: Revert #{}\n\n\
+         Auto-merged PR #{} ({}) broke `{}`.\n\n\
+         **Failed workflows:** {}\n\n\
+         This PR reverts `{}`. Re-land the change once the failure is fixed.\n\n\
+         ---\n*Generated by Git-Core Protocol Guardian Watchdog*\n",
+        pr.number,
+        pr.number,
+        pr.html_url,
+        default_branch,
+        failed_workflows.join(", "),
+        merge_sha,
+    );
+
+    let revert_pr = client
+        .create_pr_with_base(
+            &format!("⏪ Revert \"{}\" (#{})", pr.title, pr.number),
+            &body,
+            &branch,
+            default_branch,
+        )
+        .await?;
+
+    client.remove_label(pr.number, AUTO_MERGED_LABEL).await?;
+    client.add_labels(pr.number, &[REVERTED_LABEL]).await?;
+
+    client
+        .add_pr_comment(
+            pr.number,
+            &format!(
+                "@{} 🔴 The default branch CI failed after Guardian auto-merged this PR \
+                 (failed: {}). A revert has been opened in #{}.",
+                pr.user.login,
+                failed_workflows.join(", "),
+                revert_pr.number
+            ),
+        )
+        .await?;
+
+    info!("⏪ Opened revert PR #{} for #{}", revert_pr.number, pr.number);
+    Ok(Some(revert_pr.number))
+}
+
+/// Revert the PR landed at `merge_sha` in `worktree` and push it as a new `branch`
+///
+/// `pr_subjects` are the PR's commit subjects, oldest first; when the last
+/// commits up to `merge_sha` carry them, the PR was rebase-merged and all of
+/// them are reverted.
+async fn revert_in_worktree(worktree: &str, merge_sha: &str, pr_subjects: &[String], branch: &str) -> Result<()> {
+    // Merge commits need a mainline parent; squash and rebase commits do not
+    let parents = git(&["-C", worktree, "rev-list", "--parents", "-n", "1", merge_sha]).await?;
+    if parents.split_whitespace().count() > 2 {
+        git(&["-C", worktree, "revert", "--no-edit", "-m", "1", merge_sha]).await?;
+    } else if pr_subjects.len() > 1 && landed_subjects(worktree, merge_sha, pr_subjects.len()).await? == pr_subjects {
+        info!("⏪ {} is the last of {} rebased commits, reverting all of them", short(merge_sha), pr_subjects.len());
+        let range = format!("{}~{}..{}", merge_sha, pr_subjects.len(), merge_sha);
+        git(&["-C", worktree, "revert", "--no-edit", &range]).await?;
+    } else {
+        git(&["-C", worktree, "revert", "--no-edit", merge_sha]).await?;
+    }
+
+    // No --force: an existing remote branch means another run got there first
+    git(&["-C", worktree, "push", "origin", &format!("HEAD:refs/heads/{}", branch)]).await?;
+    Ok(())
+}
+
+/// Subjects of the last `count` single-parent commits up to `sha`, oldest first
+async fn landed_subjects(worktree: &str, sha: &str, count: usize) -> Result<Vec<String>> {
+    let count = count.to_string();
+    let log = git(&["-C", worktree, "log", "--no-merges", "--first-parent", "--format=%s", "-n", &count, "--reverse", sha]).await?;
+    Ok(log.lines().map(String::from).collect())
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(name: &str, branch: &str, status: &str, conclusion: Option<&str>) -> WorkflowRun {
+        WorkflowRun {
+            id: 1,
+            name: name.to_string(),
+            status: status.to_string(),
+            conclusion: conclusion.map(String::from),
+            html_url: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            run_attempt: 1,
+            workflow_id: 1,
+            head_branch: branch.to_string(),
+            head_sha: "abc".to_string(),
+            path: String::new(),
+            event: "push".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_classify_green() {
+        let runs = vec![run("CI", "main", "completed", Some("success"))];
+        assert_eq!(classify_runs(&runs, "main"), BranchHealth::Green);
+    }
+
+    #[test]
+    fn test_classify_pending_without_runs() {
+        assert_eq!(classify_runs(&[], "main"), BranchHealth::Pending);
+
+        let runs = vec![
+            run("CI", "main", "completed", Some("success")),
+            run("E2E", "main", "in_progress", None),
+        ];
+        assert_eq!(classify_runs(&runs, "main"), BranchHealth::Pending);
+    }
+
+    #[test]
+    fn test_classify_broken_ignores_other_branches() {
+        let runs = vec![
+            run("CI", "main", "completed", Some("failure")),
+            run("Lint", "feature", "completed", Some("failure")),
+            run("E2E", "main", "in_progress", None),
+        ];
+        assert_eq!(
+            classify_runs(&runs, "main"),
+            BranchHealth::Broken { failed_workflows: vec!["CI".to_string()] }
+        );
+    }
+
+    #[test]
+    fn test_classify_skips_cancelled_and_unstarted_runs() {
+        let runs = vec![
+            run("CI", "main", "completed", Some("success")),
+            run("E2E", "main", "completed", Some("cancelled")),
+        ];
+        assert_eq!(classify_runs(&runs, "main"), BranchHealth::Green);
+
+        let runs = vec![
+            run("CI", "main", "completed", Some("cancelled")),
+            run("E2E", "main", "completed", Some("startup_failure")),
+        ];
+        assert_eq!(classify_runs(&runs, "main"), BranchHealth::Unknown);
+    }
+
+    #[tokio::test]
+    async fn test_revert_reverts_every_rebased_commit() {
+        let root = std::env::temp_dir().join(format!("guardian-watch-rebase-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let origin = root.join("origin.git").to_string_lossy().into_owned();
+        let clone = root.join("clone").to_string_lossy().into_owned();
+        git(&["init", "--bare", "-q", &origin]).await.unwrap();
+        git(&["clone", "-q", &origin, &clone]).await.unwrap();
+        // Identity in the clone's own config, so the test does not touch the process environment
+        git(&["-C", &clone, "config", "user.name", "Guardian"]).await.unwrap();
+        git(&["-C", &clone, "config", "user.email", "guardian@example.com"]).await.unwrap();
+
+        let commit = |file: &'static str, subject: &'static str| {
+            let clone = clone.clone();
+            async move {
+                std::fs::write(std::path::Path::new(&clone).join(file), subject).unwrap();
+                git(&["-C", &clone, "add", file]).await.unwrap();
+                git(&["-C", &clone, "commit", "-q", "-m", subject]).await.unwrap();
+            }
+        };
+        commit("README.md", "initial").await;
+        let base_tree = git(&["-C", &clone, "rev-parse", "HEAD^{tree}"]).await.unwrap();
+        // A three-commit PR rebase-merged onto the default branch
+        commit("a.txt", "feat: add a").await;
+        commit("b.txt", "feat: add b").await;
+        commit("c.txt", "fix: break c").await;
+        let merge_sha = git(&["-C", &clone, "rev-parse", "HEAD"]).await.unwrap().trim().to_string();
+
+        let subjects: Vec<String> = ["feat: add a", "feat: add b", "fix: break c"].iter().map(|s| s.to_string()).collect();
+        revert_in_worktree(&clone, &merge_sha, &subjects, "guardian/revert-7").await.unwrap();
+
+        let reverted_tree = git(&["-C", &origin, "rev-parse", "guardian/revert-7^{tree}"]).await.unwrap();
+        assert_eq!(reverted_tree, base_tree);
+        let reverts = git(&["-C", &origin, "rev-list", "--count", &format!("{}..guardian/revert-7", merge_sha)]).await.unwrap();
+        assert_eq!(reverts.trim(), "3");
+
+        // Subjects that do not match a squash commit revert only the merge commit
+        let squashed: Vec<String> = vec!["wip".to_string(), "fix: break c".to_string()];
+        git(&["-C", &clone, "reset", "-q", "--hard", &merge_sha]).await.unwrap();
+        revert_in_worktree(&clone, &merge_sha, &squashed, "guardian/revert-8").await.unwrap();
+        let reverts = git(&["-C", &origin, "rev-list", "--count", &format!("{}..guardian/revert-8", merge_sha)]).await.unwrap();
+        assert_eq!(reverts.trim(), "1");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,506 @@
+//! # Self-Healing Re-runs
+//!
+//! Re-runs the failed jobs of workflow runs that failed for transient reasons.
+//! Each failed job is classified from its parsed log; the log patterns are
+//! only matched on lines reporting an error, so a retried download logged as a
+//! warning does not make a failure transient:
+//!
+//! | Cause | Evidence |
+//! |-------|----------|
+//! | `runner-lost` | runner shutdown signal, lost communication with the server |
+//! | `rate-limit` | API rate limit exceeded, HTTP 429 |
+//! | `network-timeout` | connection timeouts/resets, DNS failures, 502/503 from registries |
+//! | `flaky-test` | every failed test has an open `flaky-test` tracking issue |
+//!
+//! Compile errors and failed tests that are not known to be flaky make a job
+//! permanent. A run is healed only when all of its failed jobs are transient,
+//! since `rerun-failed-jobs` re-runs every failed job of the run. Re-runs are
+//! capped by `--max-retries` per run (earlier attempts of the run count too) and
+//! recorded in the history database (`heal_attempts`).
+
+use crate::flaky::{FLAKY_LABEL, FLAKY_TITLE_PREFIX};
+use crate::github::{GitHubClient, RunFilter, WorkflowRun};
+use crate::history::HistoryStore;
+use crate::log_parser::{clean_line, parse_log, FailureKind, LogFailure};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+/// Re-runs allowed per workflow run by default
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+
+/// Why a failed job is expected to pass when re-run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransientCause {
+    NetworkTimeout,
+    RunnerLost,
+    RateLimit,
+    FlakyTest,
+}
+
+impl std::fmt::Display for TransientCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TransientCause::NetworkTimeout => "network-timeout",
+            TransientCause::RunnerLost => "runner-lost",
+            TransientCause::RateLimit => "rate-limit",
+            TransientCause::FlakyTest => "flaky-test",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Log patterns per cause, checked in order
+fn patterns() -> &'static [(TransientCause, Regex)] {
+    static PATTERNS: OnceLock<Vec<(TransientCause, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| vec![
+        (
+            TransientCause::RunnerLost,
+            Regex::new(r"(?i)runner has received a shutdown signal|lost communication with the server|runner .* (did not respond|has been lost)").unwrap(),
+        ),
+        (
+            TransientCause::RateLimit,
+            Regex::new(r"(?i)api rate limit exceeded|secondary rate limit|429 too many requests|toomanyrequests").unwrap(),
+        ),
+        (
+            TransientCause::NetworkTimeout,
+            Regex::new(r"(?i)connection (timed out|reset by peer|refused)|operation timed out|timeout was reached|\b(ETIMEDOUT|ECONNRESET|EAI_AGAIN)\b|could not resolve host|temporary failure in name resolution|tls handshake timeout|i/o timeout|network is unreachable|50[23] (bad gateway|service unavailable)").unwrap(),
+        ),
+    ])
+}
+
+/// Lines reporting a failure, with their `Caused by:` context (indented or blank lines)
+fn failing_lines(log: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut in_error = false;
+    for line in log.lines().map(clean_line) {
+        let continuation = line.trim().is_empty() || line.starts_with(char::is_whitespace) || line.starts_with("Caused by:");
+        in_error = is_failing_line(&line) || (in_error && continuation);
+        if in_error {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// Error output, not warnings about retried operations
+fn is_failing_line(line: &str) -> bool {
+    static WARNING: OnceLock<Regex> = OnceLock::new();
+    static FAILING: OnceLock<Regex> = OnceLock::new();
+    let warning = WARNING.get_or_init(|| Regex::new(r"(?i)^\s*(##\[)?warn(ing)?\b").unwrap());
+    let failing = FAILING.get_or_init(|| {
+        Regex::new(r"(?i)##\[error\]|\b(error|fatal|failed|failure)\b|\bERR!|\bHTTP [45]\d\d\b").unwrap()
+    });
+    !warning.is_match(line) && failing.is_match(line)
+}
+
+/// Diagnosis of one failed job
+#[derive(Debug, Clone, Serialize)]
+pub struct JobDiagnosis {
+    pub job: String,
+    pub job_id: u64,
+    /// `None` when the failure looks permanent
+    pub cause: Option<TransientCause>,
+    /// Log line or tests that led to the classification, or why the log is missing
+    pub evidence: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealAction {
+    /// Failed jobs were re-run
+    Rerun,
+    /// Failed jobs would be re-run (`--dry-run`)
+    WouldRerun,
+    /// At least one failed job looks permanent
+    NotTransient,
+    /// The run already used its re-runs
+    BudgetExhausted,
+    /// A newer run of the same workflow and branch exists
+    Superseded,
+    /// Re-running the failed jobs was requested but failed
+    RerunFailed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealDecision {
+    pub run_id: u64,
+    pub workflow: String,
+    pub head_branch: String,
+    pub attempt: u32,
+    /// Re-runs already spent on this run
+    pub retries_used: u32,
+    pub action: HealAction,
+    pub jobs: Vec<JobDiagnosis>,
+}
+
+/// Classify a failed job from its log and parsed failures
+pub fn classify_job(
+    log: &str,
+    failures: &[LogFailure],
+    known_flaky: &BTreeSet<String>,
+) -> Option<(TransientCause, String)> {
+    // A compile error is never fixed by re-running
+    if failures.iter().any(|f| f.kind == FailureKind::CompileError) {
+        return None;
+    }
+
+    let failed_tests: Vec<&str> = failures.iter().filter_map(|f| f.test.as_deref()).collect();
+    if !failed_tests.is_empty() {
+        return failed_tests.iter()
+            .all(|t| known_flaky.contains(*t))
+            .then(|| (TransientCause::FlakyTest, failed_tests.join(", ")));
+    }
+
+    for line in failing_lines(log) {
+        if let Some((cause, _)) = patterns().iter().find(|(_, re)| re.is_match(&line)) {
+            return Some((*cause, line.trim().to_string()));
+        }
+    }
+    None
+}
+
+/// Decide what to do with a failed run
+pub fn decide(jobs: &[JobDiagnosis], retries_used: u32, max_retries: u32, superseded: bool) -> HealAction {
+    if superseded {
+        HealAction::Superseded
+    } else if jobs.is_empty() || jobs.iter().any(|j| j.cause.is_none()) {
+        HealAction::NotTransient
+    } else if retries_used >= max_retries {
+        HealAction::BudgetExhausted
+    } else {
+        HealAction::Rerun
+    }
+}
+
+/// Test names with an open flaky tracking issue
+async fn known_flaky_tests(client: &GitHubClient) -> BTreeSet<String> {
+    match client.list_issues_with_label(FLAKY_LABEL, "open").await {
+        Ok(issues) => issues.iter()
+            .filter_map(|i| i.title.strip_prefix(FLAKY_TITLE_PREFIX))
+            .map(String::from)
+            .collect(),
+        Err(e) => {
+            warn!("⚠️  Could not list flaky test issues: {}", e);
+            BTreeSet::new()
+        }
+    }
+}
+
+/// Diagnose every failed job of a run
+async fn diagnose_run(client: &GitHubClient, run: &WorkflowRun, known_flaky: &BTreeSet<String>) -> Result<Vec<JobDiagnosis>> {
+    let jobs = client.get_jobs(run.id).await?;
+    let mut diagnoses = Vec::new();
+
+    for job in jobs.iter().filter(|j| j.conclusion.as_deref() == Some("failure")) {
+        // Without a log nothing proves the failure transient, so it is not re-run
+        let log = match client.get_job_logs(job.id).await {
+            Ok(log) => log,
+            Err(e) => {
+                warn!("⚠️  Could not download the log of job '{}' (run #{}): {}", job.name, run.id, e);
+                diagnoses.push(JobDiagnosis {
+                    job: job.name.clone(),
+                    job_id: job.id,
+                    cause: None,
+                    evidence: Some(format!("job log unavailable: {}", e)),
+                });
+                continue;
+            }
+        };
+        let failures = parse_log(&log);
+        let classified = classify_job(&log, &failures, known_flaky);
+        diagnoses.push(JobDiagnosis {
+            job: job.name.clone(),
+            job_id: job.id,
+            cause: classified.as_ref().map(|(cause, _)| *cause),
+            evidence: classified.map(|(_, evidence)| evidence),
+        });
+    }
+
+    Ok(diagnoses)
+}
+
+/// Runs to fetch for healing: those matching `filter` in the last `hours`
+pub fn candidate_filter(filter: &RunFilter, hours: u64) -> RunFilter {
+    filter.clone().created_since(Utc::now() - Duration::hours(hours as i64))
+}
+
+/// A run and the later runs of its workflow and branch, which decide whether it was superseded
+async fn run_and_successors(client: &GitHubClient, run_id: u64) -> Result<Vec<WorkflowRun>> {
+    let run = client.get_workflow_run(run_id).await?;
+    let filter = RunFilter {
+        workflow: Some(run.workflow_id.to_string()),
+        branch: Some(run.head_branch.clone()),
+        ..Default::default()
+    };
+    let filter = match DateTime::parse_from_rfc3339(&run.created_at) {
+        Ok(created) => filter.created_since(created.with_timezone(&Utc)),
+        Err(_) => filter.with_limit(100),
+    };
+
+    let mut runs = client.collect_runs(&filter).await?;
+    if !runs.iter().any(|r| r.id == run.id) {
+        runs.push(run);
+    }
+    Ok(runs)
+}
+
+/// Classify failed runs and re-run the transiently failed ones
+///
+/// `run_id` limits healing to one run (e.g. from a `workflow_run` trigger) and
+/// ignores `filter`; otherwise all failed runs matching `filter` (see
+/// [`candidate_filter`]) are considered.
+pub async fn run_heal(
+    client: &GitHubClient,
+    filter: &RunFilter,
+    store: Option<&HistoryStore>,
+    run_id: Option<u64>,
+    max_retries: u32,
+    dry_run: bool,
+    output_format: &str,
+) -> Result<Vec<HealDecision>> {
+    info!("🩹 Looking for transiently failed runs (max {} re-run(s) per run)...", max_retries);
+
+    let runs = match run_id {
+        Some(id) => run_and_successors(client, id).await?,
+        None => client.collect_runs(filter).await?,
+    };
+    let failed: Vec<&WorkflowRun> = runs.iter()
+        .filter(|r| r.conclusion.as_deref() == Some("failure"))
+        .filter(|r| run_id.is_none_or(|id| r.id == id))
+        .collect();
+
+    if failed.is_empty() {
+        info!("✅ No failed runs to heal");
+    }
+
+    let known_flaky = if failed.is_empty() { BTreeSet::new() } else { known_flaky_tests(client).await };
+    let mut decisions = Vec::new();
+
+    for run in failed {
+        let superseded = runs.iter().any(|r| {
+            r.id != run.id
+                && r.workflow_id == run.workflow_id
+                && r.head_branch == run.head_branch
+                && r.created_at > run.created_at
+        });
+        let recorded = match store {
+            Some(store) => store.heal_attempts(run.id)?,
+            None => 0,
+        };
+        let retries_used = recorded.max(run.run_attempt.saturating_sub(1));
+
+        let jobs = if superseded { Vec::new() } else { diagnose_run(client, run, &known_flaky).await? };
+        let mut action = decide(&jobs, retries_used, max_retries, superseded);
+
+        if action == HealAction::Rerun {
+            if dry_run {
+                action = HealAction::WouldRerun;
+            } else if let Err(e) = client.rerun_failed_jobs(run.id).await {
+                warn!("⚠️  Could not re-run failed jobs of run #{} ({}): {:#}", run.id, run.name, e);
+                action = HealAction::RerunFailed;
+            } else {
+                info!("🔁 Re-running failed jobs of run #{} ({})", run.id, run.name);
+                if let Some(store) = store {
+                    let names: Vec<&str> = jobs.iter().map(|j| j.job.as_str()).collect();
+                    let causes: Vec<String> = jobs.iter().filter_map(|j| j.cause).map(|c| c.to_string()).collect();
+                    let causes: Vec<&str> = causes.iter().map(String::as_str).collect();
+                    store.record_heal_attempt(run.id, &run.name, run.run_attempt, &names, &causes)?;
+                }
+            }
+        }
+
+        decisions.push(HealDecision {
+            run_id: run.id,
+            workflow: run.name.clone(),
+            head_branch: run.head_branch.clone(),
+            attempt: run.run_attempt,
+            retries_used,
+            action,
+            jobs,
+        });
+    }
+
+    match output_format {
+        "json" => println!("{}", serde_json::to_string_pretty(&decisions)?),
+        "markdown" => println!("{}", heal_markdown(&decisions)),
+        _ => print_terminal_report(&decisions),
+    }
+
+    Ok(decisions)
+}
+
+fn action_label(action: HealAction) -> &'static str {
+    match action {
+        HealAction::Rerun => "🔁 re-run",
+        HealAction::WouldRerun => "🔁 would re-run",
+        HealAction::NotTransient => "❌ not transient",
+        HealAction::BudgetExhausted => "⛔ budget exhausted",
+        HealAction::Superseded => "⏭️ superseded",
+        HealAction::RerunFailed => "⚠️ re-run failed",
+    }
+}
+
+fn job_summary(job: &JobDiagnosis) -> String {
+    match job.cause {
+        Some(cause) => format!("{} ({})", job.job, cause),
+        None => format!("{} (permanent)", job.job),
+    }
+}
+
+/// Markdown table of heal decisions
+pub fn heal_markdown(decisions: &[HealDecision]) -> String {
+    let mut md = String::from("# 🩹 Heal Report\n\n");
+    if decisions.is_empty() {
+        md.push_str("No failed runs to heal.\n");
+        return md;
+    }
+
+    md.push_str("| Run | Workflow | Branch | Attempt | Action | Failed jobs |\n");
+    md.push_str("|-----|----------|--------|---------|--------|-------------|\n");
+    for d in decisions {
+        md.push_str(&format!(
+            "| #{} | {} | `{}` | {} | {} | {} |\n",
+            d.run_id,
+            d.workflow,
+            d.head_branch,
+            d.attempt,
+            action_label(d.action),
+            d.jobs.iter().map(job_summary).collect::<Vec<_>>().join(", "),
+        ));
+    }
+    md
+}
+
+fn print_terminal_report(decisions: &[HealDecision]) {
+    println!("\n🩹 Heal Report\n");
+    if decisions.is_empty() {
+        println!("No failed runs to heal.");
+        return;
+    }
+
+    for d in decisions {
+        println!("{} #{} {} ({}, attempt {})", action_label(d.action), d.run_id, d.workflow, d.head_branch, d.attempt);
+        for job in &d.jobs {
+            println!("   └─ {}", job_summary(job));
+            if let Some(evidence) = &job.evidence {
+                println!("      {}", evidence);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(log: &str, flaky: &[&str]) -> Option<TransientCause> {
+        let known: BTreeSet<String> = flaky.iter().map(|s| s.to_string()).collect();
+        classify_job(log, &parse_log(log), &known).map(|(cause, _)| cause)
+    }
+
+    #[test]
+    fn test_transient_causes_from_logs() {
+        assert_eq!(
+            classify("2025-03-03T10:00:00.0000000Z error: failed to download from `https://static.crates.io/crates/serde`\n\nCaused by:\n  [28] Timeout was reached\n", &[]),
+            Some(TransientCause::NetworkTimeout)
+        );
+        assert_eq!(
+            classify("2025-03-03T10:00:00.0000000Z curl: (28) Operation timed out after 30000 milliseconds\n##[error]Docker pull failed: connection timed out\n", &[]),
+            Some(TransientCause::NetworkTimeout)
+        );
+        assert_eq!(
+            classify("npm error code ETIMEDOUT\nnpm error network request failed\n", &[]),
+            Some(TransientCause::NetworkTimeout)
+        );
+        assert_eq!(
+            classify("##[error]The runner has received a shutdown signal.\n", &[]),
+            Some(TransientCause::RunnerLost)
+        );
+        assert_eq!(
+            classify("gh: API rate limit exceeded for installation ID 42. (HTTP 403)\n", &[]),
+            Some(TransientCause::RateLimit)
+        );
+    }
+
+    #[test]
+    fn test_permanent_failures_are_not_healed() {
+        // A compile error wins over network noise from a retried download
+        let log = "warning: spurious network error: Connection reset by peer\n\
+                   error[E0425]: cannot find value `x` in this scope\n --> src/lib.rs:3:5\n";
+        assert_eq!(classify(log, &[]), None);
+
+        let log = "test api::login ... FAILED\ntest api::logout ... FAILED\n";
+        assert_eq!(classify(log, &["api::login"]), None);
+        assert_eq!(classify(log, &["api::login", "api::logout"]), Some(TransientCause::FlakyTest));
+        assert_eq!(classify("##[error]Process completed with exit code 1.\n", &[]), None);
+
+        // Any compile error is permanent, codeless ones included
+        assert_eq!(classify("error: cannot find macro `vec2` in this scope\n --> src/lib.rs:1:1\n", &[]), None);
+
+        // Network noise outside the failing lines does not count
+        let log = "warning: spurious network error (2 tries remaining): Connection reset by peer\n\
+                   Retrying download after connection timed out\n\
+                   ##[error]Process completed with exit code 1.\n";
+        assert_eq!(classify(log, &[]), None);
+    }
+
+    #[tokio::test]
+    async fn test_single_run_is_fetched_and_rerun_errors_are_reported() {
+        use crate::cassette::{Cassette, CassetteServer};
+        use serde_json::json;
+
+        let run = json!({
+            "id": 400, "name": "CI", "status": "completed", "conclusion": "failure",
+            "html_url": "https://github.com/acme/widgets/actions/runs/400",
+            "created_at": "2025-03-05T10:00:00Z", "updated_at": "2025-03-05T10:00:00Z",
+            "run_attempt": 1, "workflow_id": 11, "head_branch": "main", "head_sha": "c400"
+        });
+        let interaction = |method: &str, path: &str, status: u16, body: serde_json::Value| json!({
+            "request": { "method": method, "path": path },
+            "response": { "status": status, "body": body },
+        });
+        let cassette: Cassette = serde_json::from_value(json!({ "interactions": [
+            interaction("GET", "/repos/acme/widgets/actions/runs/400", 200, run.clone()),
+            interaction("GET", "/repos/acme/widgets/actions/workflows/11/runs?branch=main&created=%3E%3D2025-03-05T10%3A00%3A00Z&per_page=100&page=1", 200, json!({ "total_count": 1, "workflow_runs": [run] })),
+            interaction("GET", "/repos/acme/widgets/issues?labels=flaky-test&state=open&per_page=100&page=1", 200, json!([])),
+            interaction("GET", "/repos/acme/widgets/actions/runs/400/jobs?per_page=100&page=1", 200, json!({ "total_count": 1, "jobs": [{
+                "id": 4001, "name": "test", "status": "completed", "conclusion": "failure",
+                "started_at": null, "completed_at": null, "steps": null
+            }] })),
+            {
+                "request": { "method": "GET", "path": "/repos/acme/widgets/actions/jobs/4001/logs" },
+                "response": { "status": 200, "body": "##[error]Docker pull failed: connection timed out\n", "content_type": "text/plain" },
+            },
+            interaction("POST", "/repos/acme/widgets/actions/runs/400/rerun-failed-jobs", 500, json!({ "message": "Server Error" })),
+        ] })).unwrap();
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("token", "acme/widgets", 2).with_base_url(server.url());
+
+        let decisions = run_heal(&client, &RunFilter::default(), None, Some(400), 2, false, "json").await.unwrap();
+
+        let actions: Vec<(u64, HealAction)> = decisions.iter().map(|d| (d.run_id, d.action)).collect();
+        assert_eq!(actions, vec![(400, HealAction::RerunFailed)]);
+        let requests = server.requests();
+        assert!(requests.contains(&"GET /repos/acme/widgets/actions/workflows/11/runs?branch=main&created=%3E%3D2025-03-05T10%3A00%3A00Z&per_page=100&page=1".to_string()));
+        assert!(!requests.iter().any(|r| r.starts_with("GET /repos/acme/widgets/actions/runs?")));
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+    }
+
+    #[test]
+    fn test_decide_respects_budget_and_permanent_jobs() {
+        let job = |cause| JobDiagnosis { job: "test".to_string(), job_id: 1, cause, evidence: None };
+        let transient = vec![job(Some(TransientCause::NetworkTimeout))];
+        let mixed = vec![job(Some(TransientCause::NetworkTimeout)), job(None)];
+
+        assert_eq!(decide(&transient, 0, 2, false), HealAction::Rerun);
+        assert_eq!(decide(&transient, 2, 2, false), HealAction::BudgetExhausted);
+        assert_eq!(decide(&mixed, 0, 2, false), HealAction::NotTransient);
+        assert_eq!(decide(&transient, 0, 2, true), HealAction::Superseded);
+    }
+}
@@ -0,0 +1,728 @@
+//! # Workflow History Store
+//!
+//! Persists analyzed workflow runs in a local SQLite database
+//! (`.gitcore/history.db` by default) so repeated `validate`/`analyze`/`health`
+//! invocations only fetch runs they have not seen yet. A run is analyzed again
+//! when its `run_attempt` or `updated_at` changed since it was stored (re-runs).
+//!
+//! ## Schema
+//!
+//! ```text
+//! runs     (id, workflow_id, workflow, head_branch, head_sha, conclusion,
+//!           created_at, run_attempt, duration_seconds)
+//! jobs     (id, run_id, name, conclusion, duration_seconds)
+//! failures (run_id, fingerprint, job, kind, message, file, line, test)
+//! fix_outcomes (rule_id, fingerprint, fixed, recorded_at)
+//! heal_attempts (run_id, workflow, attempt, jobs, causes, recorded_at)
+//! analyses (run_id, run_attempt, updated_at, body)   -- analysis as JSON, without raw job logs
+//! ```
+//!
+//! Only completed runs are stored; in-progress runs are fetched again later.
+//!
+//! ## Trends
+//!
+//! `report --trend` buckets runs by week and reports failure rate and p50/p95
+//! durations, plus week-over-week regressions per workflow.
+
+use crate::github::{GitHubClient, RunFilter, WorkflowAnalysis, WorkflowRun};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Default location of the history database
+pub const DEFAULT_HISTORY_PATH: &str = ".gitcore/history.db";
+
+/// Failure rate increase (percentage points) reported as a regression
+const FAILURE_RATE_REGRESSION: f64 = 0.10;
+/// Relative p95 duration increase reported as a regression
+const DURATION_REGRESSION: f64 = 0.20;
+/// Runs needed in both weeks before comparing them
+const MIN_RUNS_FOR_REGRESSION: usize = 3;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    workflow_id INTEGER NOT NULL,
+    workflow TEXT NOT NULL,
+    head_branch TEXT NOT NULL,
+    head_sha TEXT NOT NULL,
+    conclusion TEXT,
+    created_at TEXT NOT NULL,
+    run_attempt INTEGER NOT NULL,
+    duration_seconds INTEGER
+);
+CREATE INDEX IF NOT EXISTS runs_created_at ON runs (created_at);
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id INTEGER PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES runs (id),
+    name TEXT NOT NULL,
+    conclusion TEXT,
+    duration_seconds INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS failures (
+    run_id INTEGER NOT NULL REFERENCES runs (id),
+    fingerprint TEXT NOT NULL,
+    job TEXT,
+    kind TEXT NOT NULL,
+    message TEXT NOT NULL,
+    file TEXT,
+    line INTEGER,
+    test TEXT,
+    PRIMARY KEY (run_id, fingerprint)
+);
+
+CREATE TABLE IF NOT EXISTS fix_outcomes (
+    rule_id TEXT NOT NULL,
+    fingerprint TEXT,
+    fixed INTEGER NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS heal_attempts (
+    run_id INTEGER NOT NULL,
+    workflow TEXT NOT NULL,
+    attempt INTEGER NOT NULL,
+    jobs TEXT NOT NULL,
+    causes TEXT NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS analyses (
+    run_id INTEGER PRIMARY KEY REFERENCES runs (id),
+    run_attempt INTEGER NOT NULL,
+    updated_at TEXT NOT NULL,
+    body TEXT NOT NULL
+);
+";
+
+/// A stored run reduced to what trend queries need
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSample {
+    pub workflow: String,
+    pub created_at: DateTime<Utc>,
+    pub failed: bool,
+    pub duration_seconds: Option<i64>,
+}
+
+/// Aggregates for one week (oldest week first in reports)
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekTrend {
+    pub week_start: String,
+    pub runs: usize,
+    pub failed: usize,
+    pub failure_rate: f64,
+    pub p50_seconds: Option<i64>,
+    pub p95_seconds: Option<i64>,
+}
+
+/// A workflow that got worse this week compared to last week
+#[derive(Debug, Clone, Serialize)]
+pub struct Regression {
+    pub workflow: String,
+    pub metric: String,
+    pub previous: f64,
+    pub current: f64,
+}
+
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+impl HistoryStore {
+    /// Open (or create) the database at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open history database {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Store a completed run with its jobs and parsed failures (idempotent)
+    pub fn record(&mut self, analysis: &WorkflowAnalysis) -> Result<()> {
+        let run = &analysis.run;
+        let tx = self.conn.transaction()?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO runs
+             (id, workflow_id, workflow, head_branch, head_sha, conclusion, created_at, run_attempt, duration_seconds)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                run.id,
+                run.workflow_id,
+                run.name,
+                run.head_branch,
+                run.head_sha,
+                run.conclusion,
+                run.created_at,
+                run.run_attempt,
+                analysis.duration_seconds,
+            ],
+        )?;
+
+        tx.execute("DELETE FROM jobs WHERE run_id = ?1", params![run.id])?;
+        for job in &analysis.jobs {
+            let duration = match (&job.started_at, &job.completed_at) {
+                (Some(start), Some(end)) => seconds_between(start, end),
+                _ => None,
+            };
+            tx.execute(
+                "INSERT OR REPLACE INTO jobs (id, run_id, name, conclusion, duration_seconds)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![job.id, run.id, job.name, job.conclusion, duration],
+            )?;
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO analyses (run_id, run_attempt, updated_at, body) VALUES (?1, ?2, ?3, ?4)",
+            params![run.id, run.run_attempt, run.updated_at, serde_json::to_string(analysis)?],
+        )?;
+
+        tx.execute("DELETE FROM failures WHERE run_id = ?1", params![run.id])?;
+        for failure in &analysis.failures {
+            tx.execute(
+                "INSERT OR IGNORE INTO failures (run_id, fingerprint, job, kind, message, file, line, test)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    run.id,
+                    failure.fingerprint,
+                    failure.job,
+                    failure.kind.to_string(),
+                    failure.message,
+                    failure.file,
+                    failure.line,
+                    failure.test,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stored analysis of `run`, unless the run was re-run or updated since
+    pub fn analysis(&self, run: &WorkflowRun) -> Result<Option<WorkflowAnalysis>> {
+        let body: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT body FROM analyses WHERE run_id = ?1 AND run_attempt = ?2 AND updated_at = ?3",
+                params![run.id, run.run_attempt, run.updated_at],
+                |row| row.get(0),
+            )
+            .optional()?;
+        body.map(|b| serde_json::from_str(&b).with_context(|| format!("Corrupt stored analysis of run {}", run.id)))
+            .transpose()
+    }
+
+    /// Creation time of the newest stored run
+    pub fn latest_created_at(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT MAX(created_at) FROM runs", [], |row| row.get(0))?)
+    }
+
+    /// Runs created at or after `since`
+    pub fn samples_since(&self, since: DateTime<Utc>) -> Result<Vec<RunSample>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT workflow, created_at, conclusion, duration_seconds
+             FROM runs WHERE created_at >= ?1 ORDER BY created_at",
+        )?;
+
+        let rows = stmt.query_map(params![since.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+            ))
+        })?;
+
+        let mut samples = Vec::new();
+        for row in rows {
+            let (workflow, created_at, conclusion, duration_seconds) = row?;
+            let Ok(created_at) = DateTime::parse_from_rfc3339(&created_at) else {
+                continue;
+            };
+            samples.push(RunSample {
+                workflow,
+                created_at: created_at.with_timezone(&Utc),
+                failed: matches!(conclusion.as_deref(), Some("failure") | Some("timed_out")),
+                duration_seconds,
+            });
+        }
+        Ok(samples)
+    }
+
+    /// Most frequent failure fingerprints since `since`: (fingerprint, message, runs)
+    pub fn top_failures_since(&self, since: DateTime<Utc>, limit: usize) -> Result<Vec<(String, String, u32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT f.fingerprint, MIN(f.message), COUNT(DISTINCT f.run_id) AS n
+             FROM failures f JOIN runs r ON r.id = f.run_id
+             WHERE r.created_at >= ?1
+             GROUP BY f.fingerprint ORDER BY n DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(
+            params![since.to_rfc3339_opts(chrono::SecondsFormat::Secs, true), limit as i64],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Record whether applying a fix rule resolved a failure
+    pub fn record_fix_outcome(&self, rule_id: &str, fingerprint: Option<&str>, fixed: bool) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO fix_outcomes (rule_id, fingerprint, fixed, recorded_at) VALUES (?1, ?2, ?3, ?4)",
+            params![rule_id, fingerprint, fixed, Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)],
+        )?;
+        Ok(())
+    }
+
+    /// Record a re-run of the failed jobs of `run_id`, triggered from `attempt`
+    pub fn record_heal_attempt(&self, run_id: u64, workflow: &str, attempt: u32, jobs: &[&str], causes: &[&str]) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO heal_attempts (run_id, workflow, attempt, jobs, causes, recorded_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run_id,
+                workflow,
+                attempt,
+                jobs.join(","),
+                causes.join(","),
+                Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Number of heal re-runs recorded for a run
+    pub fn heal_attempts(&self, run_id: u64) -> Result<u32> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*) FROM heal_attempts WHERE run_id = ?1",
+            params![run_id],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Recorded outcomes per fix rule: (rule_id, fixed, total)
+    pub fn fix_outcomes(&self) -> Result<Vec<(String, u32, u32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT rule_id, SUM(fixed), COUNT(*) FROM fix_outcomes GROUP BY rule_id ORDER BY rule_id",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+}
+
+/// Fetch and store runs not yet in the store (or changed since); returns the number of stored runs
+pub async fn sync(client: &GitHubClient, store: &mut HistoryStore) -> Result<usize> {
+    // Look back a day so runs still in progress at the last sync are picked up
+    let since = store
+        .latest_created_at()?
+        .and_then(|latest| DateTime::parse_from_rfc3339(&latest).ok())
+        .map(|latest| latest.with_timezone(&Utc) - Duration::days(1));
+
+    let filter = match since {
+        Some(since) => RunFilter::default().created_since(since),
+        None => RunFilter::default().with_limit(100),
+    };
+    let runs = client.collect_runs(&filter).await?;
+
+    let mut new_runs = Vec::new();
+    for run in runs {
+        if run.status == "completed" && store.analysis(&run)?.is_none() {
+            new_runs.push(run);
+        }
+    }
+
+    if new_runs.is_empty() {
+        info!("🗄️ History up to date");
+        return Ok(0);
+    }
+
+    let analyses = client.analyze_runs_parallel(new_runs).await?;
+    for analysis in &analyses {
+        store.record(analysis)?;
+    }
+
+    info!("🗄️ Stored {} new run(s) in history", analyses.len());
+    Ok(analyses.len())
+}
+
+/// Analyze `runs`, reusing stored analyses of completed runs that did not change
+///
+/// Keeps the order of `runs`; runs that fail to analyze are dropped as in
+/// [`GitHubClient::analyze_runs_parallel`].
+pub async fn analyze_runs(
+    client: &GitHubClient,
+    store: Option<&HistoryStore>,
+    runs: Vec<WorkflowRun>,
+) -> Result<Vec<WorkflowAnalysis>> {
+    let order: Vec<u64> = runs.iter().map(|r| r.id).collect();
+    let mut analyses = Vec::new();
+    let mut missing = Vec::new();
+    for run in runs {
+        let stored = match store {
+            Some(store) if run.status == "completed" => store.analysis(&run)?,
+            _ => None,
+        };
+        match stored {
+            Some(analysis) => analyses.push(analysis),
+            None => missing.push(run),
+        }
+    }
+
+    if !analyses.is_empty() {
+        info!("🗄️ Reusing {} stored analyses", analyses.len());
+    }
+    if !missing.is_empty() {
+        analyses.extend(client.analyze_runs_parallel(missing).await?);
+    }
+    analyses.sort_by_key(|a| order.iter().position(|id| *id == a.run.id));
+    Ok(analyses)
+}
+
+/// Open the store and sync it, warning instead of failing when either step fails
+pub async fn open_synced<P: AsRef<Path>>(client: &GitHubClient, path: P) -> Option<HistoryStore> {
+    let path = path.as_ref();
+    let mut store = match HistoryStore::open(path) {
+        Ok(store) => store,
+        Err(e) => {
+            warn!("⚠️ History disabled: {:#}", e);
+            return None;
+        }
+    };
+    if let Err(e) = sync(client, &mut store).await {
+        warn!("⚠️ History sync failed, using stored runs only: {:#}", e);
+    }
+    Some(store)
+}
+
+/// Nearest-rank percentile of an ascending slice
+pub fn percentile(sorted: &[i64], p: f64) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Bucket samples into the last `weeks` weeks ending at `now`, oldest first
+pub fn weekly_trends(samples: &[RunSample], weeks: usize, now: DateTime<Utc>) -> Vec<WeekTrend> {
+    (0..weeks)
+        .rev()
+        .map(|week| {
+            let end = now - Duration::weeks(week as i64);
+            let start = end - Duration::weeks(1);
+            let in_week: Vec<&RunSample> = samples
+                .iter()
+                .filter(|s| s.created_at > start && s.created_at <= end)
+                .collect();
+            summarize(start, &in_week)
+        })
+        .collect()
+}
+
+fn summarize(start: DateTime<Utc>, samples: &[&RunSample]) -> WeekTrend {
+    let runs = samples.len();
+    let failed = samples.iter().filter(|s| s.failed).count();
+    let mut durations: Vec<i64> = samples.iter().filter_map(|s| s.duration_seconds).collect();
+    durations.sort_unstable();
+
+    WeekTrend {
+        week_start: start.format("%Y-%m-%d").to_string(),
+        runs,
+        failed,
+        failure_rate: if runs > 0 { failed as f64 / runs as f64 } else { 0.0 },
+        p50_seconds: percentile(&durations, 50.0),
+        p95_seconds: percentile(&durations, 95.0),
+    }
+}
+
+/// Per-workflow regressions of the last week against the week before
+pub fn regressions(samples: &[RunSample], now: DateTime<Utc>) -> Vec<Regression> {
+    let mut by_workflow: BTreeMap<&str, Vec<&RunSample>> = BTreeMap::new();
+    for sample in samples {
+        by_workflow.entry(sample.workflow.as_str()).or_default().push(sample);
+    }
+
+    let week_ago = now - Duration::weeks(1);
+    let two_weeks_ago = now - Duration::weeks(2);
+    let mut found = Vec::new();
+
+    for (workflow, runs) in by_workflow {
+        let current: Vec<&RunSample> = runs.iter().copied().filter(|s| s.created_at > week_ago).collect();
+        let previous: Vec<&RunSample> = runs
+            .iter()
+            .copied()
+            .filter(|s| s.created_at > two_weeks_ago && s.created_at <= week_ago)
+            .collect();
+
+        if current.len() < MIN_RUNS_FOR_REGRESSION || previous.len() < MIN_RUNS_FOR_REGRESSION {
+            continue;
+        }
+
+        let cur = summarize(week_ago, &current);
+        let prev = summarize(two_weeks_ago, &previous);
+
+        if cur.failure_rate - prev.failure_rate >= FAILURE_RATE_REGRESSION {
+            found.push(Regression {
+                workflow: workflow.to_string(),
+                metric: "failure_rate".to_string(),
+                previous: prev.failure_rate,
+                current: cur.failure_rate,
+            });
+        }
+
+        if let (Some(p), Some(c)) = (prev.p95_seconds, cur.p95_seconds) {
+            if p > 0 && (c - p) as f64 / p as f64 >= DURATION_REGRESSION {
+                found.push(Regression {
+                    workflow: workflow.to_string(),
+                    metric: "p95_seconds".to_string(),
+                    previous: p as f64,
+                    current: c as f64,
+                });
+            }
+        }
+    }
+
+    found
+}
+
+/// Sync, then print weekly trends and regressions
+pub async fn run_trend_report(
+    client: &GitHubClient,
+    store: &mut HistoryStore,
+    weeks: usize,
+    output_format: &str,
+) -> Result<()> {
+    if let Err(e) = sync(client, store).await {
+        warn!("⚠️ History sync failed, reporting stored runs only: {:#}", e);
+    }
+
+    let now = Utc::now();
+    let samples = store.samples_since(now - Duration::weeks(weeks.max(2) as i64))?;
+    let trends = weekly_trends(&samples, weeks, now);
+    let regressions = regressions(&samples, now);
+    let top_failures = store.top_failures_since(now - Duration::weeks(1), 5)?;
+
+    match output_format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                "weeks": trends,
+                "regressions": regressions,
+                "top_failures": top_failures.iter().map(|(fingerprint, message, runs)| serde_json::json!({
+                    "fingerprint": fingerprint,
+                    "message": message,
+                    "runs": runs,
+                })).collect::<Vec<_>>(),
+            }))?);
+        }
+        "markdown" => {
+            println!("# 📈 Workflow Trends\n");
+            println!("| Week | Runs | Failed | Failure Rate | p50 | p95 |");
+            println!("|------|------|--------|--------------|-----|-----|");
+            for week in &trends {
+                println!(
+                    "| {} | {} | {} | {:.1}% | {} | {} |",
+                    week.week_start,
+                    week.runs,
+                    week.failed,
+                    week.failure_rate * 100.0,
+                    fmt_seconds(week.p50_seconds),
+                    fmt_seconds(week.p95_seconds)
+                );
+            }
+            if !regressions.is_empty() {
+                println!("\n## ⚠️ Regressions\n");
+                for r in &regressions {
+                    println!("- **{}** {}: {:.2} → {:.2}", r.workflow, r.metric, r.previous, r.current);
+                }
+            }
+        }
+        _ => {
+            println!("\n📈 Workflow Trends ({} weeks)\n", weeks);
+            println!("{:<12} {:>6} {:>7} {:>9} {:>8} {:>8}", "Week", "Runs", "Failed", "Fail %", "p50", "p95");
+            println!("{}", "-".repeat(55));
+            for week in &trends {
+                println!(
+                    "{:<12} {:>6} {:>7} {:>8.1}% {:>8} {:>8}",
+                    week.week_start,
+                    week.runs,
+                    week.failed,
+                    week.failure_rate * 100.0,
+                    fmt_seconds(week.p50_seconds),
+                    fmt_seconds(week.p95_seconds)
+                );
+            }
+            for r in &regressions {
+                println!("⚠️  {} {} regressed: {:.2} → {:.2}", r.workflow, r.metric, r.previous, r.current);
+            }
+            for (_, message, runs) in &top_failures {
+                println!("🔴 {}x {}", runs, message);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn seconds_between(start: &str, end: &str) -> Option<i64> {
+    let start = DateTime::parse_from_rfc3339(start).ok()?;
+    let end = DateTime::parse_from_rfc3339(end).ok()?;
+    Some((end - start).num_seconds())
+}
+
+fn fmt_seconds(seconds: Option<i64>) -> String {
+    seconds.map(|s| format!("{}s", s)).unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cassette::{Cassette, CassetteServer};
+    use crate::log_parser::parse_log;
+
+    fn analysis(id: u64, created_at: &str, conclusion: &str, duration: i64) -> WorkflowAnalysis {
+        WorkflowAnalysis {
+            run: WorkflowRun {
+                id,
+                name: "CI".to_string(),
+                status: "completed".to_string(),
+                conclusion: Some(conclusion.to_string()),
+                html_url: String::new(),
+                created_at: created_at.to_string(),
+                updated_at: created_at.to_string(),
+                run_attempt: 1,
+                workflow_id: 1,
+                head_branch: "main".to_string(),
+                head_sha: format!("sha{}", id),
+                path: String::new(),
+                event: "push".to_string(),
+            },
+            jobs: Vec::new(),
+            job_logs: Default::default(),
+            errors: Vec::new(),
+            failures: parse_log("test api::login ... FAILED\n"),
+            warnings: Vec::new(),
+            duration_seconds: Some(duration),
+        }
+    }
+
+    fn sample(days_ago: i64, failed: bool, duration: i64, now: DateTime<Utc>) -> RunSample {
+        RunSample {
+            workflow: "CI".to_string(),
+            created_at: now - Duration::days(days_ago),
+            failed,
+            duration_seconds: Some(duration),
+        }
+    }
+
+    #[test]
+    fn test_record_is_idempotent_and_queryable() {
+        let mut store = HistoryStore::open_in_memory().unwrap();
+        store.record(&analysis(1, "2024-05-01T10:00:00Z", "success", 60)).unwrap();
+        store.record(&analysis(2, "2024-05-02T10:00:00Z", "failure", 90)).unwrap();
+        store.record(&analysis(2, "2024-05-02T10:00:00Z", "failure", 90)).unwrap();
+
+        assert!(store.analysis(&analysis(2, "2024-05-02T10:00:00Z", "failure", 90).run).unwrap().is_some());
+        assert!(store.analysis(&analysis(3, "2024-05-02T10:00:00Z", "failure", 90).run).unwrap().is_none());
+        assert_eq!(store.latest_created_at().unwrap().as_deref(), Some("2024-05-02T10:00:00Z"));
+
+        let since = DateTime::parse_from_rfc3339("2024-04-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let samples = store.samples_since(since).unwrap();
+        assert_eq!(samples.len(), 2);
+        assert!(samples[1].failed);
+
+        let top = store.top_failures_since(since, 5).unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].2, 2);
+    }
+
+    #[test]
+    fn test_stored_analysis_invalidated_by_rerun() {
+        let mut store = HistoryStore::open_in_memory().unwrap();
+        let mut stored = analysis(1, "2024-05-01T10:00:00Z", "failure", 60);
+        stored.job_logs.insert(10, "raw job log".repeat(1000));
+        store.record(&stored).unwrap();
+
+        let found = store.analysis(&stored.run).unwrap().unwrap();
+        assert_eq!(found.failures, stored.failures);
+        // Raw logs are not stored
+        assert!(found.job_logs.is_empty());
+
+        let mut rerun = stored.run.clone();
+        rerun.run_attempt = 2;
+        assert!(store.analysis(&rerun).unwrap().is_none());
+
+        let mut updated = stored.run.clone();
+        updated.updated_at = "2024-05-01T11:00:00Z".to_string();
+        assert!(store.analysis(&updated).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_analyze_runs_reuses_stored_analyses() {
+        let server = CassetteServer::replay(Cassette::default()).await.unwrap();
+        let client = GitHubClient::new("token", "acme/widgets", 2).with_base_url(server.url());
+        let mut store = HistoryStore::open_in_memory().unwrap();
+        let first = analysis(1, "2024-05-01T10:00:00Z", "success", 60);
+        let second = analysis(2, "2024-05-02T10:00:00Z", "failure", 90);
+        store.record(&second).unwrap();
+        store.record(&first).unwrap();
+
+        let analyses = analyze_runs(&client, Some(&store), vec![second.run.clone(), first.run.clone()]).await.unwrap();
+        let ids: Vec<u64> = analyses.iter().map(|a| a.run.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert!(server.requests().is_empty(), "unexpected requests: {:?}", server.requests());
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let durations = [10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+        assert_eq!(percentile(&durations, 50.0), Some(50));
+        assert_eq!(percentile(&durations, 95.0), Some(100));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_weekly_trends_buckets() {
+        let now = Utc::now();
+        let samples = vec![
+            sample(1, false, 60, now),
+            sample(2, true, 120, now),
+            sample(9, false, 30, now),
+        ];
+
+        let trends = weekly_trends(&samples, 2, now);
+        assert_eq!(trends.len(), 2);
+        assert_eq!(trends[0].runs, 1);
+        assert_eq!(trends[1].runs, 2);
+        assert_eq!(trends[1].failure_rate, 0.5);
+    }
+
+    #[test]
+    fn test_regressions_week_over_week() {
+        let now = Utc::now();
+        let mut samples: Vec<RunSample> = (8..11).map(|d| sample(d, false, 100, now)).collect();
+        samples.extend((1..4).map(|d| sample(d, d == 1, 200, now)));
+
+        let found = regressions(&samples, now);
+        let metrics: Vec<&str> = found.iter().map(|r| r.metric.as_str()).collect();
+        assert_eq!(metrics, vec!["failure_rate", "p95_seconds"]);
+    }
+}
@@ -2,10 +2,26 @@
 //!
 //! Public API for testing and external usage
 
+pub mod codeowners;
 pub mod guardian_core;
+pub mod guardian_report;
+pub mod guardian_local;
+pub mod guardian_policy;
+pub mod guardian_watch;
 pub mod dispatcher_core;
 pub mod github;
+pub mod cassette;
+pub mod ci_run;
+pub mod log_parser;
 pub mod analyzer;
+pub mod critical_path;
+pub mod cost;
+pub mod dashboard;
+pub mod flaky;
+pub mod heal;
+pub mod fix_rules;
+pub mod history;
+pub mod workflow_lint;
 pub mod validator;
 pub mod reporter;
 pub mod parallel;
@@ -0,0 +1,306 @@
+//! # CI Log Parser
+//!
+//! Extracts structured failures from GitHub Actions job logs and turns them
+//! into stable fingerprints, so the same failure is recognised across runs.
+//!
+//! ## Recognised Failures
+//!
+//! - rustc/cargo errors (`error[E0308]: ...` with the following `--> file:line`)
+//! - Failing tests from `cargo test`, pytest and jest
+//! - npm errors (`npm ERR! code ...` / `npm error code ...`)
+//! - Process exit codes (`##[error]Process completed with exit code N.`)
+//!
+//! ## Fingerprints
+//!
+//! Fingerprints hash the failure kind, file and a normalized message: line
+//! numbers, numbers, hex ids and temp paths are masked, so the fingerprint
+//! survives unrelated edits that shift code around.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Category of a parsed failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    CompileError,
+    TestFailure,
+    NpmError,
+    ExitCode,
+}
+
+impl std::fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FailureKind::CompileError => "compile",
+            FailureKind::TestFailure => "test",
+            FailureKind::NpmError => "npm",
+            FailureKind::ExitCode => "exit",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A single failure extracted from a job log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogFailure {
+    pub kind: FailureKind,
+    /// Error code (`E0308`, `ELIFECYCLE`, exit status)
+    pub code: Option<String>,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    /// Fully qualified test name for test failures
+    pub test: Option<String>,
+    /// Job whose log contained the failure (not part of the fingerprint)
+    pub job: Option<String>,
+    pub fingerprint: String,
+}
+
+impl LogFailure {
+    fn new(kind: FailureKind, code: Option<String>, message: String) -> Self {
+        let mut failure = Self {
+            kind,
+            code,
+            message,
+            file: None,
+            line: None,
+            test: None,
+            job: None,
+            fingerprint: String::new(),
+        };
+        failure.fingerprint = fingerprint(&failure);
+        failure
+    }
+
+    fn at(mut self, file: String, line: Option<u32>) -> Self {
+        self.file = Some(file);
+        self.line = line;
+        self.fingerprint = fingerprint(&self);
+        self
+    }
+
+    fn for_test(mut self, test: String) -> Self {
+        self.test = Some(test);
+        self.fingerprint = fingerprint(&self);
+        self
+    }
+
+    /// Attribute the failure to the job whose log it came from
+    pub fn in_job(mut self, job: &str) -> Self {
+        self.job = Some(job.to_string());
+        self
+    }
+
+    /// One-line description, e.g. `compile [E0308] mismatched types (src/lib.rs:10)`
+    pub fn summary(&self) -> String {
+        let mut summary = match &self.code {
+            Some(code) => format!("{} [{}] {}", self.kind, code, self.message),
+            None => format!("{} {}", self.kind, self.message),
+        };
+        if let Some(file) = &self.file {
+            match self.line {
+                Some(line) => summary.push_str(&format!(" ({}:{})", file, line)),
+                None => summary.push_str(&format!(" ({})", file)),
+            }
+        }
+        summary
+    }
+}
+
+/// Codeless `error:` messages cargo prints when it cannot fetch dependencies
+const CARGO_FETCH_ERRORS: &[&str] = &[
+    "failed to download",
+    "failed to get `",
+    "failed to fetch",
+    "failed to load source for dependency",
+    "failed to update registry",
+];
+
+struct Patterns {
+    timestamp: Regex,
+    ansi: Regex,
+    rust_error: Regex,
+    rust_location: Regex,
+    cargo_test: Regex,
+    pytest: Regex,
+    jest: Regex,
+    npm: Regex,
+    exit_code: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        timestamp: Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?Z ").unwrap(),
+        ansi: Regex::new(r"\x1b\[[0-9;]*m").unwrap(),
+        rust_error: Regex::new(r"^error(?:\[(E\d{4})\])?: (.+)$").unwrap(),
+        rust_location: Regex::new(r"^\s*--> ([^:\s]+):(\d+)(?::\d+)?").unwrap(),
+        cargo_test: Regex::new(r"^test (\S+) \.\.\. FAILED$").unwrap(),
+        pytest: Regex::new(r"^FAILED ([^:\s]+)::(\S+)(?: - (.+))?$").unwrap(),
+        jest: Regex::new(r"^\s*● (.+ › .+)$").unwrap(),
+        npm: Regex::new(r"^npm (?:ERR!|error) code (\S+)").unwrap(),
+        exit_code: Regex::new(r"^##\[error\]Process completed with exit code (\d+)").unwrap(),
+    })
+}
+
+/// Strip the GitHub Actions timestamp prefix and ANSI colour codes
+pub fn clean_line(line: &str) -> String {
+    let p = patterns();
+    let line = p.timestamp.replace(line, "");
+    p.ansi.replace_all(&line, "").trim_end().to_string()
+}
+
+/// Extract all recognised failures from a job log, deduplicated by fingerprint
+pub fn parse_log(log: &str) -> Vec<LogFailure> {
+    let p = patterns();
+    let lines: Vec<String> = log.lines().map(clean_line).collect();
+    let mut failures: Vec<LogFailure> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let failure = if let Some(caps) = p.rust_error.captures(line) {
+            let message = caps[2].to_string();
+            // Summary lines such as "could not compile `x`" repeat earlier errors
+            if message.starts_with("could not compile") || message.starts_with("aborting due to") {
+                continue;
+            }
+            // Cargo failing to fetch dependencies is not a compile error; the exit code covers it
+            if CARGO_FETCH_ERRORS.iter().any(|prefix| message.starts_with(prefix)) {
+                continue;
+            }
+            let failure = LogFailure::new(FailureKind::CompileError, caps.get(1).map(|m| m.as_str().to_string()), message);
+            match lines.iter().skip(i + 1).take(3).find_map(|l| p.rust_location.captures(l)) {
+                Some(loc) => failure.at(loc[1].to_string(), loc[2].parse().ok()),
+                None => failure,
+            }
+        } else if let Some(caps) = p.cargo_test.captures(line) {
+            LogFailure::new(FailureKind::TestFailure, None, "cargo test failed".to_string())
+                .for_test(caps[1].to_string())
+        } else if let Some(caps) = p.pytest.captures(line) {
+            let message = caps.get(3).map(|m| m.as_str().to_string()).unwrap_or_else(|| "pytest failed".to_string());
+            LogFailure::new(FailureKind::TestFailure, None, message)
+                .for_test(caps[2].to_string())
+                .at(caps[1].to_string(), None)
+        } else if let Some(caps) = p.jest.captures(line) {
+            LogFailure::new(FailureKind::TestFailure, None, "jest test failed".to_string())
+                .for_test(caps[1].to_string())
+        } else if let Some(caps) = p.npm.captures(line) {
+            LogFailure::new(FailureKind::NpmError, Some(caps[1].to_string()), line.clone())
+        } else if let Some(caps) = p.exit_code.captures(line) {
+            LogFailure::new(
+                FailureKind::ExitCode,
+                Some(caps[1].to_string()),
+                format!("process exited with code {}", &caps[1]),
+            )
+        } else {
+            continue;
+        };
+
+        if !failures.iter().any(|f| f.fingerprint == failure.fingerprint) {
+            failures.push(failure);
+        }
+    }
+
+    failures
+}
+
+/// Mask run-specific details (numbers, hex ids, temp paths) in a message
+pub fn normalize_message(message: &str) -> String {
+    static MASKS: OnceLock<[(Regex, &'static str); 3]> = OnceLock::new();
+    let masks = MASKS.get_or_init(|| {
+        [
+            (Regex::new(r"(/tmp|/home/runner|[a-z]:\\)\S*").unwrap(), "<path>"),
+            (Regex::new(r"\b[0-9a-f]{7,40}\b").unwrap(), "<hex>"),
+            (Regex::new(r"\d+").unwrap(), "<n>"),
+        ]
+    });
+
+    let mut normalized = message.trim().to_lowercase();
+    for (regex, replacement) in masks {
+        normalized = regex.replace_all(&normalized, *replacement).into_owned();
+    }
+    normalized
+}
+
+/// Stable fingerprint of a failure (FNV-1a over kind, code, file, test and message)
+fn fingerprint(failure: &LogFailure) -> String {
+    let key = format!(
+        "{}|{}|{}|{}|{}",
+        failure.kind,
+        failure.code.as_deref().unwrap_or(""),
+        failure.file.as_deref().unwrap_or(""),
+        failure.test.as_deref().unwrap_or(""),
+        normalize_message(&failure.message),
+    );
+
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_LOG: &str = "\
+2024-05-01T10:00:00.1234567Z    Compiling gc-core v0.1.0
+2024-05-01T10:00:01.1234567Z \x1b[1merror[E0308]\x1b[0m: mismatched types
+2024-05-01T10:00:01.1234567Z   --> crates/gc-core/src/lib.rs:42:9
+2024-05-01T10:00:01.2234567Z error: could not compile `gc-core` (lib) due to 1 previous error
+2024-05-01T10:00:02.1234567Z ##[error]Process completed with exit code 101.
+";
+
+    #[test]
+    fn test_parse_rustc_error_with_location() {
+        let failures = parse_log(CARGO_LOG);
+        assert_eq!(failures.len(), 2);
+
+        let compile = &failures[0];
+        assert_eq!(compile.kind, FailureKind::CompileError);
+        assert_eq!(compile.code.as_deref(), Some("E0308"));
+        assert_eq!(compile.file.as_deref(), Some("crates/gc-core/src/lib.rs"));
+        assert_eq!(compile.line, Some(42));
+
+        assert_eq!(failures[1].kind, FailureKind::ExitCode);
+        assert_eq!(failures[1].code.as_deref(), Some("101"));
+    }
+
+    #[test]
+    fn test_parse_test_failures() {
+        let log = "\
+test guardian_core::tests::test_blocked ... FAILED
+test guardian_core::tests::test_ok ... ok
+FAILED tests/test_api.py::test_login - AssertionError: 401 != 200
+  ● Dashboard › renders totals
+npm ERR! code ELIFECYCLE
+";
+        let failures = parse_log(log);
+        let tests: Vec<_> = failures.iter().filter_map(|f| f.test.as_deref()).collect();
+        assert_eq!(tests, vec!["guardian_core::tests::test_blocked", "test_login", "Dashboard › renders totals"]);
+        assert_eq!(failures[1].file.as_deref(), Some("tests/test_api.py"));
+        assert_eq!(failures[3].kind, FailureKind::NpmError);
+        assert_eq!(failures[3].code.as_deref(), Some("ELIFECYCLE"));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_line_shifts_and_timestamps() {
+        let other_run = CARGO_LOG
+            .replace("2024-05-01", "2024-06-30")
+            .replace("lib.rs:42:9", "lib.rs:57:13");
+
+        let a = parse_log(CARGO_LOG);
+        let b = parse_log(&other_run);
+        assert_eq!(a[0].fingerprint, b[0].fingerprint);
+        assert_ne!(a[0].fingerprint, a[1].fingerprint);
+    }
+
+    #[test]
+    fn test_normalize_message() {
+        assert_eq!(
+            normalize_message("Timeout after 3000ms in /tmp/build-8f3a/out.log (abc1234f)"),
+            "timeout after <n>ms in <path> (<hex>)"
+        );
+    }
+}
@@ -5,16 +5,31 @@
 //! with maximum parallelism using Tokio.
 
 use clap::{Parser, Subcommand};
-use anyhow::Result;
-use tracing::{info, Level};
+use anyhow::{Context, Result};
+use tracing::{info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
 
 mod github;
+mod cassette;
+mod log_parser;
 mod analyzer;
+mod critical_path;
+mod cost;
+mod dashboard;
+mod flaky;
+mod heal;
+mod fix_rules;
+mod history;
+mod workflow_lint;
 mod validator;
 mod reporter;
 mod parallel;
+mod codeowners;
 mod guardian_core;
+mod guardian_report;
+mod guardian_local;
+mod guardian_policy;
+mod guardian_watch;
 mod dispatcher_core;
 
 #[derive(Parser, Debug)]
@@ -41,13 +56,57 @@ struct Cli {
     #[arg(short, long, default_value = "false")]
     verbose: bool,
 
-    /// Output format (json, markdown, terminal)
+    /// Output format (json, markdown, terminal; sarif and junit for analyze and lint, html for report)
     #[arg(short, long, default_value = "terminal")]
     output: String,
 
     /// Maximum parallel tasks
     #[arg(long, default_value = "10")]
     max_parallel: usize,
+
+    /// GitHub API base URL (GHES or a local stub server)
+    #[arg(long, env = "GITHUB_API_URL", default_value = github::DEFAULT_API_URL)]
+    api_url: String,
+
+    /// Replay GitHub API responses from a cassette file instead of calling the API
+    #[arg(long)]
+    cassette: Option<std::path::PathBuf>,
+
+    /// With --cassette: call the API and record its responses into the cassette
+    #[arg(long, default_value = "false", requires = "cassette")]
+    record: bool,
+
+    /// Local history database for analyzed runs
+    #[arg(long, default_value = history::DEFAULT_HISTORY_PATH)]
+    history_db: String,
+
+    /// Do not read or write the history database
+    #[arg(long, default_value = "false")]
+    no_history: bool,
+
+    /// Only runs of this workflow (file name or ID)
+    #[arg(long)]
+    workflow: Option<String>,
+
+    /// Only runs on this branch
+    #[arg(long)]
+    branch: Option<String>,
+
+    /// Only runs triggered by this event (push, pull_request, schedule, ...)
+    #[arg(long)]
+    event: Option<String>,
+
+    /// Only runs triggered by this user
+    #[arg(long)]
+    actor: Option<String>,
+
+    /// Only runs with this status or conclusion (completed, failure, ...)
+    #[arg(long)]
+    status: Option<String>,
+
+    /// Only runs created in this range (`>=2024-05-01`, `2024-05-01..2024-05-07`)
+    #[arg(long)]
+    created: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -98,6 +157,78 @@ enum Commands {
         /// Time range in hours
         #[arg(long, default_value = "168")]
         hours: u64,
+
+        /// Weekly trends (failure rate, p50/p95 durations) from the history database
+        #[arg(long, default_value = "false")]
+        trend: bool,
+
+        /// Number of weeks for --trend
+        #[arg(long, default_value = "8")]
+        weeks: usize,
+
+        /// Write the HTML dashboard (--output html) to a file instead of stdout
+        #[arg(long)]
+        file: Option<String>,
+    },
+
+    /// Detect flaky jobs and tests (passed and failed on the same SHA)
+    Flaky {
+        /// Time window in hours
+        #[arg(long, default_value = "168")]
+        hours: u64,
+
+        /// Open or update a tracking issue per flaky test
+        #[arg(long, default_value = "false")]
+        create_issues: bool,
+    },
+
+    /// Billable CI minutes per workflow, job and runner OS, projected against a budget
+    ///
+    /// Exits with status 2 when the projection is over budget (1 means the report failed).
+    Cost {
+        /// Time window in hours
+        #[arg(long, default_value = "168")]
+        hours: u64,
+
+        /// Monthly budget in billable minutes (0 for unlimited)
+        #[arg(long, env = "CI_MINUTES_BUDGET", default_value_t = cost::DEFAULT_BUDGET_MINUTES)]
+        budget: u64,
+    },
+
+    /// Re-run failed jobs of runs that failed for transient reasons
+    Heal {
+        /// Heal a single run instead of all failed runs in the window
+        #[arg(long)]
+        run_id: Option<u64>,
+
+        /// Time window in hours
+        #[arg(long, default_value = "24")]
+        hours: u64,
+
+        /// Maximum re-runs per workflow run
+        #[arg(long, default_value_t = heal::DEFAULT_MAX_RETRIES)]
+        max_retries: u32,
+
+        /// Classify failures without re-running anything
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+    },
+
+    /// Statically lint workflow files (.github/workflows)
+    Lint {
+        /// Directory containing workflow files
+        #[arg(long, default_value = ".github/workflows")]
+        path: String,
+
+        /// Schedule mode from `gc ci-detect` (aggressive, moderate, conservative)
+        #[arg(long, env = "SCHEDULE_MODE")]
+        schedule_mode: Option<String>,
+    },
+
+    /// Fix-suggestion rules (shipped + .gitcore/ci-fixes.yml) and their recorded outcomes
+    Fixes {
+        #[command(subcommand)]
+        action: FixesAction,
     },
 
     /// Health check for all workflows
@@ -110,8 +241,8 @@ enum Commands {
     /// Guardian Agent - Auto-merge PR evaluation
     Guardian {
         /// Pull Request number to evaluate
-        #[arg(short, long)]
-        pr_number: u64,
+        #[arg(short, long, required_unless_present_any = ["local", "watch"])]
+        pr_number: Option<u64>,
 
         /// Confidence threshold (0-100)
         #[arg(long, default_value = "70")]
@@ -128,9 +259,91 @@ enum Commands {
         /// CI mode (exit with error code on escalate/block)
         #[arg(long, default_value = "false")]
         ci_mode: bool,
+
+        /// Evaluate the local branch (git diff base...head) instead of a PR
+        #[arg(long, default_value = "false")]
+        local: bool,
+
+        /// Base ref for local mode
+        #[arg(long, default_value = "main")]
+        base: String,
+
+        /// Head ref for local mode
+        #[arg(long, default_value = "HEAD")]
+        head: String,
+
+        /// CI result JSON file for local mode
+        #[arg(long)]
+        ci_result: Option<std::path::PathBuf>,
+
+        /// Local mode: do not assume an approving review
+        #[arg(long, default_value = "false")]
+        require_review: bool,
+
+        /// Path to guardian-policy.json (merge method, required checks)
+        #[arg(long, default_value = ".gitcore/guardian-policy.json")]
+        policy: String,
+
+        /// Merge method (squash, merge, rebase); overrides policy and repo settings
+        #[arg(long)]
+        merge_method: Option<String>,
+
+        /// Wait for pending checks instead of escalating immediately
+        #[arg(long, default_value = "false")]
+        wait: bool,
+
+        /// Maximum time to wait for pending checks (seconds)
+        #[arg(long, default_value = "1800")]
+        wait_timeout: u64,
+
+        /// Interval between check polls (seconds)
+        #[arg(long, default_value = "30")]
+        poll_interval: u64,
+
+        /// Verify auto-merged PRs on the default branch and revert breakages
+        #[arg(long, default_value = "false", conflicts_with = "local")]
+        watch: bool,
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum FixesAction {
+    /// List fix rules with their confidence and recorded outcomes
+    List {
+        /// Repository rules file
+        #[arg(long, default_value = fix_rules::DEFAULT_RULES_PATH)]
+        rules: String,
+    },
+
+    /// Record whether a suggested fix resolved the failure
+    Record {
+        /// Rule id from the validation report
+        #[arg(long)]
+        rule: String,
+
+        /// Fingerprint of the failure the fix was applied to
+        #[arg(long)]
+        fingerprint: Option<String>,
+
+        /// The suggestion did not fix the failure
+        #[arg(long, default_value = "false")]
+        not_fixed: bool,
+
+        /// Repository rules file
+        #[arg(long, default_value = fix_rules::DEFAULT_RULES_PATH)]
+        rules: String,
+    },
+}
+
+/// Summarize cassette traffic; unmatched requests mean the cassette is stale
+fn log_cassette(cassette: Option<&cassette::CassetteServer>) {
+    let Some(cassette) = cassette else { return };
+    info!("📼 Cassette served {} request(s)", cassette.requests().len());
+    for request in cassette.unmatched() {
+        warn!("📼 Not in cassette: {}", request);
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
@@ -142,34 +355,132 @@ async fn main() -> Result<()> {
     let subscriber = FmtSubscriber::builder()
         .with_max_level(level)
         .with_target(false)
+        .with_writer(std::io::stderr)
         .compact()
         .init();
 
     info!("🚀 Workflow Orchestrator v{}", env!("CARGO_PKG_VERSION"));
 
-    let token = cli.token.or_else(|| std::env::var("GITHUB_TOKEN").ok())
-        .expect("GITHUB_TOKEN required");
-
-    let repo = cli.repo.or_else(|| std::env::var("GITHUB_REPOSITORY").ok())
-        .expect("Repository required (--repo or GITHUB_REPOSITORY)");
-
-    let github_client = github::GitHubClient::new(&token, &repo, cli.max_parallel);
+    // Local Guardian evaluation, linting and fix rules work without a token or repository
+    let token = cli.token.or_else(|| std::env::var("GITHUB_TOKEN").ok());
+    let repo = cli.repo.or_else(|| std::env::var("GITHUB_REPOSITORY").ok());
+    let require_token = || token.as_deref().context("GITHUB_TOKEN required");
+    let require_repo = || repo.as_deref().context("Repository required (--repo or GITHUB_REPOSITORY)");
+
+    // Route API calls through a cassette server when replaying or recording
+    let cassette = match &cli.cassette {
+        Some(path) if cli.record => Some(cassette::CassetteServer::record(path, &cli.api_url).await?),
+        Some(path) => Some(cassette::CassetteServer::replay_file(path).await?),
+        None => None,
+    };
+    let api_url = cassette.as_ref().map(|c| c.url().to_string()).unwrap_or_else(|| cli.api_url.clone());
+
+    let github_client = || -> Result<github::GitHubClient> {
+        Ok(github::GitHubClient::new(require_token()?, require_repo()?, cli.max_parallel).with_base_url(&api_url))
+    };
+    let filter = github::RunFilter {
+        workflow: cli.workflow.clone(),
+        branch: cli.branch.clone(),
+        event: cli.event.clone(),
+        actor: cli.actor.clone(),
+        status: cli.status.clone(),
+        created: cli.created.clone(),
+        ..Default::default()
+    };
+
+    // Persist newly completed runs before commands that analyze them
+    let records_history = matches!(
+        cli.command,
+        Commands::Validate { .. } | Commands::Analyze { .. } | Commands::Health { .. }
+    );
+    let store = if records_history && !cli.no_history {
+        history::open_synced(&github_client()?, &cli.history_db).await
+    } else {
+        None
+    };
 
     match cli.command {
         Commands::Validate { run_id, last_hours, create_pr } => {
-            validator::run_validation(&github_client, &run_id, last_hours, create_pr, &cli.output).await?;
+            validator::run_validation(&github_client()?, store.as_ref(), &filter, &run_id, last_hours, create_pr, &cli.output).await?;
         }
         Commands::Analyze { types, include_success } => {
-            analyzer::run_analysis(&github_client, &types, include_success, &cli.output).await?;
+            analyzer::run_analysis(&github_client()?, store.as_ref(), &filter, &types, include_success, &cli.output).await?;
         }
         Commands::PostRun { run_id, ai_review } => {
-            validator::post_run_validation(&github_client, &run_id, ai_review).await?;
+            validator::post_run_validation(&github_client()?, &run_id, ai_review).await?;
+        }
+        Commands::Report { report_type, hours, trend, weeks, file } => {
+            if trend {
+                let mut store = history::HistoryStore::open(&cli.history_db)?;
+                history::run_trend_report(&github_client()?, &mut store, weeks, &cli.output).await?;
+            } else {
+                reporter::generate_report(&github_client()?, &filter, &report_type, hours, &cli.output, file.as_deref()).await?;
+            }
+        }
+        Commands::Flaky { hours, create_issues } => {
+            flaky::run_flaky_analysis(&github_client()?, &filter, hours, create_issues, &cli.output).await?;
+        }
+        Commands::Cost { hours, budget } => {
+            let report = cost::run_cost_report(&github_client()?, &filter, hours, budget, &cli.output).await?;
+            if report.over_budget() {
+                std::process::exit(cost::EXIT_OVER_BUDGET);
+            }
+        }
+        Commands::Heal { run_id, hours, max_retries, dry_run } => {
+            let store = (!cli.no_history).then(|| history::HistoryStore::open(&cli.history_db)).transpose()?;
+            let candidates = heal::candidate_filter(&filter, hours);
+            heal::run_heal(&github_client()?, &candidates, store.as_ref(), run_id, max_retries, dry_run, &cli.output).await?;
         }
-        Commands::Report { report_type, hours } => {
-            reporter::generate_report(&github_client, &report_type, hours, &cli.output).await?;
+        Commands::Lint { path, schedule_mode } => {
+            let mode = schedule_mode.map(|m| m.parse()).transpose()?;
+            let findings = workflow_lint::lint_dir(&path, mode)?;
+            println!("{}", workflow_lint::render(&findings, &cli.output)?);
+
+            if workflow_lint::has_errors(&findings) {
+                std::process::exit(1);
+            }
         }
+        Commands::Fixes { action } => match action {
+            FixesAction::List { rules } => {
+                let mut kb = fix_rules::KnowledgeBase::shipped()?.with_rules_file(&rules)?;
+                // Listing only reads outcomes; never create the history database for it
+                if std::path::Path::new(&cli.history_db).exists() {
+                    kb = kb.with_outcomes(&history::HistoryStore::open(&cli.history_db)?)?;
+                }
+
+                match cli.output.as_str() {
+                    "json" => {
+                        let rules: Vec<_> = kb.rules().iter()
+                            .map(|r| serde_json::json!({
+                                "id": r.id,
+                                "title": r.title,
+                                "link": r.link,
+                                "confidence": r.confidence,
+                                "effective_confidence": r.effective_confidence(),
+                            }))
+                            .collect();
+                        println!("{}", serde_json::to_string_pretty(&rules)?);
+                    }
+                    _ => {
+                        for r in kb.rules() {
+                            println!("{:<28} {:>4.0}% → {:>4.0}%  {}",
+                                r.id, r.confidence * 100.0, r.effective_confidence() * 100.0, r.title);
+                        }
+                    }
+                }
+            }
+            FixesAction::Record { rule, fingerprint, not_fixed, rules } => {
+                let kb = fix_rules::KnowledgeBase::shipped()?.with_rules_file(&rules)?;
+                if !kb.contains(&rule) {
+                    anyhow::bail!("Unknown fix rule: {}", rule);
+                }
+                let store = history::HistoryStore::open(&cli.history_db)?;
+                store.record_fix_outcome(&rule, fingerprint.as_deref(), !not_fixed)?;
+                info!("📝 Recorded {} for rule {}", if not_fixed { "not fixed" } else { "fixed" }, rule);
+            }
+        },
         Commands::Health { quick } => {
-            analyzer::health_check(&github_client, quick).await?;
+            analyzer::health_check(&github_client()?, &filter, quick).await?;
         }
         Commands::Guardian {
             pr_number,
@@ -177,46 +488,84 @@ async fn main() -> Result<()> {
             risk_map,
             dry_run,
             ci_mode,
+            local,
+            base,
+            head,
+            ci_result,
+            require_review,
+            policy,
+            merge_method,
+            wait,
+            wait_timeout,
+            poll_interval,
+            watch,
         } => {
-            // Create Octocrab client for Guardian
-            let octocrab = octocrab::Octocrab::builder()
-                .personal_token(token.clone())
-                .build()?;
-
-            let (owner, repo_name) = repo.split_once('/').expect("Invalid repo format (expected owner/repo)");
-
-            let mut guardian = guardian_core::GuardianCore::new(
-                octocrab,
-                owner.to_string(),
-                repo_name.to_string(),
-            )
-            .with_threshold(threshold);
-
-            // Load risk map if exists
-            if std::path::Path::new(&risk_map).exists() {
-                guardian = guardian.with_risk_map(&risk_map)?;
+            if watch {
+                let outcomes = guardian_watch::run_watch(&github_client()?, dry_run).await?;
+                if ci_mode {
+                    println!("{}", serde_json::to_string_pretty(&outcomes)?);
+                }
+                return Ok(());
+            }
+
+            // Local evaluation scores git data and never calls the API
+            let guardian = if local {
+                guardian_core::GuardianCore::local()
             } else {
-                info!("⚠️  Risk map not found: {}, skipping risk analysis", risk_map);
+                let repo = require_repo()?;
+                let (owner, repo_name) = repo.split_once('/')
+                    .with_context(|| format!("Invalid repo format (expected owner/repo): {}", repo))?;
+                guardian_core::GuardianCore::from_token_at(Some(require_token()?), &api_url, owner, repo_name)?
+            };
+
+            // Risk map, policy and CODEOWNERS; --merge-method wins over the policy file
+            let mut guardian = guardian
+                .with_threshold(threshold)
+                .with_repo_config(&risk_map, &policy)?;
+            if let Some(method) = merge_method {
+                guardian = guardian.with_merge_method(method.parse()?);
+            }
+            let neutral_is_success = guardian.policy().neutral_is_success;
+
+            if wait {
+                guardian = guardian.with_wait(
+                    std::time::Duration::from_secs(wait_timeout),
+                    std::time::Duration::from_secs(poll_interval),
+                );
             }
 
-            let decision = guardian.evaluate_pr(pr_number, dry_run).await?;
+            let report = if local {
+                let pr_data = guardian_local::LocalPrSource::new(&base, &head)
+                    .with_ci_result(ci_result)
+                    .with_require_review(require_review)
+                    .with_neutral_is_success(neutral_is_success)
+                    .with_required_checks(&guardian.policy().required_checks)
+                    .collect()
+                    .await?;
+                let report = guardian.evaluate(&pr_data);
+                if !ci_mode {
+                    println!("{}", report.to_markdown());
+                }
+                report
+            } else {
+                let pr_number = pr_number.expect("--pr-number required without --local");
+                guardian.evaluate_pr_report(pr_number, dry_run).await?
+            };
 
-            // Output decision as JSON for CI consumption
+            // Output decision report as JSON for CI consumption
             if ci_mode {
-                println!("{}", serde_json::to_string_pretty(&decision)?);
+                println!("{}", report.to_json()?);
             }
 
-            // Exit with error code in CI mode if escalated/blocked
+            // Exit with the decision's code in CI mode (same codes as `gc guardian`)
             if ci_mode {
-                match decision {
-                    guardian_core::Decision::AutoMerge { .. } => std::process::exit(0),
-                    guardian_core::Decision::Escalate { .. } => std::process::exit(1),
-                    guardian_core::Decision::Blocked { .. } => std::process::exit(2),
-                }
+                log_cassette(cassette.as_ref());
+                std::process::exit(report.decision.exit_code());
             }
         }
     }
 
+    log_cassette(cassette.as_ref());
     info!("✅ Orchestrator completed successfully");
     Ok(())
 }
@@ -1,36 +1,46 @@
 //! Report generation with parallel data gathering
 
-use crate::github::{GitHubClient, WorkflowRun};
+use crate::dashboard::Dashboard;
+use crate::github::{GitHubClient, RunFilter};
 use anyhow::Result;
 use chrono::{Utc, Duration};
 use futures::future::join_all;
 use tracing::info;
 
 /// Generate comprehensive report
+///
+/// With `output_format` `html` the report is the self-contained dashboard,
+/// written to `file` when given.
 pub async fn generate_report(
     client: &GitHubClient,
+    filter: &RunFilter,
     report_type: &str,
     hours: u64,
     output_format: &str,
+    file: Option<&str>,
 ) -> Result<()> {
     info!("📝 Generating {} report for last {} hours...", report_type, hours);
 
-    let runs = client.get_workflow_runs(100).await?;
     let cutoff = Utc::now() - Duration::hours(hours as i64);
-
-    let filtered_runs: Vec<_> = runs.into_iter()
-        .filter(|r| {
-            chrono::DateTime::parse_from_rfc3339(&r.created_at)
-                .map(|dt| dt > cutoff)
-                .unwrap_or(false)
-        })
-        .collect();
+    let filtered_runs = client.collect_runs(&filter.clone().created_since(cutoff)).await?;
 
     info!("📊 Found {} runs in time range", filtered_runs.len());
 
     // Parallel analysis
     let analyses = client.analyze_runs_parallel(filtered_runs).await?;
 
+    if output_format == "html" {
+        let html = Dashboard::build(&analyses, hours, Utc::now()).to_html();
+        match file {
+            Some(path) => {
+                std::fs::write(path, html)?;
+                info!("📄 Dashboard written to {}", path);
+            }
+            None => print!("{}", html),
+        }
+        return Ok(());
+    }
+
     match report_type {
         "summary" => generate_summary_report(&analyses, output_format),
         "detailed" => generate_detailed_report(&analyses, output_format),
@@ -1,13 +1,31 @@
 //! Post-run validation and continuous improvement system
 
-use crate::github::{GitHubClient, WorkflowAnalysis};
+use crate::critical_path::{self, CriticalPathReport};
+use crate::fix_rules::{FixContext, FixSuggestion, KnowledgeBase};
+use crate::github::{GitHubClient, PullRequestInfo, RunFilter, WorkflowAnalysis, WorkflowRun};
+use crate::history::{self, HistoryStore};
 use anyhow::Result;
 use chrono::Utc;
+use std::collections::HashMap;
 use tracing::{info, warn};
 
+/// Marker identifying the validator's own PR
+pub const VALIDATION_PR_MARKER: &str = "<!-- gc-validator:validation-report -->";
+
+/// Branch of the validation PR
+const VALIDATION_BRANCH: &str = "gc-validator/validation-report";
+
+/// Report file committed to the validation branch
+const VALIDATION_REPORT_PATH: &str = ".gitcore/validation-report.md";
+
 /// Run validation on completed workflows
+///
+/// `run_id` `latest` validates the runs matching `filter` from the last
+/// `last_hours`, or the 10 most recent ones.
 pub async fn run_validation(
     client: &GitHubClient,
+    store: Option<&HistoryStore>,
+    filter: &RunFilter,
     run_id: &str,
     last_hours: Option<u64>,
     create_pr: bool,
@@ -15,36 +33,26 @@ pub async fn run_validation(
 ) -> Result<()> {
     info!("🔍 Starting workflow validation...");
 
-    let runs = client.get_workflow_runs(50).await?;
-
-    let runs_to_validate: Vec<_> = if run_id == "latest" {
-        if let Some(hours) = last_hours {
-            let cutoff = Utc::now() - chrono::Duration::hours(hours as i64);
-            runs.into_iter()
-                .filter(|r| {
-                    chrono::DateTime::parse_from_rfc3339(&r.created_at)
-                        .map(|dt| dt > cutoff)
-                        .unwrap_or(false)
-                })
-                .collect()
-        } else {
-            runs.into_iter().take(10).collect()
-        }
+    let runs_to_validate = if run_id == "latest" {
+        let filter = match last_hours {
+            Some(hours) => filter.clone().created_since(Utc::now() - chrono::Duration::hours(hours as i64)),
+            None => filter.clone().with_limit(10),
+        };
+        client.collect_runs(&filter).await?
     } else {
-        let target_id: u64 = run_id.parse()?;
-        runs.into_iter().filter(|r| r.id == target_id).collect()
+        vec![client.get_workflow_run(run_id.parse()?).await?]
     };
 
     info!("📊 Validating {} workflow runs...", runs_to_validate.len());
 
     // Parallel analysis
-    let analyses = client.analyze_runs_parallel(runs_to_validate).await?;
+    let analyses = history::analyze_runs(client, store, runs_to_validate).await?;
 
     // Generate validation report
     let report = generate_validation_report(&analyses);
 
     if create_pr {
-        create_validation_pr(client, &report, &analyses).await?;
+        sync_validation_pr(client, &report, &analyses).await?;
     }
 
     match output_format {
@@ -65,11 +73,8 @@ pub async fn post_run_validation(
     info!("🔬 Running post-execution validation for run {}...", run_id);
 
     let target_id: u64 = run_id.parse()?;
-    let runs = client.get_workflow_runs(20).await?;
-
-    let run = runs.into_iter()
-        .find(|r| r.id == target_id)
-        .ok_or_else(|| anyhow::anyhow!("Run {} not found", run_id))?;
+    let run = client.get_workflow_run(target_id).await
+        .map_err(|e| anyhow::anyhow!("Run {} not found: {}", run_id, e))?;
 
     let analyses = client.analyze_runs_parallel(vec![run.clone()]).await?;
     let analysis = analyses.into_iter().next()
@@ -118,6 +123,7 @@ pub struct ValidationReport {
     pub security_score: f64,
     pub recommendations: Vec<Recommendation>,
     pub metrics: ValidationMetrics,
+    pub critical_path: Option<CriticalPathReport>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -136,6 +142,14 @@ pub struct Recommendation {
     pub title: String,
     pub description: String,
     pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    /// Confidence of a fix suggestion (0-1), adjusted by recorded outcomes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// Fix rule that produced this recommendation, for `fixes record`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -151,12 +165,22 @@ pub struct ValidationMetrics {
 }
 
 impl ValidationReport {
+    /// Build a report using the repository's fix rules (`.gitcore/ci-fixes.yml`)
     pub fn from_analysis(analysis: &WorkflowAnalysis) -> Self {
+        let kb = KnowledgeBase::for_repo().unwrap_or_else(|e| {
+            warn!("⚠️ Failed to load fix rules, using shipped rules: {:#}", e);
+            KnowledgeBase::shipped().unwrap_or_default()
+        });
+        Self::from_analysis_with(analysis, &kb)
+    }
+
+    pub fn from_analysis_with(analysis: &WorkflowAnalysis, kb: &KnowledgeBase) -> Self {
         let mut errors = Vec::new();
         let mut warnings = analysis.warnings.clone();
         let mut failed_jobs = 0;
         let mut failed_steps = 0;
         let mut step_count = 0;
+        let mut fixes: Vec<FixSuggestion> = Vec::new();
 
         for job in &analysis.jobs {
             if job.conclusion.as_deref() == Some("failure") {
@@ -167,13 +191,28 @@ impl ValidationReport {
                         step_count += 1;
                         if step.conclusion.as_deref() == Some("failure") {
                             failed_steps += 1;
+                            let job_failures: Vec<_> = analysis.failures.iter()
+                                .filter(|f| f.job.as_deref() == Some(job.name.as_str()))
+                                .cloned()
+                                .collect();
+                            let suggestions = kb.suggest(&FixContext {
+                                job: &job.name,
+                                step: Some(&step.name),
+                                failures: &job_failures,
+                                log: analysis.job_logs.get(&job.id).map(String::as_str),
+                            });
                             errors.push(ErrorDetail {
                                 job: job.name.clone(),
                                 step: Some(step.name.clone()),
                                 message: format!("Step '{}' failed in job '{}'", step.name, job.name),
                                 severity: "error".to_string(),
-                                suggested_fix: suggest_fix(&step.name),
+                                suggested_fix: suggestions.first().map(|s| s.suggestion.clone()),
                             });
+                            for suggestion in suggestions {
+                                if !fixes.iter().any(|f| f.rule_id == suggestion.rule_id) {
+                                    fixes.push(suggestion);
+                                }
+                            }
                         }
                     }
                 }
@@ -192,6 +231,9 @@ impl ValidationReport {
                 title: "Fix failing jobs".to_string(),
                 description: format!("{} job(s) failed in this run", failed_jobs),
                 action: "Review error logs and fix the underlying issues".to_string(),
+                link: None,
+                confidence: None,
+                rule_id: None,
             });
         }
 
@@ -203,10 +245,48 @@ impl ValidationReport {
                     title: "Optimize workflow duration".to_string(),
                     description: format!("Workflow took {}s (>10 min)", duration),
                     action: "Consider parallelizing jobs or using caching".to_string(),
+                    link: None,
+                    confidence: None,
+                    rule_id: None,
                 });
             }
         }
 
+        for fix in fixes {
+            let priority = if fix.confidence >= 0.8 { "high" } else if fix.confidence >= 0.5 { "medium" } else { "low" };
+            let mut description = format!("{} ({} / {})", fix.suggestion, fix.job, fix.step.as_deref().unwrap_or("N/A"));
+            if fix.times_fixed > 0 {
+                description.push_str(&format!(" — fixed this {} time(s) before", fix.times_fixed));
+            }
+            recommendations.push(Recommendation {
+                category: "fix".to_string(),
+                priority: priority.to_string(),
+                title: fix.title,
+                description,
+                action: fix.suggestion,
+                link: fix.link,
+                confidence: Some(fix.confidence),
+                rule_id: Some(fix.rule_id),
+            });
+        }
+
+        let critical_path = critical_path::analyze_run(analysis);
+        if let Some(cp) = &critical_path {
+            for suggestion in &cp.suggestions {
+                recommendations.push(Recommendation {
+                    category: "performance".to_string(),
+                    priority: "medium".to_string(),
+                    title: suggestion.kind.title().to_string(),
+                    description: suggestion.message.clone(),
+                    action: suggestion.kind.action().to_string(),
+                    link: None,
+                    confidence: None,
+                    rule_id: None,
+                });
+            }
+        }
+        let parallel_jobs = critical_path.as_ref().map(|cp| cp.parallel_jobs).unwrap_or(0);
+
         // Calculate scores
         let total_jobs = analysis.jobs.len().max(1);
         let performance_score = if let Some(d) = analysis.duration_seconds {
@@ -234,11 +314,12 @@ impl ValidationReport {
                 step_count,
                 failed_jobs,
                 failed_steps,
-                parallel_jobs: estimate_parallel_jobs(&analysis.jobs),
-                sequential_jobs: analysis.jobs.len() - estimate_parallel_jobs(&analysis.jobs),
+                parallel_jobs,
+                sequential_jobs: analysis.jobs.len().saturating_sub(parallel_jobs),
                 cache_hits: 0,  // Would need log analysis
                 cache_misses: 0,
             },
+            critical_path,
         }
     }
 
@@ -259,6 +340,23 @@ impl ValidationReport {
         md.push_str(&format!("| Security Score | {:.1}% |\n", self.security_score));
         md.push('\n');
 
+        if let Some(cp) = &self.critical_path {
+            md.push_str("## 🛤️ Critical Path\n\n");
+            md.push_str(&format!("**Path:** {} ({}s of {}s wall time)\n\n",
+                cp.critical_path.join(" → "), cp.critical_path_seconds, cp.wall_seconds));
+            md.push_str(&format!("**Idle queue time:** {}s | **Achievable speedup:** {:.2}x{}\n\n",
+                cp.idle_queue_seconds, cp.achievable_speedup,
+                if cp.has_dependency_graph { "" } else { " (workflow file not found, jobs treated as independent)" }));
+            md.push_str("| Job | Start | Duration | Queued | Critical |\n");
+            md.push_str("|-----|-------|----------|--------|----------|\n");
+            for job in &cp.jobs {
+                md.push_str(&format!("| {} | +{}s | {}s | {}s | {} |\n",
+                    job.name, job.start_offset_seconds, job.duration_seconds, job.queue_seconds,
+                    if job.on_critical_path { "✅" } else { "" }));
+            }
+            md.push('\n');
+        }
+
         if !self.errors.is_empty() {
             md.push_str("## ❌ Errors\n\n");
             for error in &self.errors {
@@ -277,6 +375,16 @@ impl ValidationReport {
             for rec in &self.recommendations {
                 md.push_str(&format!("### {} [{}]\n", rec.title, rec.priority.to_uppercase()));
                 md.push_str(&format!("**Category:** {} | **Action:** {}\n\n", rec.category, rec.action));
+                if let Some(confidence) = rec.confidence {
+                    md.push_str(&format!("**Confidence:** {:.0}%", confidence * 100.0));
+                    if let Some(rule_id) = &rec.rule_id {
+                        md.push_str(&format!(" | **Rule:** `{}`", rule_id));
+                    }
+                    if let Some(link) = &rec.link {
+                        md.push_str(&format!(" | [Docs]({})", link));
+                    }
+                    md.push_str("\n\n");
+                }
                 md.push_str(&format!("{}\n\n", rec.description));
             }
         }
@@ -294,39 +402,14 @@ impl ValidationReport {
             self.errors.len()));
         out.push_str(&format!("   Performance: {:.1}% | Security: {:.1}%\n",
             self.performance_score, self.security_score));
+        if let Some(cp) = &self.critical_path {
+            out.push_str(&format!("   Critical path: {} ({}s) | Queued: {}s | Speedup: {:.2}x\n",
+                cp.critical_path.join(" → "), cp.critical_path_seconds, cp.idle_queue_seconds, cp.achievable_speedup));
+        }
         out
     }
 }
 
-fn suggest_fix(step_name: &str) -> Option<String> {
-    let name_lower = step_name.to_lowercase();
-
-    if name_lower.contains("checkout") {
-        Some("Check repository permissions and branch existence".to_string())
-    } else if name_lower.contains("install") || name_lower.contains("setup") {
-        Some("Verify dependencies and cache configuration".to_string())
-    } else if name_lower.contains("build") {
-        Some("Check build configuration and dependencies".to_string())
-    } else if name_lower.contains("test") {
-        Some("Review test failures and check test environment".to_string())
-    } else if name_lower.contains("deploy") {
-        Some("Verify deployment credentials and target environment".to_string())
-    } else {
-        None
-    }
-}
-
-fn estimate_parallel_jobs(jobs: &[crate::github::Job]) -> usize {
-    // Estimate based on start times - jobs starting at similar times are parallel
-    // This is a simplified heuristic
-    if jobs.len() <= 1 {
-        return 0;
-    }
-
-    // For now, assume ~50% parallelism if multiple jobs
-    jobs.len() / 2
-}
-
 fn generate_validation_report(analyses: &[WorkflowAnalysis]) -> ValidationReport {
     if let Some(analysis) = analyses.first() {
         ValidationReport::from_analysis(analysis)
@@ -353,6 +436,7 @@ fn generate_validation_report(analyses: &[WorkflowAnalysis]) -> ValidationReport
                 cache_hits: 0,
                 cache_misses: 0,
             },
+            critical_path: None,
         }
     }
 }
@@ -382,6 +466,13 @@ fn generate_pr_body(report: &ValidationReport, analysis: &WorkflowAnalysis, ai_r
         if report.metrics.failed_jobs == 0 { "✅" } else { "🔴" }
     ));
 
+    if let Some(cp) = &report.critical_path {
+        body.push_str("### 🛤️ Critical Path\n\n");
+        body.push_str(&format!("`{}` — {}s of {}s wall time, {}s queued, {:.2}x achievable speedup\n\n",
+            cp.critical_path.join(" → "), cp.critical_path_seconds, cp.wall_seconds,
+            cp.idle_queue_seconds, cp.achievable_speedup));
+    }
+
     if !report.errors.is_empty() {
         body.push_str("### ❌ Errors Found\n\n");
         for error in &report.errors {
@@ -396,7 +487,11 @@ fn generate_pr_body(report: &ValidationReport, analysis: &WorkflowAnalysis, ai_r
     if !report.recommendations.is_empty() {
         body.push_str("### 💡 Recommendations\n\n");
         for rec in &report.recommendations {
-            body.push_str(&format!("- **[{}]** {}: {}\n", rec.priority.to_uppercase(), rec.title, rec.action));
+            body.push_str(&format!("- **[{}]** {}: {}", rec.priority.to_uppercase(), rec.title, rec.action));
+            if let Some(link) = &rec.link {
+                body.push_str(&format!(" ([docs]({}))", link));
+            }
+            body.push('\n');
         }
         body.push('\n');
     }
@@ -418,36 +513,108 @@ fn generate_pr_body(report: &ValidationReport, analysis: &WorkflowAnalysis, ai_r
     body
 }
 
-async fn create_validation_pr(
+/// What `sync_validation_pr` did with the validation PR
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub enum ValidationPrAction {
+    Created(u64),
+    Updated(u64),
+    /// All workflows healthy again
+    Closed(u64),
+    /// Healthy and no validation PR open
+    None,
+}
+
+/// Whether the latest completed run of every workflow succeeded
+///
+/// Older failures that were fixed since do not keep the validation PR open.
+fn all_healthy(analyses: &[WorkflowAnalysis]) -> bool {
+    let mut latest: HashMap<u64, &WorkflowRun> = HashMap::new();
+    for run in analyses.iter().map(|a| &a.run).filter(|r| r.status == "completed") {
+        let newer = latest.get(&run.workflow_id).is_none_or(|seen| (&run.created_at, run.id) > (&seen.created_at, seen.id));
+        if newer {
+            latest.insert(run.workflow_id, run);
+        }
+    }
+
+    latest.values().all(|run| {
+        !matches!(run.conclusion.as_deref(), Some("failure") | Some("timed_out") | Some("startup_failure"))
+    })
+}
+
+/// The PR comes from `VALIDATION_BRANCH` of this repository (not a fork) and carries the marker
+fn is_validation_pr(pr: &PullRequestInfo) -> bool {
+    let (Some(head), Some(base)) = (&pr.head, &pr.base) else {
+        return false;
+    };
+    head.ref_name == VALIDATION_BRANCH
+        && head.repo.is_some()
+        && head.repo == base.repo
+        && pr.body.as_deref().is_some_and(|b| b.contains(VALIDATION_PR_MARKER))
+}
+
+/// Keep a single validation PR in sync with the latest report
+///
+/// The PR is the open one from `VALIDATION_BRANCH` of this repository with
+/// `VALIDATION_PR_MARKER` in its body. Failing runs create it (or push an
+/// updated report commit to it); healthy runs close it.
+pub async fn sync_validation_pr(
     client: &GitHubClient,
     report: &ValidationReport,
     analyses: &[WorkflowAnalysis],
-) -> Result<()> {
+) -> Result<ValidationPrAction> {
     if analyses.is_empty() {
         warn!("No analyses to create PR from");
-        return Ok(());
+        return Ok(ValidationPrAction::None);
     }
 
-    let analysis = &analyses[0];
-    let branch_name = format!("validation/batch-{}", Utc::now().format("%Y%m%d%H%M%S"));
+    let existing = client.list_open_pull_requests().await?
+        .into_iter()
+        .find(is_validation_pr);
 
-    let sha = client.get_default_branch_sha().await?;
-    let _ = client.create_branch(&branch_name, &sha).await;
+    if all_healthy(analyses) {
+        let Some(pr) = existing else {
+            info!("✅ All workflows healthy, no validation PR open");
+            return Ok(ValidationPrAction::None);
+        };
+        client.add_pr_comment(pr.number, "✅ All workflows are healthy again, closing this validation report.").await?;
+        client.close_pull_request(pr.number).await?;
+        client.delete_branch(VALIDATION_BRANCH).await?;
+        info!("✅ Closed validation PR #{}: all workflows healthy", pr.number);
+        return Ok(ValidationPrAction::Closed(pr.number));
+    }
 
-    let pr_body = generate_pr_body(report, analysis, true);
+    let analysis = &analyses[0];
 
-    let pr = client.create_pr(
-        &format!("🔬 Validation Report: {} runs analyzed", analyses.len()),
-        &pr_body,
-        &branch_name,
-    ).await?;
+    let title = format!("🔬 Validation Report: {} runs analyzed", analyses.len());
+    let pr_body = format!("{}\n{}\n", generate_pr_body(report, analysis, true), VALIDATION_PR_MARKER);
+    let commit_message = format!("Update validation report ({})", Utc::now().format("%Y-%m-%d %H:%M UTC"));
 
-    info!("✅ Created validation PR #{}: {}", pr.number, pr.html_url);
+    match existing {
+        Some(pr) => {
+            client.put_file(VALIDATION_REPORT_PATH, &report.to_markdown(), &commit_message, VALIDATION_BRANCH).await?;
+            client.update_issue(pr.number, &title, &pr_body).await?;
+            info!("🔄 Updated validation PR #{}: {}", pr.number, pr.html_url);
+            Ok(ValidationPrAction::Updated(pr.number))
+        }
+        None => {
+            let base = client.get_default_branch().await?;
+            let sha = client.get_branch_sha(&base).await?;
+            // A leftover branch from a closed PR starts over from the default branch
+            if client.branch_exists(VALIDATION_BRANCH).await? {
+                client.reset_branch(VALIDATION_BRANCH, &sha).await?;
+            } else {
+                client.create_branch(VALIDATION_BRANCH, &sha).await?;
+            }
+            client.put_file(VALIDATION_REPORT_PATH, &report.to_markdown(), &commit_message, VALIDATION_BRANCH).await?;
 
-    // Request AI reviews
-    request_ai_reviews(client, pr.number).await?;
+            let pr = client.create_pr_with_base(&title, &pr_body, VALIDATION_BRANCH, &base).await?;
+            info!("✅ Created validation PR #{}: {}", pr.number, pr.html_url);
 
-    Ok(())
+            // Request AI reviews
+            request_ai_reviews(client, pr.number).await?;
+            Ok(ValidationPrAction::Created(pr.number))
+        }
+    }
 }
 
 async fn request_ai_reviews(client: &GitHubClient, pr_number: u64) -> Result<()> {
@@ -491,3 +658,237 @@ Once AI reviews are complete, assign this PR to Copilot for:
     info!("✅ AI review requests sent");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cassette::{Cassette, CassetteServer};
+    use crate::github::{Job, Step};
+    use serde_json::json;
+
+    const REPO: &str = "/repos/acme/widgets";
+
+    fn analysis(conclusion: &str) -> WorkflowAnalysis {
+        WorkflowAnalysis {
+            run: WorkflowRun {
+                id: 1,
+                name: "CI".to_string(),
+                status: "completed".to_string(),
+                conclusion: Some(conclusion.to_string()),
+                html_url: String::new(),
+                created_at: String::new(),
+                updated_at: String::new(),
+                run_attempt: 1,
+                workflow_id: 1,
+                head_branch: "trunk".to_string(),
+                head_sha: "abc".to_string(),
+                path: String::new(),
+                event: "push".to_string(),
+            },
+            jobs: Vec::new(),
+            job_logs: Default::default(),
+            errors: Vec::new(),
+            failures: Vec::new(),
+            warnings: Vec::new(),
+            duration_seconds: None,
+        }
+    }
+
+    fn open_validation_pr() -> serde_json::Value {
+        validation_pr("acme/widgets")
+    }
+
+    /// Open PR from `VALIDATION_BRANCH` of `head_repo` into acme/widgets
+    fn validation_pr(head_repo: &str) -> serde_json::Value {
+        json!([{
+            "number": 9,
+            "title": "🔬 Validation Report: 1 runs analyzed",
+            "html_url": "https://github.com/acme/widgets/pull/9",
+            "user": { "login": "github-actions[bot]" },
+            "merged_at": null,
+            "merge_commit_sha": null,
+            "body": format!("report\n{}", VALIDATION_PR_MARKER),
+            "head": { "ref": VALIDATION_BRANCH, "repo": { "full_name": head_repo } },
+            "base": { "ref": "trunk", "repo": { "full_name": "acme/widgets" } },
+        }])
+    }
+
+    async fn sync(cassette: Cassette, conclusion: &str) -> (ValidationPrAction, Vec<String>) {
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("token", "acme/widgets", 2).with_base_url(server.url());
+        let analyses = vec![analysis(conclusion)];
+        let report = generate_validation_report(&analyses);
+
+        let action = sync_validation_pr(&client, &report, &analyses).await.unwrap();
+        assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+        (action, server.requests())
+    }
+
+    #[tokio::test]
+    async fn test_creates_pr_against_default_branch() {
+        let contents = format!("{}/contents/{}", REPO, VALIDATION_REPORT_PATH);
+        let cassette = Cassette::default()
+            .with("GET", &format!("{}/pulls?state=open&per_page=100&page=1", REPO), 200, json!([]))
+            .with("GET", REPO, 200, json!({ "default_branch": "trunk" }))
+            .with("GET", &format!("{}/git/refs/heads/trunk", REPO), 200, json!({ "object": { "sha": "abc" } }))
+            .with("GET", &format!("{}/git/refs/heads/{}", REPO, VALIDATION_BRANCH), 404, json!({ "message": "Not Found" }))
+            .with("POST", &format!("{}/git/refs", REPO), 201, json!({}))
+            .with("GET", &format!("{}?ref={}", contents, VALIDATION_BRANCH.replace('/', "%2F")), 404, json!({ "message": "Not Found" }))
+            .with("PUT", &contents, 201, json!({}))
+            .with("POST", &format!("{}/pulls", REPO), 201, json!({ "number": 5, "html_url": "https://github.com/acme/widgets/pull/5" }))
+            .with("POST", &format!("{}/issues/5/comments", REPO), 201, json!({}))
+            .with("POST", &format!("{}/issues/5/comments", REPO), 201, json!({}))
+            .with("POST", &format!("{}/issues/5/comments", REPO), 201, json!({}));
+
+        let (action, _) = sync(cassette, "failure").await;
+        assert_eq!(action, ValidationPrAction::Created(5));
+    }
+
+    #[tokio::test]
+    async fn test_resets_leftover_branch_before_creating_pr() {
+        let contents = format!("{}/contents/{}", REPO, VALIDATION_REPORT_PATH);
+        let branch = format!("{}/git/refs/heads/{}", REPO, VALIDATION_BRANCH);
+        let cassette = Cassette::default()
+            .with("GET", &format!("{}/pulls?state=open&per_page=100&page=1", REPO), 200, json!([]))
+            .with("GET", REPO, 200, json!({ "default_branch": "trunk" }))
+            .with("GET", &format!("{}/git/refs/heads/trunk", REPO), 200, json!({ "object": { "sha": "abc" } }))
+            .with("GET", &branch, 200, json!({ "object": { "sha": "stale" } }))
+            .with("PATCH", &branch, 200, json!({}))
+            .with("GET", &format!("{}?ref={}", contents, VALIDATION_BRANCH.replace('/', "%2F")), 404, json!({ "message": "Not Found" }))
+            .with("PUT", &contents, 201, json!({}))
+            .with("POST", &format!("{}/pulls", REPO), 201, json!({ "number": 6, "html_url": "https://github.com/acme/widgets/pull/6" }))
+            .with("POST", &format!("{}/issues/6/comments", REPO), 201, json!({}))
+            .with("POST", &format!("{}/issues/6/comments", REPO), 201, json!({}))
+            .with("POST", &format!("{}/issues/6/comments", REPO), 201, json!({}));
+
+        let (action, requests) = sync(cassette, "failure").await;
+        assert_eq!(action, ValidationPrAction::Created(6));
+        assert!(requests.contains(&format!("PATCH {}", branch)));
+        assert!(!requests.contains(&format!("POST {}/git/refs", REPO)));
+    }
+
+    #[tokio::test]
+    async fn test_create_pr_propagates_branch_errors() {
+        let cassette = Cassette::default()
+            .with("GET", &format!("{}/pulls?state=open&per_page=100&page=1", REPO), 200, json!([]))
+            .with("GET", REPO, 200, json!({ "default_branch": "trunk" }))
+            .with("GET", &format!("{}/git/refs/heads/trunk", REPO), 200, json!({ "object": { "sha": "abc" } }))
+            .with("GET", &format!("{}/git/refs/heads/{}", REPO, VALIDATION_BRANCH), 404, json!({ "message": "Not Found" }))
+            .with("POST", &format!("{}/git/refs", REPO), 403, json!({ "message": "Resource not accessible by integration" }));
+        let server = CassetteServer::replay(cassette).await.unwrap();
+        let client = GitHubClient::new("token", "acme/widgets", 2).with_base_url(server.url());
+        let analyses = vec![analysis("failure")];
+        let report = generate_validation_report(&analyses);
+
+        assert!(sync_validation_pr(&client, &report, &analyses).await.is_err());
+        assert!(!server.requests().iter().any(|r| r.starts_with("PUT")));
+    }
+
+    #[tokio::test]
+    async fn test_updates_existing_pr_found_by_marker() {
+        let contents = format!("{}/contents/{}", REPO, VALIDATION_REPORT_PATH);
+        let cassette = Cassette::default()
+            .with("GET", &format!("{}/pulls?state=open&per_page=100&page=1", REPO), 200, open_validation_pr())
+            .with("GET", &format!("{}?ref={}", contents, VALIDATION_BRANCH.replace('/', "%2F")), 200, json!({ "sha": "blob1" }))
+            .with("PUT", &contents, 200, json!({}))
+            .with("PATCH", &format!("{}/issues/9", REPO), 200, json!({}));
+
+        let (action, requests) = sync(cassette, "failure").await;
+        assert_eq!(action, ValidationPrAction::Updated(9));
+        assert!(!requests.iter().any(|r| r == &format!("POST {}/pulls", REPO)));
+    }
+
+    #[tokio::test]
+    async fn test_closes_pr_when_healthy() {
+        let cassette = Cassette::default()
+            .with("GET", &format!("{}/pulls?state=open&per_page=100&page=1", REPO), 200, open_validation_pr())
+            .with("POST", &format!("{}/issues/9/comments", REPO), 201, json!({}))
+            .with("PATCH", &format!("{}/pulls/9", REPO), 200, json!({}))
+            .with("DELETE", &format!("{}/git/refs/heads/{}", REPO, VALIDATION_BRANCH), 204, json!(null));
+
+        let (action, _) = sync(cassette, "success").await;
+        assert_eq!(action, ValidationPrAction::Closed(9));
+    }
+
+    #[tokio::test]
+    async fn test_ignores_fork_pr_from_validation_branch() {
+        let cassette = Cassette::default()
+            .with("GET", &format!("{}/pulls?state=open&per_page=100&page=1", REPO), 200, validation_pr("mallory/widgets"));
+
+        let (action, requests) = sync(cassette, "success").await;
+        assert_eq!(action, ValidationPrAction::None);
+        assert!(!requests.iter().any(|r| r.starts_with("DELETE")));
+    }
+
+    #[test]
+    fn test_health_uses_latest_run_per_workflow() {
+        let run = |id: u64, workflow_id: u64, created_at: &str, conclusion: &str| {
+            let mut a = analysis(conclusion);
+            a.run.id = id;
+            a.run.workflow_id = workflow_id;
+            a.run.created_at = created_at.to_string();
+            a
+        };
+
+        let fixed = vec![
+            run(2, 1, "2025-03-02T00:00:00Z", "success"),
+            run(1, 1, "2025-03-01T00:00:00Z", "failure"),
+            run(3, 2, "2025-03-01T00:00:00Z", "success"),
+        ];
+        assert!(all_healthy(&fixed));
+
+        let broken = vec![
+            run(2, 1, "2025-03-02T00:00:00Z", "failure"),
+            run(1, 1, "2025-03-01T00:00:00Z", "success"),
+        ];
+        assert!(!all_healthy(&broken));
+
+        // A newer run still in progress does not hide the latest completed failure
+        let mut running = run(3, 1, "2025-03-03T00:00:00Z", "success");
+        running.run.status = "in_progress".to_string();
+        running.run.conclusion = None;
+        assert!(!all_healthy(&[broken[0].clone(), running]));
+    }
+
+    #[test]
+    fn test_log_rule_matches_only_the_owning_job() {
+        let job = |id: u64, name: &str| Job {
+            id,
+            name: name.to_string(),
+            status: "completed".to_string(),
+            conclusion: Some("failure".to_string()),
+            started_at: None,
+            completed_at: None,
+            steps: Some(vec![Step {
+                name: "Run tests".to_string(),
+                status: "completed".to_string(),
+                conclusion: Some("failure".to_string()),
+                number: 1,
+                started_at: None,
+                completed_at: None,
+            }]),
+            labels: Vec::new(),
+        };
+        let mut a = analysis("failure");
+        a.jobs = vec![job(10, "integration"), job(20, "unit")];
+        a.job_logs = [
+            (10, "connection refused: 127.0.0.1:5432".to_string()),
+            (20, "assertion failed: left == right".to_string()),
+        ].into();
+
+        let kb = KnowledgeBase::default().with_rules_str(r#"
+rules:
+  - id: db-not-ready
+    title: Database not ready
+    match:
+      log: "connection refused.*5432"
+    suggestion: Wait for postgres.
+"#).unwrap();
+        let report = ValidationReport::from_analysis_with(&a, &kb);
+
+        let fixes: Vec<_> = report.errors.iter()
+            .map(|e| (e.job.as_str(), e.suggested_fix.as_deref()))
+            .collect();
+        assert_eq!(fixes, vec![("integration", Some("Wait for postgres.")), ("unit", None)]);
+    }
+}
@@ -0,0 +1,452 @@
+//! # Workflow Lint
+//!
+//! Static analysis of `.github/workflows/*.yml`, complementing the run-based
+//! analyzers. Rules:
+//!
+//! | Rule | Severity |
+//! |------|----------|
+//! | `missing-timeout` — job without `timeout-minutes` | warning |
+//! | `unpinned-action` — third-party action pinned to a tag/branch, not a SHA | warning |
+//! | `broad-permissions` — `write-all`, or no `permissions` at all | error / warning |
+//! | `pr-target-checkout` — `pull_request_target` checking out the PR head | error |
+//! | `missing-concurrency` — push/PR workflow without a concurrency group | info |
+//! | `schedule-mode` — cron more frequent than the `gc ci-detect` mode allows | warning |
+//!
+//! Schedule modes follow `gc ci-detect`: `aggressive` allows every 30 minutes,
+//! `moderate` every 6 hours, `conservative` no schedules at all.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Owners whose actions do not need SHA pinning
+const FIRST_PARTY_OWNERS: [&str; 2] = ["actions", "github"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A lint finding with its location
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub file: String,
+    pub line: usize,
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Schedule mode chosen by `gc ci-detect` (`SCHEDULE_MODE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleMode {
+    Aggressive,
+    Moderate,
+    Conservative,
+}
+
+impl ScheduleMode {
+    /// Shortest allowed interval between scheduled runs, in minutes
+    fn min_interval_minutes(self) -> Option<u32> {
+        match self {
+            ScheduleMode::Aggressive => Some(30),
+            ScheduleMode::Moderate => Some(360),
+            ScheduleMode::Conservative => None,
+        }
+    }
+}
+
+impl std::str::FromStr for ScheduleMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "aggressive" => Ok(ScheduleMode::Aggressive),
+            "moderate" => Ok(ScheduleMode::Moderate),
+            "conservative" => Ok(ScheduleMode::Conservative),
+            _ => Err(anyhow::anyhow!("Invalid schedule mode: {}", s)),
+        }
+    }
+}
+
+/// Lint every `*.yml`/`*.yaml` file in `dir`
+pub fn lint_dir<P: AsRef<Path>>(dir: P, mode: Option<ScheduleMode>) -> Result<Vec<Finding>> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir.as_ref())
+        .with_context(|| format!("Failed to read {}", dir.as_ref().display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("yml") | Some("yaml")))
+        .collect();
+    paths.sort();
+
+    let mut findings = Vec::new();
+    for path in paths {
+        let content = std::fs::read_to_string(&path)?;
+        findings.extend(lint_workflow(&path.display().to_string(), &content, mode)?);
+    }
+    Ok(findings)
+}
+
+/// Lint a single workflow file
+pub fn lint_workflow(file: &str, content: &str, mode: Option<ScheduleMode>) -> Result<Vec<Finding>> {
+    let doc: Value = serde_yaml::from_str(content).with_context(|| format!("Invalid YAML in {}", file))?;
+    let Some(root) = doc.as_mapping() else {
+        return Ok(Vec::new());
+    };
+
+    let lines = LineIndex::new(content);
+    let mut findings = Vec::new();
+    let mut push = |line: usize, rule: &'static str, severity: Severity, message: String| {
+        findings.push(Finding { file: file.to_string(), line, rule, severity, message });
+    };
+
+    let triggers = triggers(root);
+    let jobs = root.get("jobs").and_then(Value::as_mapping).cloned().unwrap_or_default();
+
+    // Permissions
+    let top_permissions = root.get("permissions");
+    if top_permissions.and_then(Value::as_str) == Some("write-all") {
+        push(lines.key("permissions", 0), "broad-permissions", Severity::Error,
+            "Workflow grants `permissions: write-all`; list only the scopes it needs".to_string());
+    }
+    let every_job_scoped = !jobs.is_empty() && jobs.values().all(|j| j.get("permissions").is_some());
+    if top_permissions.is_none() && !every_job_scoped {
+        push(1, "broad-permissions", Severity::Warning,
+            "No `permissions` block; the token gets the repository default (often read/write)".to_string());
+    }
+
+    // Concurrency
+    let has_concurrency = root.get("concurrency").is_some() || jobs.values().any(|j| j.get("concurrency").is_some());
+    if !has_concurrency && triggers.iter().any(|t| t == "push" || t == "pull_request") {
+        push(lines.key("on", 0), "missing-concurrency", Severity::Info,
+            "No concurrency group; superseded runs keep consuming minutes".to_string());
+    }
+
+    // Schedules
+    if let Some(mode) = mode {
+        for cron in crons(root) {
+            let line = lines.containing(&cron, 0);
+            match (mode.min_interval_minutes(), cron_interval_minutes(&cron)) {
+                (None, _) => push(line, "schedule-mode", Severity::Warning,
+                    format!("Schedule `{}` runs in conservative mode, which allows no schedules", cron)),
+                (Some(min), Some(interval)) if interval < min => push(line, "schedule-mode", Severity::Warning,
+                    format!("Schedule `{}` runs every ~{} min; {:?} mode allows at most every {} min", cron, interval, mode, min)),
+                _ => {}
+            }
+        }
+    }
+
+    // Jobs
+    let jobs_line = lines.key("jobs", 0);
+    let mut cursor = jobs_line;
+    for (id, job) in &jobs {
+        let Some(id) = id.as_str() else { continue };
+        let job_line = lines.key(id, jobs_line);
+
+        if job.get("permissions").and_then(Value::as_str) == Some("write-all") {
+            push(lines.key("permissions", job_line), "broad-permissions", Severity::Error,
+                format!("Job `{}` grants `permissions: write-all`", id));
+        }
+
+        // Reusable workflow calls cannot set a timeout
+        if job.get("uses").is_none() && job.get("timeout-minutes").is_none() {
+            push(job_line, "missing-timeout", Severity::Warning,
+                format!("Job `{}` has no `timeout-minutes` (default is 360)", id));
+        }
+
+        let uses_values = job.get("uses").into_iter().chain(
+            job.get("steps")
+                .and_then(Value::as_sequence)
+                .into_iter()
+                .flatten()
+                .filter_map(|step| step.get("uses")),
+        );
+        for uses in uses_values.filter_map(Value::as_str) {
+            let line = lines.containing(uses, cursor.max(job_line));
+            cursor = line + 1;
+            if let Some(message) = unpinned(uses) {
+                push(line, "unpinned-action", Severity::Warning, message);
+            }
+        }
+
+        if triggers.iter().any(|t| t == "pull_request_target") {
+            for step in job.get("steps").and_then(Value::as_sequence).into_iter().flatten() {
+                let is_checkout = step.get("uses").and_then(Value::as_str).is_some_and(|u| u.starts_with("actions/checkout"));
+                let git_ref = step.get("with").and_then(|w| w.get("ref")).and_then(Value::as_str).unwrap_or("");
+                if is_checkout && (git_ref.contains("pull_request.head") || git_ref.contains("head_ref")) {
+                    push(lines.containing(git_ref, job_line), "pr-target-checkout", Severity::Error,
+                        format!("Job `{}` checks out the PR head under `pull_request_target`, running untrusted code with a privileged token", id));
+                }
+            }
+        }
+    }
+
+    findings.sort_by_key(|f| f.line);
+    Ok(findings)
+}
+
+/// Event names from `on:` (string, list or mapping form)
+fn triggers(root: &Mapping) -> Vec<String> {
+    match root.get("on") {
+        Some(Value::String(event)) => vec![event.clone()],
+        Some(Value::Sequence(events)) => events.iter().filter_map(Value::as_str).map(String::from).collect(),
+        Some(Value::Mapping(events)) => events.keys().filter_map(Value::as_str).map(String::from).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn crons(root: &Mapping) -> Vec<String> {
+    root.get("on")
+        .and_then(|on| on.get("schedule"))
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("cron").and_then(Value::as_str).map(String::from))
+        .collect()
+}
+
+/// Explain why `uses` is not pinned to a commit SHA, if it is not
+fn unpinned(uses: &str) -> Option<String> {
+    if uses.starts_with("./") || uses.starts_with("docker://") {
+        return None;
+    }
+    let (action, git_ref) = uses.split_once('@')?;
+    let owner = action.split('/').next().unwrap_or(action);
+    if FIRST_PARTY_OWNERS.contains(&owner) {
+        return None;
+    }
+    let is_sha = git_ref.len() == 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit());
+    (!is_sha).then(|| format!("`{}` is pinned to `{}`; pin third-party actions to a commit SHA", action, git_ref))
+}
+
+/// Shortest interval between runs of a cron expression, in minutes
+///
+/// Only the minute and hour fields are considered: the gap is the shortest
+/// one between consecutive fire times of a day, wrapping to the next day, so
+/// `*/5 0 * * *` counts as every 5 minutes even though it only runs for an hour.
+pub fn cron_interval_minutes(cron: &str) -> Option<u32> {
+    let fields: Vec<&str> = cron.split_whitespace().collect();
+    if fields.len() != 5 {
+        return None;
+    }
+    let minutes = field_values(fields[0], 59)?;
+    let hours = field_values(fields[1], 23)?;
+    let times: Vec<u32> = hours.iter()
+        .flat_map(|hour| minutes.iter().map(move |minute| hour * 60 + minute))
+        .collect();
+
+    let (first, last) = (*times.first()?, *times.last()?);
+    let wrap = first + 1440 - last;
+    Some(times.windows(2).map(|pair| pair[1] - pair[0]).fold(wrap, u32::min))
+}
+
+/// Sorted values a cron field matches in `0..=max`
+fn field_values(field: &str, max: u32) -> Option<Vec<u32>> {
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (base, step) = match part.split_once('/') {
+            Some((base, step)) => (base, step.parse::<u32>().ok().filter(|&s| s > 0)?),
+            None => (part, 1),
+        };
+        let (start, end) = match base {
+            "*" => (0, max),
+            _ => match base.split_once('-') {
+                Some((a, b)) => (a.parse().ok()?, b.parse().ok()?),
+                // `n/step` runs from n to the end of the range
+                None => {
+                    let start = base.parse().ok()?;
+                    (start, if step > 1 { max } else { start })
+                }
+            },
+        };
+        if start > end || end > max {
+            return None;
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Some(values)
+}
+
+/// Maps YAML keys and values back to 1-based line numbers
+struct LineIndex<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(content: &'a str) -> Self {
+        Self { lines: content.lines().collect() }
+    }
+
+    /// First line at or after `from` declaring `key:`
+    fn key(&self, key: &str, from: usize) -> usize {
+        let pattern = Regex::new(&format!(r#"^\s*['"]?{}['"]?\s*:"#, regex::escape(key))).unwrap();
+        self.find(from, |line| pattern.is_match(line))
+    }
+
+    /// First line at or after `from` containing `needle`
+    fn containing(&self, needle: &str, from: usize) -> usize {
+        self.find(from, |line| line.contains(needle))
+    }
+
+    fn find(&self, from: usize, predicate: impl Fn(&str) -> bool) -> usize {
+        let start = from.saturating_sub(1);
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .find(|(_, line)| !line.trim_start().starts_with('#') && predicate(line))
+            .map(|(i, _)| i + 1)
+            .unwrap_or(from.max(1))
+    }
+}
+
+/// Map lint findings to the shared finding model (SARIF / JUnit output)
+pub fn to_report(findings: &[Finding]) -> gc_report::Report {
+    gc_report::Report::new("workflow-lint", env!("CARGO_PKG_VERSION"))
+        .with_rule("missing-timeout", "Job without timeout-minutes")
+        .with_rule("unpinned-action", "Third-party action not pinned to a commit SHA")
+        .with_rule("broad-permissions", "write-all or missing permissions")
+        .with_rule("pr-target-checkout", "pull_request_target checking out the PR head")
+        .with_rule("missing-concurrency", "Push/PR workflow without a concurrency group")
+        .with_rule("schedule-mode", "Cron more frequent than the schedule mode allows")
+        .with_findings(findings.iter().map(|f| {
+            let severity = match f.severity {
+                Severity::Error => gc_report::Severity::Error,
+                Severity::Warning => gc_report::Severity::Warning,
+                Severity::Info => gc_report::Severity::Note,
+            };
+            gc_report::Finding::new(f.rule, severity, f.message.clone()).at(&f.file, Some(f.line))
+        }))
+}
+
+/// Render findings for `gc validate lint` and `workflow-orchestrator lint`
+///
+/// `json`, `sarif` and `junit` are machine-readable; anything else lists one
+/// finding per line followed by the count.
+pub fn render(findings: &[Finding], format: &str) -> Result<String> {
+    match format {
+        "json" => Ok(serde_json::to_string_pretty(findings)?),
+        "sarif" | "junit" => Ok(to_report(findings).render(format)?),
+        _ => {
+            let mut out = String::new();
+            for f in findings {
+                let icon = match f.severity {
+                    Severity::Error => "❌",
+                    Severity::Warning => "⚠️ ",
+                    Severity::Info => "ℹ️ ",
+                };
+                out.push_str(&format!("{} {}:{} [{}] {}\n", icon, f.file, f.line, f.rule, f.message));
+            }
+            out.push_str(&format!("🔎 {} finding(s)", findings.len()));
+            Ok(out)
+        }
+    }
+}
+
+/// Whether any finding should fail the lint (exit status 1)
+pub fn has_errors(findings: &[Finding]) -> bool {
+    findings.iter().any(|f| f.severity == Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"name: CI
+on:
+  push:
+  schedule:
+    - cron: "*/10 * * * *"
+
+permissions: write-all
+
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: dtolnay/rust-toolchain@stable
+      - uses: Swatinem/rust-cache@23bce251a8cd2ffc3c1075eaa2367cf899916d84
+  call:
+    uses: org/shared/.github/workflows/ci.yml@main
+"#;
+
+    fn rules(findings: &[Finding]) -> Vec<(&'static str, usize)> {
+        findings.iter().map(|f| (f.rule, f.line)).collect()
+    }
+
+    #[test]
+    fn test_findings_with_locations() {
+        let findings = lint_workflow("ci.yml", WORKFLOW, Some(ScheduleMode::Moderate)).unwrap();
+        assert_eq!(
+            rules(&findings),
+            vec![
+                ("missing-concurrency", 2),
+                ("schedule-mode", 5),
+                ("broad-permissions", 7),
+                ("missing-timeout", 10),
+                ("unpinned-action", 14),
+                ("unpinned-action", 17),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let findings = lint_workflow("ci.yml", WORKFLOW, Some(ScheduleMode::Moderate)).unwrap();
+        let text = render(&findings, "terminal").unwrap();
+        assert!(text.lines().any(|l| l.contains("ci.yml:7 [broad-permissions]")));
+        assert!(text.ends_with("6 finding(s)"));
+
+        let json: serde_json::Value = serde_json::from_str(&render(&findings, "json").unwrap()).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 6);
+        assert!(render(&findings, "sarif").unwrap().contains("\"workflow-lint\""));
+        assert!(has_errors(&findings));
+    }
+
+    #[test]
+    fn test_pull_request_target_head_checkout() {
+        let workflow = r#"on: pull_request_target
+permissions:
+  contents: read
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    timeout-minutes: 10
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          ref: ${{ github.event.pull_request.head.sha }}
+"#;
+        let findings = lint_workflow("pr.yml", workflow, None).unwrap();
+        assert_eq!(rules(&findings), vec![("pr-target-checkout", 11)]);
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_cron_interval() {
+        assert_eq!(cron_interval_minutes("*/30 * * * *"), Some(30));
+        assert_eq!(cron_interval_minutes("0 */6 * * *"), Some(360));
+        assert_eq!(cron_interval_minutes("17 8,20 * * *"), Some(720));
+        assert_eq!(cron_interval_minutes("0 9 * * 1"), Some(1440));
+        // Bursts count by their shortest gap, not their daily average
+        assert_eq!(cron_interval_minutes("*/5 0 * * *"), Some(5));
+        assert_eq!(cron_interval_minutes("0,50 9 * * *"), Some(50));
+        assert_eq!(cron_interval_minutes("0 22-23,1 * * *"), Some(60));
+        assert_eq!(cron_interval_minutes("0 25 * * *"), None);
+        assert_eq!(cron_interval_minutes("bad"), None);
+    }
+
+    #[test]
+    fn test_conservative_mode_rejects_any_schedule() {
+        let workflow = "on:\n  schedule:\n    - cron: '0 9 * * 1'\npermissions: {}\njobs: {}\n";
+        let findings = lint_workflow("s.yml", workflow, Some(ScheduleMode::Conservative)).unwrap();
+        assert_eq!(rules(&findings), vec![("schedule-mode", 3)]);
+    }
+}
@@ -2,7 +2,7 @@
 //! 
 //! These tests verify the dispatch logic and strategy selection.
 
-use workflow_orchestrator::dispatcher_core::{DispatcherCore, Strategy, Agent};
+use gc_validator::dispatcher_core::{DispatcherCore, Strategy, Agent};
 use octocrab::Octocrab;
 
 async fn create_dispatcher() -> DispatcherCore {
@@ -3,7 +3,7 @@
 //! These tests verify the decision-making logic of Guardian Agent
 //! with realistic PR scenarios.
 
-use workflow_orchestrator::guardian_core::{Decision, GuardianCore};
+use gc_validator::guardian_core::{Decision, GuardianCore};
 use octocrab::Octocrab;
 
 /// Test helper to create a GuardianCore instance
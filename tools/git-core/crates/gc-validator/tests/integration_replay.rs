@@ -0,0 +1,170 @@
+//! End-to-end tests against recorded GitHub API responses
+//!
+//! Each test replays a cassette from `tests/fixtures/` through a local
+//! `CassetteServer`, so analyzer, validator, guardian and dispatcher flows run
+//! offline. Re-record a fixture against a real repository with:
+//!
+//! ```text
+//! workflow-orchestrator --cassette tests/fixtures/new.json --record analyze
+//! ```
+
+use gc_validator::cassette::CassetteServer;
+use gc_validator::dispatcher_core::{Agent, DispatcherCore, Strategy};
+use gc_validator::fix_rules::KnowledgeBase;
+use gc_validator::github::{GitHubClient, RunFilter};
+use gc_validator::guardian_core::{Decision, GuardianCore};
+use gc_validator::guardian_watch::{self, BranchHealth};
+use gc_validator::heal::{self, HealAction, TransientCause};
+use gc_validator::history::HistoryStore;
+use gc_validator::log_parser::FailureKind;
+use gc_validator::validator::ValidationReport;
+use gc_validator::{analyzer, validator};
+use octocrab::Octocrab;
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+async fn replay(name: &str) -> (CassetteServer, GitHubClient) {
+    let server = CassetteServer::replay_file(fixture(name)).await.unwrap();
+    let client = GitHubClient::new("test-token", "acme/widgets", 4).with_base_url(server.url());
+    (server, client)
+}
+
+#[tokio::test]
+async fn test_analysis_parses_failed_job_logs() {
+    let (server, client) = replay("ci_failure.json").await;
+
+    let runs = client.collect_runs(&RunFilter::default().with_limit(50)).await.unwrap();
+    let mut analyses = client.analyze_runs_parallel(runs).await.unwrap();
+    analyses.sort_by_key(|a| a.run.id);
+
+    let failed = &analyses[1];
+    assert_eq!(failed.run.id, 101);
+    assert!(failed.failures.iter().any(|f| f.kind == FailureKind::TestFailure
+        && f.test.as_deref() == Some("parser::tests::rejects_garbage")
+        && f.job.as_deref() == Some("test")));
+
+    let errors = analyzer::group_errors(&analyses);
+    assert!(errors.iter().all(|e| e.run_ids == vec![101]));
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+}
+
+#[tokio::test]
+async fn test_validation_report_from_recorded_run() {
+    let (server, client) = replay("ci_failure.json").await;
+
+    let runs = client.collect_runs(&RunFilter::default().with_limit(50)).await.unwrap();
+    let failed_run = runs.into_iter().filter(|r| r.id == 101).collect();
+    let analyses = client.analyze_runs_parallel(failed_run).await.unwrap();
+    let report = ValidationReport::from_analysis_with(&analyses[0], &KnowledgeBase::shipped().unwrap());
+
+    assert_eq!(report.metrics.failed_jobs, 1);
+    assert_eq!(report.metrics.parallel_jobs, 2);
+    assert_eq!(report.errors[0].step.as_deref(), Some("Run tests"));
+    assert!(report.recommendations.iter().any(|r| r.rule_id.as_deref() == Some("step-test")));
+    let critical_path = report.critical_path.as_ref().unwrap();
+    assert_eq!(critical_path.critical_path, vec!["test"]);
+
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+
+    // Full command flows print their report and must not hit unrecorded endpoints
+    let (server, client) = replay("ci_failure.json").await;
+    analyzer::run_analysis(&client, None, &RunFilter::default(), &["errors".to_string()], false, "json").await.unwrap();
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+
+    let (server, client) = replay("ci_failure.json").await;
+    validator::run_validation(&client, None, &RunFilter::default(), "101", None, false, "json").await.unwrap();
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+}
+
+#[tokio::test]
+async fn test_guardian_watch_verifies_green_merge() {
+    let (server, client) = replay("guardian_watch.json").await;
+
+    let outcomes = guardian_watch::run_watch(&client, false).await.unwrap();
+
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].pr_number, 7);
+    assert_eq!(outcomes[0].health, BranchHealth::Green);
+    let requests = server.requests();
+    assert!(requests.contains(&"DELETE /repos/acme/widgets/issues/7/labels/guardian:auto-merged".to_string()));
+    assert!(requests.contains(&"POST /repos/acme/widgets/issues/7/labels".to_string()));
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+}
+
+#[tokio::test]
+async fn test_guardian_watch_retry_reuses_open_revert_pr() {
+    let (server, client) = replay("guardian_watch_retry.json").await;
+
+    let outcomes = guardian_watch::run_watch(&client, false).await.unwrap();
+
+    assert!(matches!(outcomes[0].health, BranchHealth::Broken { .. }));
+    assert_eq!(outcomes[0].revert_pr, Some(12));
+    let requests = server.requests();
+    assert!(requests.contains(&"GET /repos/acme/widgets/issues?labels=guardian%3Aauto-merged&state=closed&per_page=100&page=1".to_string()));
+    assert!(!requests.contains(&"POST /repos/acme/widgets/pulls".to_string()));
+    assert!(requests.contains(&"POST /repos/acme/widgets/issues/7/labels".to_string()));
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+}
+
+#[tokio::test]
+async fn test_heal_reruns_only_transient_failures() {
+    let (server, client) = replay("heal_runs.json").await;
+    let store = HistoryStore::open_in_memory().unwrap();
+
+    let decisions = heal::run_heal(&client, &RunFilter::default(), Some(&store), None, 2, false, "json").await.unwrap();
+
+    let actions: Vec<(u64, HealAction)> = decisions.iter().map(|d| (d.run_id, d.action)).collect();
+    assert_eq!(actions, vec![
+        (300, HealAction::Rerun),
+        (299, HealAction::Superseded),
+        // Known flaky test, but attempt 3 already used both re-runs
+        (310, HealAction::BudgetExhausted),
+        (320, HealAction::NotTransient),
+    ]);
+    assert_eq!(decisions[0].jobs[0].cause, Some(TransientCause::NetworkTimeout));
+    assert_eq!(decisions[2].jobs[0].cause, Some(TransientCause::FlakyTest));
+    assert_eq!(store.heal_attempts(300).unwrap(), 1);
+    assert!(server.requests().contains(&"POST /repos/acme/widgets/actions/runs/300/rerun-failed-jobs".to_string()));
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+}
+
+#[tokio::test]
+async fn test_dispatcher_assigns_unassigned_issues() {
+    let server = CassetteServer::replay_file(fixture("dispatcher_issues.json")).await.unwrap();
+    let github = Octocrab::builder().base_uri(server.url()).unwrap().build().unwrap();
+    let dispatcher = DispatcherCore::new(github, "acme".to_string(), "widgets".to_string());
+
+    let assignments = dispatcher
+        .dispatch_issues(Strategy::CopilotOnly, 10, "ai-agent".to_string(), true)
+        .await
+        .unwrap();
+
+    // #13 already has an agent label, #14 has an assignee
+    let numbers: Vec<u64> = assignments.iter().map(|a| a.issue_number).collect();
+    assert_eq!(numbers, vec![11, 12]);
+    assert!(assignments.iter().all(|a| a.agent == Agent::Copilot));
+    assert!(assignments[1].risk_score > assignments[0].risk_score);
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+}
+
+#[tokio::test]
+async fn test_guardian_reads_codeowners_from_base_branch() {
+    let server = CassetteServer::replay_file(fixture("guardian_codeowners.json")).await.unwrap();
+    let guardian = GuardianCore::from_token_at(None, server.url(), "acme", "widgets")
+        .unwrap()
+        .with_repo_config("missing-risk-map.json", "missing-policy.yml")
+        .unwrap();
+
+    let report = guardian.evaluate_pr_report(7, true).await.unwrap();
+
+    // The PR rewrites CODEOWNERS, but the base branch still requires @bob for src/
+    assert!(matches!(report.decision, Decision::Escalate { .. }), "{:?}", report.decision);
+    assert_eq!(report.missing_owner_approvals.len(), 1);
+    assert_eq!(report.missing_owner_approvals[0].owners, vec!["@bob"]);
+    let requests = server.requests();
+    assert!(requests.contains(&"GET /repos/acme/widgets/pulls/7/files?per_page=100".to_string()));
+    assert!(requests.contains(&"GET /repos/acme/widgets/contents/CODEOWNERS?ref=main".to_string()));
+    assert!(server.unmatched().is_empty(), "unexpected requests: {:?}", server.unmatched());
+}